@@ -15,7 +15,7 @@ use axum::{
         WebSocket
     },
 };
-use axum::routing::delete;
+use axum::routing::{delete, put};
 use tower_http::services::ServeDir;
 
 
@@ -27,6 +27,7 @@ use crate::presentation::controllers::api::v1::index_data_query_controller::Inde
 use crate::presentation::controllers::api::v1::ws_controller::WsController;
 use crate::presentation::controllers::api::v1::index_controller::IndexController;
 use crate::presentation::controllers::api::v1::home_controller::HomeController;
+use crate::presentation::controllers::api::v1::job_controller::JobController;
 use crate::state::AppState;
 
 pub async fn create_app(
@@ -47,12 +48,26 @@ pub async fn create_app(
         .route("/api/data-sources", get(DataSourceController::index))
         .route("/api/data-sources", post(DataSourceController::store))
         .route("/api/data-sources/test", post(DataSourceController::test))
+        .route("/api/data-sources/connections", get(DataSourceController::connections))
+        .route("/api/data-sources/connectors", get(DataSourceController::connectors))
+        .route("/api/data-sources/{:id}/introspect", get(DataSourceController::introspect))
         .route("/api/data-sources/{:id}", delete(DataSourceController::destroy))
         .route("/api/data-sources/{:id}", get(DataSourceController::show))
         .route("/api/index-data-queries", get(IndexDataQueryController::index))
         .route("/api/index-data-queries/test", get(IndexDataQueryController::test))
+        .route("/api/index-data-queries/preview", get(IndexDataQueryController::preview))
         .route("/api/index-data-queries", post(IndexDataQueryController::store))
         .route("/api/index-data-queries/insert-data", post(IndexDataQueryController::insert_data))
+        .route("/api/index-data-queries/{:id}/reset-cursor", post(IndexDataQueryController::reset_cursor))
+        .route("/api/index-data-queries/{:id}/reconcile-deletions", post(IndexDataQueryController::reconcile_deletions))
+        .route("/api/index-data-queries/{:id}/schedule", put(IndexDataQueryController::update_schedule))
+        .route("/api/index-data-queries/{:id}/reindex", post(IndexDataQueryController::reindex))
+        .route("/api/index-data-queries/{:id}/transformation", put(IndexDataQueryController::update_transformation))
+        .route("/api/index-data-queries/{:id}/notify-channel", put(IndexDataQueryController::update_notify_channel))
+        .route("/api/index-data-queries/{:id}/child-queries", put(IndexDataQueryController::update_child_queries))
+        .route("/api/index-data-queries/{:id}/notify-trigger", get(IndexDataQueryController::notify_trigger))
+        .route("/api/index-data-queries/{:id}/runs", get(IndexDataQueryController::runs))
+        .route("/api/jobs/{:id}", get(JobController::show))
         .route("/ws", get(WsController::websocket_handler))
         // Static resources for SvelteKit (JS, CSS, images)
         .nest_service("/_app", ServeDir::new(PathBuf::from("static/_app")))
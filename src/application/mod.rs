@@ -1 +1,2 @@
 pub mod use_cases;
+pub mod workers;
@@ -0,0 +1,28 @@
+use anyhow::Error;
+use crate::domain::data_source::services::data_source_connector::SourceObject;
+use crate::domain::data_source::services::query_executor::QueryExecutor;
+use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
+
+/// Таблицы (файлы, эндпоинты) источника и их колонки — для выбора полей при составлении запроса
+pub struct IntrospectDataSourceUseCase<R: DataSourceRepositoryTrait> {
+    data_source_repository: R,
+    query_executor: QueryExecutor,
+}
+
+impl<R: DataSourceRepositoryTrait> IntrospectDataSourceUseCase<R> {
+    pub fn new(data_source_repository: R, query_executor: QueryExecutor) -> Self {
+        Self {
+            data_source_repository,
+            query_executor,
+        }
+    }
+
+    pub async fn execute(&self, data_source_id: u32) -> Result<Vec<SourceObject>, Error> {
+        let data_source = self.data_source_repository
+            .get(data_source_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Data source {} not found", data_source_id))?;
+
+        self.query_executor.introspect(&data_source).await.map_err(Error::msg)
+    }
+}
@@ -1,2 +1,4 @@
 pub mod test_data_sources_use_case;
-pub mod get_data_sources_use_case;
\ No newline at end of file
+pub mod get_data_sources_use_case;
+pub mod introspect_data_source_use_case;
+pub mod rotate_credentials_key_use_case;
@@ -0,0 +1,75 @@
+use anyhow::Error;
+use sqlx::Row;
+use crate::database::Database;
+use crate::domain::data_source::entities::http_source_options::HttpSourceOptions;
+use crate::domain::data_source::services::credential_cipher::CredentialCipher;
+
+/// Перешифровывает пароли источников и значения заголовков из `http_options` в `data_sources`. Все строки меняются в одной транзакции:
+/// если хоть одно значение не расшифровалось, в базе остаются прежние.
+pub struct RotateCredentialsKeyUseCase {
+    db: Database,
+}
+
+impl RotateCredentialsKeyUseCase {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Расшифровывает пароли текущим ключом и шифрует новым; возвращает число изменённых строк
+    pub async fn execute(&self, current: &CredentialCipher, next: &CredentialCipher) -> Result<u64, Error> {
+        self.reencrypt(current, next, false).await
+    }
+
+    /// Шифрует пароли, сохранённые открытым текстом до появления шифрования
+    pub async fn encrypt_plaintext(&self, cipher: &CredentialCipher) -> Result<u64, Error> {
+        self.reencrypt(cipher, cipher, true).await
+    }
+
+    async fn reencrypt(&self, current: &CredentialCipher, next: &CredentialCipher, only_plaintext: bool) -> Result<u64, Error> {
+        let mut transaction = self.db.pool.begin().await?;
+        let rows = sqlx::query("SELECT id, password, http_options FROM data_sources")
+            .fetch_all(&mut *transaction)
+            .await?;
+
+        let mut updated = 0;
+        for row in rows {
+            let id: i64 = row.get("id");
+            let stored: String = row.get("password");
+            let http_options: Option<String> = row.get("http_options");
+
+            let password = if stored.is_empty() || (only_plaintext && CredentialCipher::is_encrypted(&stored)) {
+                None
+            } else {
+                let plaintext = current
+                    .decrypt(&stored)
+                    .map_err(|e| anyhow::anyhow!("Data source {}: {}", id, e))?;
+                Some(next.encrypt(&plaintext).map_err(Error::msg)?)
+            };
+
+            let http_options = match http_options.filter(|http_options| !http_options.is_empty()) {
+                Some(http_options) => {
+                    let options: HttpSourceOptions = serde_json::from_str(&http_options)
+                        .map_err(|e| anyhow::anyhow!("Data source {}: invalid http_options: {}", id, e))?;
+                    let options = if only_plaintext { options.encrypted(next) } else { options.rotated(current, next) }
+                        .map_err(|e| anyhow::anyhow!("Data source {}: {}", id, e))?;
+                    Some(serde_json::to_string(&options)?).filter(|rotated| *rotated != http_options)
+                }
+                None => None,
+            };
+
+            if password.is_none() && http_options.is_none() {
+                continue;
+            }
+            sqlx::query("UPDATE data_sources SET password = COALESCE(?, password), http_options = COALESCE(?, http_options) WHERE id = ?")
+                .bind(password)
+                .bind(http_options)
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+            updated += 1;
+        }
+
+        transaction.commit().await?;
+        Ok(updated)
+    }
+}
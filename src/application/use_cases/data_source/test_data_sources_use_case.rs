@@ -1,18 +1,20 @@
 use anyhow::Error;
 use colored::Colorize;
 use sqlx::{query_as, Pool, Sqlite};
-use sqlx::postgres::PgPoolOptions;
+use crate::domain::data_source::services::query_executor::QueryExecutor;
 use crate::models::data_source::DataSource;
 use crate::requests::data_source::test_data_source_request::TestDataSourceRequest;
 
 pub struct TestDataSourcesUseCase{
     pub pool: Pool<Sqlite>,
+    pub query_executor: QueryExecutor,
 }
 
 impl TestDataSourcesUseCase {
-    pub async  fn new(pool: Pool<Sqlite>) -> Self {
+    pub async  fn new(pool: Pool<Sqlite>, query_executor: QueryExecutor) -> Self {
         Self{
             pool,
+            query_executor,
         }
     }
 
@@ -20,7 +22,7 @@ impl TestDataSourcesUseCase {
         &self,
         payload: TestDataSourceRequest,
     ) -> Result<String, Error> {
-        let query = "SELECT name, host, database, username, password, port, database_path, database_name, database_type FROM data_sources WHERE id = ?";
+        let query = "SELECT name, host, database, username, password, port, database_path, database_name, database_type, file_options, http_options FROM data_sources WHERE id = ?";
 
         let database_params = match query_as::<_, DataSource>(query)
             .bind(payload.id)
@@ -33,54 +35,16 @@ impl TestDataSourcesUseCase {
             }
         };
 
-        // Test the connection based on database type
-        match database_params.database_type.as_str() {
-            "sqlite" => {
-                // For SQLite, try to establish a connection to the database file
-                let connection_string = format!("sqlite:{}", database_params.database_path);
-                match sqlx::SqlitePool::connect(&connection_string).await {
-                    Ok(_) => {
-                        println!("{} {}", "✅ SQLite connection test successful: ".color("Green"), database_params.name);
-                        return Ok("SQLite connection test successful".to_string());
-                    },
-                    Err(e) => {
-                        eprintln!("{} {}", "❌ Failed to connect to SQLite database: ".color("Red"), e);
-                        return Err(anyhow::anyhow!("Failed to connect to SQLite database: {}", e));
-                    }
-                }
+        // Проверку выполняет коннектор типа источника, тот же, что читает его при синхронизации
+        let data_source = database_params.to_entity();
+        match self.query_executor.test_connection(&data_source).await {
+            Ok(_) => {
+                println!("{} {} ({})", "✅ Connection test successful: ".color("Green"), database_params.name, database_params.database_type);
+                Ok(format!("{} connection test successful", database_params.database_type))
             },
-            "mysql" => {
-                // Since MySQL feature is not enabled in sqlx, we can't directly test the connection
-                println!("{} {}", "⚠️ MySQL connection test: ".color("Yellow"),
-                         "MySQL connection testing is not directly supported. Please ensure MySQL features are enabled in sqlx.");
-                return Ok("MySQL connection test: Feature not enabled. Connection parameters look valid.".to_string());
-            },
-            "postgresql" => {
-                // For PostgreSQL, try to establish a connection to the database
-                let connection_string = format!(
-                    "postgres://{}:{}@{}:{}/{}",
-                    database_params.username,
-                    database_params.password,
-                    database_params.host,
-                    database_params.port,
-                    database_params.database_name
-                );
-                match PgPoolOptions::new()
-                    .max_connections(5)
-                    .connect(&connection_string)
-                    .await {
-                    Ok(_) => {
-                        println!("{} {}", "✅ PostgreSQL connection test successful: ".color("Green"), database_params.name);
-                        return Ok("PostgreSQL connection test successful".to_string());
-                    },
-                    Err(e) => {
-                        eprintln!("{} {}", "❌ Failed to connect to PostgreSQL database: ".color("Red"), e);
-                        return Err(anyhow::anyhow!("Failed to connect to PostgreSQL database: {}", e));
-                    }
-                }
-            },
-            _ => {
-                return Err(anyhow::anyhow!("Unsupported database type: {}", database_params.database_type));
+            Err(e) => {
+                eprintln!("{} {}", "❌ Connection test failed: ".color("Red"), e);
+                Err(anyhow::anyhow!("Connection test failed: {}", e))
             }
         }
     }
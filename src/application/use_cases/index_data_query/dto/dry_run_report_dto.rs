@@ -0,0 +1,55 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Сколько примеров документов показывать в каждой группе
+const SAMPLE_SIZE: usize = 5;
+
+/// Итог пробного прогона синхронизации: что было бы добавлено, изменено и осталось бы сиротами
+#[derive(Serialize, Debug, Clone)]
+pub struct DryRunReportDto {
+    pub index_uid: String,
+    pub rows_read: u64,
+    pub new: DryRunGroupDto,
+    pub changed: DryRunGroupDto,
+    pub unchanged: DryRunGroupDto,
+    pub orphaned: DryRunGroupDto,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DryRunGroupDto {
+    pub count: u64,
+    pub samples: Vec<Value>,
+}
+
+impl DryRunReportDto {
+    pub fn new(index_uid: String) -> Self {
+        Self {
+            index_uid,
+            rows_read: 0,
+            new: DryRunGroupDto::default(),
+            changed: DryRunGroupDto::default(),
+            unchanged: DryRunGroupDto::default(),
+            orphaned: DryRunGroupDto::default(),
+        }
+    }
+}
+
+impl DryRunGroupDto {
+    /// Учитывает документ; в примеры попадают только первые SAMPLE_SIZE
+    pub fn push(&mut self, document: Value) {
+        self.count += 1;
+        if self.samples.len() < SAMPLE_SIZE {
+            self.samples.push(document);
+        }
+    }
+
+    /// Нужны ли группе ещё примеры; если нет, документ достаточно учесть через `tally`
+    pub fn needs_sample(&self) -> bool {
+        self.samples.len() < SAMPLE_SIZE
+    }
+
+    /// Учитывает документ без примера
+    pub fn tally(&mut self) {
+        self.count += 1;
+    }
+}
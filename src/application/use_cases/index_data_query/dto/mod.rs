@@ -1 +1,5 @@
-mod test_index_data_query_use_case_response_dto;
\ No newline at end of file
+mod test_index_data_query_use_case_response_dto;
+pub mod sync_report_dto;
+pub mod dry_run_report_dto;
+
+pub mod sync_runs_dto;
\ No newline at end of file
@@ -0,0 +1,85 @@
+use anyhow::Error;
+use serde::Serialize;
+use crate::domain::data_source::entities::sync_run::{PartialSyncError, SyncRunStats};
+use crate::domain::data_source::services::deletion_reconciler::DeletionReport;
+
+/// Итог синхронизации запроса с индексом Meilisearch
+#[derive(Serialize, Debug, Clone)]
+pub struct SyncReportDto {
+    pub index_uid: String,
+    pub rows_read: u64,
+    /// Документы в отправленных пачках; после преобразований и при ошибке может отличаться от rows_read
+    pub documents_sent: u64,
+    pub batches_sent: u32,
+    /// Объём отправленных NDJSON-пачек
+    pub bytes_sent: u64,
+    /// Сколько отправка ждала разгрузки очереди задач Meilisearch
+    pub throttled_ms: u64,
+    pub task_uids: Vec<u32>,
+    /// Выборка ограничена отметкой предыдущего запуска
+    pub incremental: bool,
+    /// Новая отметка курсора после синхронизации
+    pub cursor_value: Option<String>,
+    /// Результат сверки удалений, если она включена для запроса
+    pub deletions: Option<DeletionReport>,
+}
+
+impl SyncReportDto {
+    pub fn new(index_uid: String) -> Self {
+        Self {
+            index_uid,
+            rows_read: 0,
+            documents_sent: 0,
+            batches_sent: 0,
+            bytes_sent: 0,
+            throttled_ms: 0,
+            task_uids: Vec::new(),
+            incremental: false,
+            cursor_value: None,
+            deletions: None,
+        }
+    }
+
+    /// Итог для истории запусков
+    pub fn stats(&self) -> SyncRunStats {
+        SyncRunStats {
+            rows_read: self.rows_read,
+            documents_sent: self.documents_sent,
+            task_uids: self.task_uids.clone(),
+            throttled_ms: self.throttled_ms,
+        }
+    }
+
+    /// Ошибка, которая сохраняет уже сделанную часть работы (см. PartialSyncError)
+    pub fn failure(&self, error: Error) -> Error {
+        if error.is::<PartialSyncError>() {
+            return error;
+        }
+        Error::new(PartialSyncError {
+            stats: self.stats(),
+            message: format!("{:#}", error),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failure_keeps_the_work_done_so_far() {
+        let mut report = SyncReportDto::new("products".to_string());
+        report.rows_read = 1_500;
+        report.documents_sent = 1_000;
+        report.task_uids = vec![7];
+
+        let error = report.failure(anyhow::anyhow!("connection reset"));
+        report.documents_sent = 2_000;
+        let error = report.failure(error);
+
+        let partial = error.downcast_ref::<PartialSyncError>().unwrap();
+        assert_eq!((partial.stats.rows_read, partial.stats.documents_sent), (1_500, 1_000));
+        assert_eq!(partial.stats.task_uids, vec![7]);
+        assert_eq!(error.to_string(), "connection reset");
+    }
+}
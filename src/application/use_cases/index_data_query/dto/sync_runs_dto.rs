@@ -0,0 +1,11 @@
+use serde::Serialize;
+use crate::domain::data_source::entities::sync_run::SyncRun;
+
+/// История синхронизаций запроса
+#[derive(Serialize, Debug)]
+pub struct SyncRunsDto {
+    /// Время завершения последнего успешного запуска
+    pub last_succeeded_at: Option<String>,
+    /// Последние запуски, новые первыми
+    pub runs: Vec<SyncRun>,
+}
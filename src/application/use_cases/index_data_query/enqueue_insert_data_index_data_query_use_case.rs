@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use anyhow::Error;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::queues::application::queue_service::JobService;
+use crate::queues::domain::entities::job::Job;
+use crate::queues::domain::value_objects::queue_name::QueueName;
+use crate::requests::index_data_query::insert_data_index_data_query_request::InsertDataIndexDataQueryRequest;
+
+/// Ставит синхронизацию запроса в очередь; выполняет её SyncWorker
+pub struct EnqueueInsertDataIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait> {
+    index_data_query_repository: R,
+    job_service: Arc<JobService>,
+}
+
+impl <R: IndexDataQueryRepositoryTrait> EnqueueInsertDataIndexDataQueryUseCase <R> {
+    pub fn new(index_data_query_repository: R, job_service: Arc<JobService>) -> Self {
+        Self {
+            index_data_query_repository,
+            job_service,
+        }
+    }
+
+    /// Обычная синхронизация (очередь IndexDocuments). Возвращает id созданной задачи
+    pub async fn execute(&self, payload: &InsertDataIndexDataQueryRequest) -> Result<i32, Error> {
+        self.enqueue(payload, QueueName::IndexDocuments).await
+    }
+
+    /// Полная переиндексация через теневой индекс (очередь ReindexAll)
+    pub async fn execute_reindex(&self, payload: &InsertDataIndexDataQueryRequest) -> Result<i32, Error> {
+        self.enqueue(payload, QueueName::ReindexAll).await
+    }
+
+    async fn enqueue(&self, payload: &InsertDataIndexDataQueryRequest, queue_name: QueueName) -> Result<i32, Error> {
+        if self.index_data_query_repository.get(payload.id).await.is_none() {
+            return Err(anyhow::anyhow!("Index data query {} not found", payload.id));
+        }
+
+        let job = Job::new(queue_name, payload.serialise()?);
+        let job = self.job_service.enqueue(job).await?;
+
+        job.id.ok_or_else(|| anyhow::anyhow!("Job was stored without an id"))
+    }
+}
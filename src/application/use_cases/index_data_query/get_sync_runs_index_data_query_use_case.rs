@@ -0,0 +1,38 @@
+use anyhow::Error;
+use crate::application::use_cases::index_data_query::dto::sync_runs_dto::SyncRunsDto;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::domain::repository::sync_run_repository_trait::SyncRunRepositoryTrait;
+use crate::presentation::requests::index_data_query::get_sync_runs_index_data_query_request::GetSyncRunsIndexDataQueryRequest;
+
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 500;
+
+/// История запусков синхронизации запроса и время последнего успешного обновления индекса
+pub struct GetSyncRunsIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait, R2: SyncRunRepositoryTrait> {
+    index_data_query_repository: R,
+    sync_run_repository: R2,
+}
+
+impl <R: IndexDataQueryRepositoryTrait, R2: SyncRunRepositoryTrait> GetSyncRunsIndexDataQueryUseCase <R, R2> {
+    pub fn new(index_data_query_repository: R, sync_run_repository: R2) -> Self {
+        Self {
+            index_data_query_repository,
+            sync_run_repository,
+        }
+    }
+
+    pub async fn execute(&self, id: u32, payload: &GetSyncRunsIndexDataQueryRequest) -> Result<SyncRunsDto, Error> {
+        if self.index_data_query_repository.get(id).await.is_none() {
+            return Err(anyhow::anyhow!("Index data query {} not found", id));
+        }
+
+        let limit = payload.limit.filter(|limit| *limit > 0).unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        let runs = self.sync_run_repository.list(id, limit).await?;
+        let last_succeeded_at = self.sync_run_repository
+            .last_succeeded(id)
+            .await?
+            .and_then(|run| run.finished_at);
+
+        Ok(SyncRunsDto { last_succeeded_at, runs })
+    }
+}
@@ -1,104 +1,420 @@
+use std::collections::HashMap;
+use std::time::Duration;
 use anyhow::Error;
-use chrono::Utc;
 use meilisearch_sdk::client::Client;
+use meilisearch_sdk::errors::{Error as MeilisearchSdkError, ErrorCode};
+use meilisearch_sdk::indexes::Index;
+use meilisearch_sdk::task_info::TaskInfo;
 use meilisearch_sdk::tasks::Task;
-use serde_json::json;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use crate::application::use_cases::index_data_query::dto::dry_run_report_dto::DryRunReportDto;
+use crate::application::use_cases::index_data_query::dto::sync_report_dto::SyncReportDto;
 use crate::domain::data_source::entities::data_source::DataSource;
 use crate::domain::data_source::entities::index_data_query::IndexDataQuery;
 use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
-use crate::presentation::requests::index_data_query::store_index_data_query_request::StoreIndexDataQueryRequest;
 use crate::requests::index_data_query::insert_data_index_data_query_request::InsertDataIndexDataQueryRequest;
-use crate::domain::data_source::services::query_executor::QueryExecutor;
+use crate::domain::data_source::services::query_executor::{CursorWindow, QueryExecutor};
+use crate::domain::data_source::services::deletion_reconciler::DeletionReconciler;
+use crate::domain::data_source::services::child_query_assembler::ChildQueryAssembler;
+use crate::domain::data_source::services::ndjson_batch::NdjsonBatch;
+use crate::domain::data_source::services::task_throttle::{TaskThrottle, TaskThrottleSettings};
+use crate::domain::data_source::services::document_transformer::DocumentTransformer;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::index_document_reader::IndexDocumentReader;
+use crate::domain::data_source::services::named_params::{NamedParams, QueryParams};
 use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
 
+/// Сколько ждать завершения одной задачи Meilisearch
+const TASK_TIMEOUT: Duration = Duration::from_secs(300);
+
 pub struct InsertDataIndexDataQueryRequestUseCase <R: IndexDataQueryRepositoryTrait, R2: DataSourceRepositoryTrait> {
     index_data_query_repository:R,
     data_source_repository:R2,
     meilisearch_client: Client,
+    default_batch_size: u32,
+    /// Порог размера NDJSON-пачки в байтах
+    flush_bytes: usize,
+    throttle: TaskThrottleSettings,
+    query_executor: QueryExecutor,
 }
 
 impl <R: IndexDataQueryRepositoryTrait, R2: DataSourceRepositoryTrait> InsertDataIndexDataQueryRequestUseCase <R, R2> {
-    pub async  fn new(index_data_query_repository: R, data_source_repository: R2, meilisearch_client: Client) -> Self {
-        Self { 
+    pub async  fn new(
+        index_data_query_repository: R,
+        data_source_repository: R2,
+        meilisearch_client: Client,
+        default_batch_size: u32,
+        flush_bytes: usize,
+        throttle: TaskThrottleSettings,
+        query_executor: QueryExecutor,
+    ) -> Self {
+        Self {
             index_data_query_repository,
             data_source_repository,
             meilisearch_client,
+            default_batch_size,
+            flush_bytes,
+            throttle,
+            query_executor,
         }
     }
+
+    /// Синхронизация: читает результат запроса пачками по первичному ключу
+    /// и отправляет каждую пачку отдельной задачей add_documents.
+    /// Если у запроса задана курсорная колонка, читаются только строки новее сохранённой отметки.
     pub async fn execute(
         &self,
         payload: &InsertDataIndexDataQueryRequest,
-    ) -> Result<String, Error> {
-        
-        // нужно работать с батчами
-        // измерить нужное количество батчей и через цикл получать из бд и отправлять
-        
-        let query_executor = QueryExecutor::new();
-        let index_data_query = Self::get_index_data_query(&self, payload).await;
-        let data_source_id = index_data_query.data_source_id;
-        let index_uid = index_data_query.index_uid;
-        let query = index_data_query.query;
-        let limit = 3_000;
-
-        let data_source = self.data_source_repository.get(data_source_id).await.unwrap();
-
-        let result = query_executor.execute_query(&data_source, &query, limit).await;
-
-        let document = match result {
-            Ok(document) => {
-                // Преобразуем в чистый JSON
-                document
-            },
-            Err(e) => {
-                println!("Error: {:?}", e);
-                return Err(anyhow::anyhow!("Error: {:?}", e));
-            },
+    ) -> Result<SyncReportDto, Error> {
+        let index_data_query = self.get_index_data_query(payload).await?;
+        let data_source = self.get_data_source(&index_data_query).await?;
+        let batch_size = self.batch_size(payload);
+        let params = NamedParams::merge(&index_data_query.params, payload.params.as_ref());
+
+        let mut report = self
+            .sync_into(
+                &data_source,
+                &index_data_query,
+                &index_data_query.index_uid,
+                &params,
+                index_data_query.cursor_value.clone(),
+                batch_size,
+            )
+            .await?;
+
+        // Отметку двигаем только после того, как все пачки успешно проиндексированы
+        if let Some(cursor_value) = &report.cursor_value {
+            self.index_data_query_repository
+                .update_cursor(index_data_query.id, Some(cursor_value.clone()))
+                .await
+                .map_err(|e| report.failure(e))?;
+        }
+
+        if index_data_query.delete_orphans {
+            let key = DocumentKey::for_query(&index_data_query).map_err(|e| report.failure(Error::msg(e)))?;
+            let deletions = DeletionReconciler::new(self.meilisearch_client.clone())
+                .reconcile(&data_source, &index_data_query, &key, &self.query_executor(&index_data_query, &params), batch_size)
+                .await
+                .map_err(|e| report.failure(anyhow::anyhow!("Deletion reconciliation failed: {}", e)))?;
+            report.deletions = Some(deletions);
+        }
+
+        Ok(report)
+    }
+
+    /// Полная выгрузка результата запроса в произвольный индекс (например, теневой при переиндексации).
+    /// Отметка курсора не сохраняется: её значение возвращается в отчёте.
+    pub async fn execute_full_into(
+        &self,
+        payload: &InsertDataIndexDataQueryRequest,
+        target_index_uid: &str,
+    ) -> Result<SyncReportDto, Error> {
+        let index_data_query = self.get_index_data_query(payload).await?;
+        let data_source = self.get_data_source(&index_data_query).await?;
+        let batch_size = self.batch_size(payload);
+        let params = NamedParams::merge(&index_data_query.params, payload.params.as_ref());
+
+        self.sync_into(&data_source, &index_data_query, target_index_uid, &params, None, batch_size).await
+    }
+
+    /// Отправляет строки запроса в индекс `target_index_uid` и дожидается всех задач.
+    /// Строки читаются потоком и уходят NDJSON-пачками по порогу строк (`batch_size`) или байтов,
+    /// так что память не зависит от размера таблицы.
+    /// `params` — значения параметров `:name`, `after` — нижняя граница курсора (None — читать с начала).
+    /// Ошибка несёт то, что успело уйти в индекс (SyncReportDto::failure).
+    async fn sync_into(
+        &self,
+        data_source: &DataSource,
+        index_data_query: &IndexDataQuery,
+        target_index_uid: &str,
+        params: &QueryParams,
+        after: Option<String>,
+        batch_size: u32,
+    ) -> Result<SyncReportDto, Error> {
+        let mut report = SyncReportDto::new(target_index_uid.to_string());
+        match self.send_into(data_source, index_data_query, params, after, batch_size, &mut report).await {
+            Ok(()) => Ok(report),
+            Err(e) => Err(report.failure(e)),
+        }
+    }
+
+    async fn send_into(
+        &self,
+        data_source: &DataSource,
+        index_data_query: &IndexDataQuery,
+        params: &QueryParams,
+        after: Option<String>,
+        batch_size: u32,
+        report: &mut SyncReportDto,
+    ) -> Result<(), Error> {
+        let target_index_uid = report.index_uid.clone();
+        let query_executor = self.query_executor(index_data_query, params);
+        let key = DocumentKey::for_query(index_data_query).map_err(Error::msg)?;
+        self.ensure_primary_key(&target_index_uid, key.field()).await?;
+
+        let index = self.meilisearch_client.index(&target_index_uid);
+        let mut tasks: Vec<TaskInfo> = Vec::new();
+
+        let cursor = match &index_data_query.cursor_column {
+            Some(cursor_column) => {
+                let bounds = query_executor
+                    .fetch_cursor_bounds(data_source, &index_data_query.query, cursor_column)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Error: {}", e))?;
+
+                match bounds {
+                    Some((up_to, column_type)) => Some(CursorWindow {
+                        column: cursor_column.clone(),
+                        column_type,
+                        after,
+                        up_to,
+                    }),
+                    // Источник пуст: отправлять нечего, отметку не трогаем
+                    None => return Ok(()),
+                }
+            }
+            None => None,
         };
+        report.incremental = cursor.as_ref().is_some_and(|window| window.after.is_some());
 
-        let meiliserach_insert_result = match self.meilisearch_client
-            .index(&index_uid.to_string())
-            .add_documents(&document, Some("id"))
-            .await
-        {
-            Ok(task_info) => {
-                // Ждем завершения задачи
-                let final_task = self.meilisearch_client
-                    .wait_for_task(task_info, None, None)
-                    .await?;
-
-                println!("Final task result: {:#?}", final_task);
-
-                match final_task {
-                    Task::Succeeded { content, .. } => {
-                        println!("✅ Documents added successfully!");
-                        println!("Task details: {:?}", content);
-                        Ok("Success - Data inserted successfully".to_string())
-                    },
-                    Task::Failed { content, .. } => {
-                        println!("❌ Task failed: {:?}", content);
-                        Err(anyhow::anyhow!("Meilisearch task failed: {:?}", content))
-                    },
-                    _ => {
-                        println!("⚠️ Task still in progress");
-                        Ok("Success - Data insertion in progress".to_string())
-                    },
+        // Канал на одну пачку строк: чтение из источника не обгоняет отправку
+        let (sender, mut receiver) = mpsc::channel::<Value>(batch_size as usize);
+        let read = query_executor.stream_query(data_source, &index_data_query.query, &key, cursor.as_ref(), batch_size, sender);
+        let mut throttle = TaskThrottle::new(self.meilisearch_client.clone(), &target_index_uid, self.throttle);
+        let send = async {
+            let mut ndjson = NdjsonBatch::new(batch_size as usize, self.flush_bytes);
+            let mut rows = Vec::with_capacity(batch_size as usize);
+            let mut closed = false;
+            while !closed {
+                while rows.len() < batch_size as usize {
+                    match receiver.recv().await {
+                        Some(row) => rows.push(row),
+                        None => {
+                            closed = true;
+                            break;
+                        }
+                    }
                 }
-            },
-            Err(e) => {
-                println!("Error enqueueing task: {:?}", e);
-                Err(anyhow::anyhow!("Error: {:?}", e))
-            },
+
+                report.rows_read += rows.len() as u64;
+                for row in rows.iter_mut() {
+                    key.take_key(row).map_err(Error::msg)?;
+                }
+                let documents = Self::assemble(&query_executor, data_source, index_data_query, &key, std::mem::take(&mut rows)).await?;
+                for document in &documents {
+                    ndjson.push(document).map_err(Error::msg)?;
+                    if ndjson.is_full() {
+                        Self::flush(&index, &key, &mut ndjson, &mut throttle, report, &mut tasks).await?;
+                    }
+                }
+            }
+            if !ndjson.is_empty() {
+                Self::flush(&index, &key, &mut ndjson, &mut throttle, report, &mut tasks).await?;
+            }
+            Ok::<(), Error>(())
+        };
+
+        let (read, send) = tokio::join!(read, send);
+        report.throttled_ms = throttle.throttled().as_millis() as u64;
+        // Ошибка отправки останавливает и чтение, поэтому она первопричина
+        send?;
+        read.map_err(|e| anyhow::anyhow!("Error: {}", e))?;
+
+        for task_info in tasks {
+            let task_uid = task_info.task_uid;
+            match self.meilisearch_client.wait_for_task(task_info, None, Some(TASK_TIMEOUT)).await? {
+                Task::Failed { content } => {
+                    println!("❌ Task failed: {:?}", content);
+                    return Err(anyhow::anyhow!("Meilisearch task {} failed: {:?}", task_uid, content.error));
+                }
+                _ => println!("✅ Task {} finished", task_uid),
+            }
+        }
+
+        report.cursor_value = cursor.map(|window| window.up_to);
+        Ok(())
+    }
+
+    /// Пробный прогон: сравнивает весь результат запроса с документами индекса по первичному ключу
+    /// и ничего не пишет в Meilisearch. Курсор не учитывается — сравнивается полная выборка.
+    pub async fn execute_dry_run(
+        &self,
+        payload: &InsertDataIndexDataQueryRequest,
+    ) -> Result<DryRunReportDto, Error> {
+        let index_data_query = self.get_index_data_query(payload).await?;
+        let data_source = self.get_data_source(&index_data_query).await?;
+        let batch_size = self.batch_size(payload);
+        let params = NamedParams::merge(&index_data_query.params, payload.params.as_ref());
+        let key = DocumentKey::for_query(&index_data_query).map_err(Error::msg)?;
+
+        // Из индекса читаются только ключи и хеши документов, целиком — лишь документы для примеров
+        let index = self.meilisearch_client.index(&index_data_query.index_uid);
+        let mut current = if self.ensure_primary_key(&index_data_query.index_uid, key.field()).await? {
+            IndexDocumentReader::fetch_document_hashes(&index, key.field(), batch_size)
+                .await
+                .map_err(Error::msg)?
+        } else {
+            HashMap::new()
         };
-        
 
+        let query_executor = self.query_executor(&index_data_query, &params);
+        let mut report = DryRunReportDto::new(index_data_query.index_uid.clone());
 
-        // этот json отправить на добавление в index
-        // Temporary stub implementation
-        Ok((Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()  ))
+        // Результат читается одним потоком, как при синхронизации: HTTP API и файлы
+        // не перечитываются заново ради каждой keyset-страницы
+        let (sender, mut receiver) = mpsc::channel::<Value>(batch_size as usize);
+        let read = query_executor.stream_query(&data_source, &index_data_query.query, &key, None, batch_size, sender);
+        let compare = async {
+            let mut rows = Vec::with_capacity(batch_size as usize);
+            let mut closed = false;
+            while !closed {
+                while rows.len() < batch_size as usize {
+                    match receiver.recv().await {
+                        Some(row) => rows.push(row),
+                        None => {
+                            closed = true;
+                            break;
+                        }
+                    }
+                }
+
+                for row in rows.iter_mut() {
+                    key.take_key(row).map_err(Error::msg)?;
+                }
+                let documents = Self::assemble(&query_executor, &data_source, &index_data_query, &key, std::mem::take(&mut rows)).await?;
+                for document in documents {
+                    report.rows_read += 1;
+                    let document_key = document.get(key.field()).and_then(IndexDocumentReader::key_to_string);
+                    let existing = document_key.as_ref().and_then(|document_key| current.remove(document_key));
+                    match (document_key, existing) {
+                        (Some(document_key), Some(hash)) if hash != IndexDocumentReader::document_hash(&document) => {
+                            if report.changed.needs_sample() {
+                                let before = IndexDocumentReader::fetch_document(&index, &document_key)
+                                    .await
+                                    .map_err(Error::msg)?;
+                                report.changed.push(json!({ "before": before, "after": document }));
+                            } else {
+                                report.changed.tally();
+                            }
+                        }
+                        (_, Some(_)) => report.unchanged.push(document),
+                        (_, None) => report.new.push(document),
+                    }
+                }
+            }
+            Ok::<(), Error>(())
+        };
+
+        let (read, compare) = tokio::join!(read, compare);
+        compare?;
+        read.map_err(|e| anyhow::anyhow!("Error: {}", e))?;
+
+        let mut orphans: Vec<String> = current.into_keys().collect();
+        orphans.sort();
+        for document_key in orphans {
+            if report.orphaned.needs_sample() {
+                let document = IndexDocumentReader::fetch_document(&index, &document_key)
+                    .await
+                    .map_err(Error::msg)?;
+                report.orphaned.push(document);
+            } else {
+                report.orphaned.tally();
+            }
+        }
+
+        Ok(report)
     }
-    
-    async fn get_index_data_query(&self, request: &InsertDataIndexDataQueryRequest) -> IndexDataQuery {
-        self.index_data_query_repository.get(request.id).await.unwrap()
+
+    /// Достраивает документы пачки: вложенные массивы дочерних запросов, затем преобразование
+    async fn assemble(
+        query_executor: &QueryExecutor,
+        data_source: &DataSource,
+        index_data_query: &IndexDataQuery,
+        key: &DocumentKey,
+        mut documents: Vec<Value>,
+    ) -> Result<Vec<Value>, Error> {
+        ChildQueryAssembler::attach(query_executor, data_source, &index_data_query.child_queries, key.field(), &mut documents)
+            .await
+            .map_err(Error::msg)?;
+
+        match &index_data_query.transformation {
+            Some(spec) => DocumentTransformer::apply_all(spec, documents).map_err(Error::msg),
+            None => Ok(documents),
+        }
     }
-}
 
+    /// Отправляет накопленную NDJSON-пачку одной задачей add_documents, когда это позволяет очередь индекса
+    async fn flush(
+        index: &Index,
+        key: &DocumentKey,
+        ndjson: &mut NdjsonBatch,
+        throttle: &mut TaskThrottle,
+        report: &mut SyncReportDto,
+        tasks: &mut Vec<TaskInfo>,
+    ) -> Result<(), Error> {
+        let documents = ndjson.rows();
+        let payload = ndjson.take();
+        let bytes = payload.len() as u64;
+
+        throttle.acquire().await?;
+        let task_info = index
+            .add_documents_ndjson(futures::io::Cursor::new(payload), Some(key.field()))
+            .await
+            .map_err(|e| anyhow::anyhow!("Error enqueueing task: {:?}", e))?;
+
+        throttle.track(task_info.task_uid);
+        report.batches_sent += 1;
+        report.documents_sent += documents as u64;
+        report.bytes_sent += bytes;
+        report.task_uids.push(task_info.task_uid);
+        println!(
+            "📦 Batch {} sent: {} documents ({} bytes), task {}",
+            report.batches_sent, documents, bytes, task_info.task_uid
+        );
+        tasks.push(task_info);
+        Ok(())
+    }
+
+    /// Документы нельзя отправлять, если у существующего индекса другой первичный ключ
+    /// Возвращает, существует ли индекс
+    async fn ensure_primary_key(&self, index_uid: &str, primary_key: &str) -> Result<bool, Error> {
+        match self.meilisearch_client.get_index(index_uid).await {
+            Ok(index) => match index.primary_key {
+                Some(existing) if existing != primary_key => Err(anyhow::anyhow!(
+                    "Index '{}' uses primary key '{}', but the query is configured with '{}'",
+                    index_uid, existing, primary_key
+                )),
+                _ => Ok(true),
+            },
+            Err(MeilisearchSdkError::Meilisearch(error)) if error.error_code == ErrorCode::IndexNotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Исполнитель запроса с его параметрами и форматом дат
+    fn query_executor(&self, index_data_query: &IndexDataQuery, params: &QueryParams) -> QueryExecutor {
+        self.query_executor
+            .clone()
+            .with_epoch_timestamps(index_data_query.epoch_timestamps)
+            .with_params(params.clone())
+    }
+
+    fn batch_size(&self, payload: &InsertDataIndexDataQueryRequest) -> u32 {
+        payload.batch_size.filter(|size| *size > 0).unwrap_or(self.default_batch_size)
+    }
+
+    async fn get_data_source(&self, index_data_query: &IndexDataQuery) -> Result<DataSource, Error> {
+        self.data_source_repository
+            .get(index_data_query.data_source_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Data source {} not found", index_data_query.data_source_id))
+    }
+
+    async fn get_index_data_query(&self, request: &InsertDataIndexDataQueryRequest) -> Result<IndexDataQuery, Error> {
+        self.index_data_query_repository
+            .get(request.id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", request.id))
+    }
+}
@@ -3,3 +3,14 @@ pub mod dto;
 pub mod store_index_data_query_request_use_case;
 pub mod get_index_data_queries_use_case;
 pub mod insert_data_index_data_query_request_use_case;
+pub mod enqueue_insert_data_index_data_query_use_case;
+pub mod reset_cursor_index_data_query_use_case;
+pub mod reconcile_deletions_index_data_query_use_case;
+pub mod update_schedule_index_data_query_use_case;
+pub mod reindex_index_data_query_use_case;
+pub mod update_transformation_index_data_query_use_case;
+pub mod update_notify_channel_index_data_query_use_case;
+pub mod notify_trigger_index_data_query_use_case;
+pub mod get_sync_runs_index_data_query_use_case;
+pub mod preview_index_data_query_use_case;
+pub mod update_child_queries_index_data_query_use_case;
@@ -0,0 +1,28 @@
+use anyhow::Error;
+use crate::domain::data_source::services::notify_trigger::NotifyTrigger;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::presentation::requests::index_data_query::notify_trigger_index_data_query_request::NotifyTriggerIndexDataQueryRequest;
+
+/// Возвращает SQL триггера, который нужно выполнить в базе-источнике для канала запроса
+pub struct NotifyTriggerIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait> {
+    index_data_query_repository: R,
+}
+
+impl <R: IndexDataQueryRepositoryTrait> NotifyTriggerIndexDataQueryUseCase <R> {
+    pub fn new(index_data_query_repository: R) -> Self {
+        Self { index_data_query_repository }
+    }
+
+    pub async fn execute(&self, id: u32, payload: &NotifyTriggerIndexDataQueryRequest) -> Result<String, Error> {
+        let index_data_query = self.index_data_query_repository
+            .get(id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", id))?;
+
+        let channel = index_data_query.notify_channel
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} has no notify channel", id))?;
+        let key_column = payload.key_column.as_deref().unwrap_or(&index_data_query.primary_key);
+
+        NotifyTrigger::sql(&payload.table, key_column, &channel).map_err(Error::msg)
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::Error;
+use crate::domain::data_source::entities::transformation_spec::TransformationSpec;
+use crate::domain::data_source::services::document_transformer::DocumentTransformer;
+use crate::domain::data_source::services::named_params::QueryParams;
+use crate::domain::data_source::services::query_executor::{QueryExecutor, QueryPreview};
+use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
+use crate::presentation::requests::index_data_query::preview_index_data_query_request::PreviewIndexDataQueryRequest;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 1_000;
+
+/// Превью запроса: первые строки в том виде, в каком они попадут в индекс, и схема колонок.
+/// Идёт через тот же QueryExecutor и DocumentTransformer, что и синхронизация.
+pub struct PreviewIndexDataQueryUseCase <R: DataSourceRepositoryTrait> {
+    data_source_repository: R,
+    query_executor: QueryExecutor,
+}
+
+impl <R: DataSourceRepositoryTrait> PreviewIndexDataQueryUseCase <R> {
+    pub fn new(data_source_repository: R, query_executor: QueryExecutor) -> Self {
+        Self {
+            data_source_repository,
+            query_executor,
+        }
+    }
+
+    pub async fn execute(&self, payload: &PreviewIndexDataQueryRequest) -> Result<QueryPreview, Error> {
+        let data_source = self.data_source_repository
+            .get(payload.data_source_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Data source {} not found", payload.data_source_id))?;
+
+        let params: QueryParams = match payload.params.as_deref().filter(|params| !params.trim().is_empty()) {
+            Some(params) => serde_json::from_str(params).map_err(|e| anyhow::anyhow!("Invalid params: {}", e))?,
+            None => QueryParams::new(),
+        };
+        let transformation: Option<TransformationSpec> = match payload.transformation.as_deref().filter(|spec| !spec.trim().is_empty()) {
+            Some(spec) => Some(serde_json::from_str(spec).map_err(|e| anyhow::anyhow!("Invalid transformation: {}", e))?),
+            None => None,
+        };
+        let limit = payload.limit.filter(|limit| *limit > 0).unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+        let mut preview = self.query_executor
+            .clone()
+            .with_params(params)
+            .with_epoch_timestamps(payload.epoch_timestamps.unwrap_or(false))
+            .preview(&data_source, &payload.query, limit)
+            .await
+            .map_err(Error::msg)?;
+
+        if let Some(spec) = &transformation {
+            preview.rows = DocumentTransformer::apply_all(spec, preview.rows).map_err(Error::msg)?;
+        }
+
+        Ok(preview)
+    }
+}
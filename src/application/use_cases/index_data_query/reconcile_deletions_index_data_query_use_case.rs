@@ -0,0 +1,53 @@
+use anyhow::Error;
+use meilisearch_sdk::client::Client;
+use crate::domain::data_source::services::deletion_reconciler::{DeletionReconciler, DeletionReport};
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::query_executor::QueryExecutor;
+use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+
+/// Удаляет из индекса документы, строк которых больше нет в источнике запроса
+pub struct ReconcileDeletionsIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait, R2: DataSourceRepositoryTrait> {
+    index_data_query_repository: R,
+    data_source_repository: R2,
+    meilisearch_client: Client,
+    batch_size: u32,
+    query_executor: QueryExecutor,
+}
+
+impl <R: IndexDataQueryRepositoryTrait, R2: DataSourceRepositoryTrait> ReconcileDeletionsIndexDataQueryUseCase <R, R2> {
+    pub fn new(
+        index_data_query_repository: R,
+        data_source_repository: R2,
+        meilisearch_client: Client,
+        batch_size: u32,
+        query_executor: QueryExecutor,
+    ) -> Self {
+        Self {
+            index_data_query_repository,
+            data_source_repository,
+            meilisearch_client,
+            batch_size,
+            query_executor,
+        }
+    }
+
+    pub async fn execute(&self, id: u32) -> Result<DeletionReport, Error> {
+        let index_data_query = self.index_data_query_repository
+            .get(id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", id))?;
+
+        let data_source = self.data_source_repository
+            .get(index_data_query.data_source_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Data source {} not found", index_data_query.data_source_id))?;
+
+        let key = DocumentKey::for_query(&index_data_query).map_err(Error::msg)?;
+        let query_executor = self.query_executor.clone().with_params(index_data_query.params.clone());
+        DeletionReconciler::new(self.meilisearch_client.clone())
+            .reconcile(&data_source, &index_data_query, &key, &query_executor, self.batch_size)
+            .await
+            .map_err(Error::msg)
+    }
+}
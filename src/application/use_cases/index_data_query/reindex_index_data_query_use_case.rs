@@ -0,0 +1,126 @@
+use std::time::Duration;
+use anyhow::Error;
+use chrono::Utc;
+use meilisearch_sdk::client::{Client, SwapIndexes};
+use meilisearch_sdk::errors::{Error as MeilisearchSdkError, ErrorCode};
+use meilisearch_sdk::indexes::Index;
+use meilisearch_sdk::task_info::TaskInfo;
+use meilisearch_sdk::tasks::Task;
+use crate::application::use_cases::index_data_query::dto::sync_report_dto::SyncReportDto;
+use crate::application::use_cases::index_data_query::insert_data_index_data_query_request_use_case::InsertDataIndexDataQueryRequestUseCase;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::requests::index_data_query::insert_data_index_data_query_request::InsertDataIndexDataQueryRequest;
+
+/// Сколько ждать завершения служебных задач (создание, настройки, swap, удаление)
+const TASK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Полная переиндексация без простоя: данные заливаются в теневой индекс с настройками живого,
+/// затем индексы атомарно меняются местами, а старый удаляется
+pub struct ReindexIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait, R2: DataSourceRepositoryTrait> {
+    index_data_query_repository: R,
+    sync_use_case: InsertDataIndexDataQueryRequestUseCase<R, R2>,
+    meilisearch_client: Client,
+}
+
+impl <R: IndexDataQueryRepositoryTrait, R2: DataSourceRepositoryTrait> ReindexIndexDataQueryUseCase <R, R2> {
+    pub fn new(
+        index_data_query_repository: R,
+        sync_use_case: InsertDataIndexDataQueryRequestUseCase<R, R2>,
+        meilisearch_client: Client,
+    ) -> Self {
+        Self {
+            index_data_query_repository,
+            sync_use_case,
+            meilisearch_client,
+        }
+    }
+
+    pub async fn execute(&self, payload: &InsertDataIndexDataQueryRequest) -> Result<SyncReportDto, Error> {
+        let index_data_query = self.index_data_query_repository
+            .get(payload.id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", payload.id))?;
+
+        // Теневой индекс создаётся с ключом запроса: так переиндексация позволяет сменить первичный ключ
+        let primary_key = DocumentKey::for_query(&index_data_query).map_err(Error::msg)?.field().to_string();
+        let live_uid = index_data_query.index_uid.clone();
+        let live = self.get_or_create_index(&live_uid, &primary_key).await?;
+        let settings = live.get_settings().await?;
+
+        let shadow_uid = format!("{}__reindex_{}", live_uid, Utc::now().timestamp());
+        println!("🔁 Reindexing '{}' through shadow index '{}'", live_uid, shadow_uid);
+
+        let task = self.meilisearch_client.create_index(&shadow_uid, Some(&primary_key)).await?;
+        self.wait(task).await?;
+
+        let result = self.fill_and_swap(payload, &live_uid, &shadow_uid, &settings).await;
+
+        // После swap под теневым именем лежат старые данные; при ошибке — недозалитая копия
+        let cleanup = match self.meilisearch_client.index(&shadow_uid).delete().await {
+            Ok(task) => self.wait(task).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = cleanup {
+            println!("⚠️ Failed to delete index '{}': {}", shadow_uid, e);
+        }
+
+        let mut report = result?;
+        if let Some(cursor_value) = &report.cursor_value {
+            self.index_data_query_repository
+                .update_cursor(index_data_query.id, Some(cursor_value.clone()))
+                .await
+                .map_err(|e| report.failure(e))?;
+        }
+
+        report.index_uid = live_uid;
+        Ok(report)
+    }
+
+    async fn fill_and_swap(
+        &self,
+        payload: &InsertDataIndexDataQueryRequest,
+        live_uid: &str,
+        shadow_uid: &str,
+        settings: &meilisearch_sdk::settings::Settings,
+    ) -> Result<SyncReportDto, Error> {
+        let task = self.meilisearch_client.index(shadow_uid).set_settings(settings).await?;
+        self.wait(task).await?;
+
+        let report = self.sync_use_case.execute_full_into(payload, shadow_uid).await?;
+
+        let swap = SwapIndexes {
+            indexes: (live_uid.to_string(), shadow_uid.to_string()),
+            rename: None,
+        };
+        let swapped = match self.meilisearch_client.swap_indexes([&swap]).await {
+            Ok(task) => self.wait(task).await,
+            Err(e) => Err(e.into()),
+        };
+        swapped.map_err(|e| report.failure(e))?;
+
+        Ok(report)
+    }
+
+    /// Живой индекс может ещё не существовать: swap требует оба индекса
+    async fn get_or_create_index(&self, uid: &str, primary_key: &str) -> Result<Index, Error> {
+        match self.meilisearch_client.get_index(uid).await {
+            Ok(index) => Ok(index),
+            Err(MeilisearchSdkError::Meilisearch(error)) if error.error_code == ErrorCode::IndexNotFound => {
+                let task = self.meilisearch_client.create_index(uid, Some(primary_key)).await?;
+                self.wait(task).await?;
+                Ok(self.meilisearch_client.get_index(uid).await?)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn wait(&self, task_info: TaskInfo) -> Result<(), Error> {
+        let task_uid = task_info.task_uid;
+        match self.meilisearch_client.wait_for_task(task_info, None, Some(TASK_TIMEOUT)).await? {
+            Task::Failed { content } => Err(anyhow::anyhow!("Meilisearch task {} failed: {:?}", task_uid, content.error)),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use anyhow::Error;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+
+/// Сбрасывает отметку инкрементальной синхронизации,
+/// чтобы следующий запуск перечитал все данные запроса
+pub struct ResetCursorIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait> {
+    repo: R,
+}
+
+impl <R: IndexDataQueryRepositoryTrait> ResetCursorIndexDataQueryUseCase <R> {
+    pub fn new(repo: R) -> Self {
+        Self { repo }
+    }
+
+    pub async fn execute(&self, id: u32) -> Result<(), Error> {
+        self.repo.update_cursor(id, None).await
+    }
+}
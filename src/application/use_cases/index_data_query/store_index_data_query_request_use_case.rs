@@ -1,4 +1,10 @@
 use anyhow::Error;
+use crate::domain::data_source::services::sync_schedule::SyncSchedule;
+use crate::domain::data_source::services::document_transformer::DocumentTransformer;
+use crate::domain::data_source::services::document_key::{DocumentKey, DEFAULT_PRIMARY_KEY};
+use crate::domain::data_source::services::notify_trigger::NotifyTrigger;
+use crate::domain::data_source::services::named_params::NamedParams;
+use crate::domain::data_source::services::child_query_assembler::ChildQueryAssembler;
 use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
 use crate::presentation::requests::index_data_query::store_index_data_query_request::StoreIndexDataQueryRequest;
 
@@ -14,6 +20,40 @@ impl <R: IndexDataQueryRepositoryTrait> StoreIndexDataQueryRequestUseCase <R> {
         &self,
         payload: &StoreIndexDataQueryRequest,
     ) -> Result<String, Error> {
+        if payload.max_delete_ratio.is_some_and(|ratio| !(0.0..=1.0).contains(&ratio)) {
+            return Err(Error::msg("max_delete_ratio must be between 0 and 1"));
+        }
+
+        if let Some(expression) = payload.cron_expression.as_deref().filter(|expression| !expression.trim().is_empty()) {
+            SyncSchedule::parse(expression, payload.timezone.as_deref()).map_err(Error::msg)?;
+        }
+
+        // Запрос разбирается сразу, чтобы ошибка в параметрах не всплыла только при синхронизации
+        NamedParams::compile(&payload.query).map_err(Error::msg)?;
+        if let Some(params) = &payload.params {
+            NamedParams::validate_names(params).map_err(Error::msg)?;
+        }
+
+        let key = DocumentKey::new(
+            payload.primary_key.as_deref().unwrap_or(DEFAULT_PRIMARY_KEY),
+            payload.primary_key_template.as_deref(),
+        ).map_err(Error::msg)?;
+
+        if let Some(channel) = payload.notify_channel.as_deref().filter(|channel| !channel.trim().is_empty()) {
+            NotifyTrigger::validate_channel(channel).map_err(Error::msg)?;
+            if key.template().is_some() {
+                return Err(Error::msg("Notify listener is not supported for composite primary keys"));
+            }
+        }
+
+        if let Some(spec) = &payload.transformation {
+            DocumentTransformer::validate(spec, key.field()).map_err(Error::msg)?;
+        }
+
+        if let Some(child_queries) = &payload.child_queries {
+            ChildQueryAssembler::validate(child_queries, &key).map_err(Error::msg)?;
+        }
+
         let result = self.repo.store(payload).await;
         match result {
             Some(_) => Ok("Success".to_string()),
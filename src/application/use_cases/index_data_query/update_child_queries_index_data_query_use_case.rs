@@ -0,0 +1,30 @@
+use anyhow::Error;
+use crate::domain::data_source::services::child_query_assembler::ChildQueryAssembler;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::presentation::requests::index_data_query::update_child_queries_index_data_query_request::UpdateChildQueriesIndexDataQueryRequest;
+
+/// Задаёт дочерние запросы; документы получат новые массивы при следующей синхронизации
+pub struct UpdateChildQueriesIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait> {
+    index_data_query_repository: R,
+}
+
+impl <R: IndexDataQueryRepositoryTrait> UpdateChildQueriesIndexDataQueryUseCase <R> {
+    pub fn new(index_data_query_repository: R) -> Self {
+        Self { index_data_query_repository }
+    }
+
+    pub async fn execute(&self, id: u32, payload: &UpdateChildQueriesIndexDataQueryRequest) -> Result<(), Error> {
+        let index_data_query = self.index_data_query_repository
+            .get(id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", id))?;
+
+        let key = DocumentKey::for_query(&index_data_query).map_err(Error::msg)?;
+        ChildQueryAssembler::validate(&payload.child_queries, &key).map_err(Error::msg)?;
+
+        self.index_data_query_repository
+            .update_child_queries(id, payload.child_queries.clone())
+            .await
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Error;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::notify_trigger::NotifyTrigger;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::presentation::requests::index_data_query::update_notify_channel_index_data_query_request::UpdateNotifyChannelIndexDataQueryRequest;
+
+/// Включает или выключает синхронизацию по NOTIFY; NotifyListener подхватывает изменение сам
+pub struct UpdateNotifyChannelIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait> {
+    index_data_query_repository: R,
+}
+
+impl <R: IndexDataQueryRepositoryTrait> UpdateNotifyChannelIndexDataQueryUseCase <R> {
+    pub fn new(index_data_query_repository: R) -> Self {
+        Self { index_data_query_repository }
+    }
+
+    pub async fn execute(&self, id: u32, payload: &UpdateNotifyChannelIndexDataQueryRequest) -> Result<(), Error> {
+        let index_data_query = self.index_data_query_repository
+            .get(id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", id))?;
+
+        let notify_channel = payload.notify_channel.clone().filter(|channel| !channel.trim().is_empty());
+        if let Some(channel) = &notify_channel {
+            NotifyTrigger::validate_channel(channel).map_err(Error::msg)?;
+            if DocumentKey::for_query(&index_data_query).map_err(Error::msg)?.template().is_some() {
+                return Err(Error::msg("Notify listener is not supported for composite primary keys"));
+            }
+        }
+
+        self.index_data_query_repository
+            .update_notify_channel(id, notify_channel)
+            .await
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use crate::domain::data_source::services::sync_schedule::SyncSchedule;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::presentation::requests::index_data_query::update_schedule_index_data_query_request::UpdateScheduleIndexDataQueryRequest;
+use crate::queues::application::queue_service::JobService;
+use crate::queues::domain::value_objects::queue_name::QueueName;
+use crate::requests::index_data_query::insert_data_index_data_query_request::InsertDataIndexDataQueryRequest;
+
+/// Меняет расписание запроса и снимает уже запланированный по старому расписанию запуск
+pub struct UpdateScheduleIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait> {
+    index_data_query_repository: R,
+    job_service: Arc<JobService>,
+}
+
+impl <R: IndexDataQueryRepositoryTrait> UpdateScheduleIndexDataQueryUseCase <R> {
+    pub fn new(index_data_query_repository: R, job_service: Arc<JobService>) -> Self {
+        Self {
+            index_data_query_repository,
+            job_service,
+        }
+    }
+
+    /// Возвращает время следующего запуска по новому расписанию
+    pub async fn execute(
+        &self,
+        id: u32,
+        payload: &UpdateScheduleIndexDataQueryRequest,
+    ) -> Result<Option<DateTime<Utc>>, Error> {
+        let cron_expression = payload.cron_expression.clone().filter(|expression| !expression.trim().is_empty());
+        let timezone = payload.timezone.clone().filter(|timezone| !timezone.trim().is_empty());
+
+        let next_run_at = match &cron_expression {
+            Some(expression) => SyncSchedule::parse(expression, timezone.as_deref())
+                .map_err(Error::msg)?
+                .next_after(Utc::now()),
+            None => None,
+        };
+
+        self.index_data_query_repository
+            .update_schedule(id, cron_expression, timezone)
+            .await?;
+
+        // Отложенные задачи создаёт только планировщик; ручные запуски не трогаем
+        for job in self.job_service.list_active_jobs(&QueueName::IndexDocuments).await? {
+            let is_this_query = serde_json::from_str::<InsertDataIndexDataQueryRequest>(&job.payload)
+                .is_ok_and(|request| request.id == id);
+            if let (true, Some(_), Some(job_id)) = (is_this_query, job.scheduled_at, job.id) {
+                self.job_service.cancel_job(job_id).await?;
+            }
+        }
+
+        Ok(next_run_at)
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::Error;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::document_transformer::DocumentTransformer;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::presentation::requests::index_data_query::update_transformation_index_data_query_request::UpdateTransformationIndexDataQueryRequest;
+
+pub struct UpdateTransformationIndexDataQueryUseCase <R: IndexDataQueryRepositoryTrait> {
+    index_data_query_repository: R,
+}
+
+impl <R: IndexDataQueryRepositoryTrait> UpdateTransformationIndexDataQueryUseCase <R> {
+    pub fn new(index_data_query_repository: R) -> Self {
+        Self { index_data_query_repository }
+    }
+
+    pub async fn execute(&self, id: u32, payload: &UpdateTransformationIndexDataQueryRequest) -> Result<(), Error> {
+        let index_data_query = self.index_data_query_repository
+            .get(id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", id))?;
+
+        if let Some(spec) = &payload.transformation {
+            let key = DocumentKey::for_query(&index_data_query).map_err(Error::msg)?;
+            DocumentTransformer::validate(spec, key.field()).map_err(Error::msg)?;
+        }
+
+        self.index_data_query_repository
+            .update_transformation(id, payload.transformation.clone())
+            .await
+    }
+}
@@ -0,0 +1,5 @@
+pub mod sync_worker;
+pub mod sync_scheduler;
+pub mod notify_listener;
+pub mod sync_run_recorder;
+pub mod sync_lock;
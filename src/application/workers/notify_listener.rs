@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use anyhow::Error;
+use sqlx::postgres::PgListener;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use crate::application::workers::sync_run_recorder::SyncRunRecorder;
+use crate::domain::data_source::entities::sync_run::{PartialSyncError, SyncRunStats, SyncTrigger};
+use crate::domain::data_source::services::child_query_assembler::ChildQueryAssembler;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::document_transformer::DocumentTransformer;
+use crate::domain::data_source::services::index_document_reader::IndexDocumentReader;
+use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::infrastructure::repositories::data_source_repository::DataSourceRepository;
+use crate::infrastructure::repositories::index_data_query_repository::IndexDataQueryRepository;
+use crate::state::AppState;
+
+/// Как часто пересматривать, какие запросы нужно слушать
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Тишина в канале, после которой накопленные ключи отправляются пачкой
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Пачка отправляется и без паузы, если набралось столько ключей
+const MAX_PENDING_KEYS: usize = 1_000;
+
+/// Предел паузы между попытками переподключения
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Синхронизация почти в реальном времени: для каждого запроса с `notify_channel` держит
+/// открытый PgListener, копит ключи из уведомлений и перечитывает только эти строки
+pub struct NotifyListener {
+    state: AppState,
+}
+
+/// Слушатель одного запроса; перезапускается, если поменялись канал или источник
+struct RunningListener {
+    channel: String,
+    data_source_id: u32,
+    handle: JoinHandle<()>,
+}
+
+impl NotifyListener {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Бесконечный цикл; запускается через tokio::spawn вместе с сервером
+    pub async fn run(self) {
+        info!("Notify listener supervisor started");
+        let mut running: HashMap<u32, RunningListener> = HashMap::new();
+
+        loop {
+            if let Err(e) = self.supervise(&mut running).await {
+                error!("Notify listener supervisor failed: {}", e);
+            }
+            tokio::time::sleep(SUPERVISE_INTERVAL).await;
+        }
+    }
+
+    async fn supervise(&self, running: &mut HashMap<u32, RunningListener>) -> Result<(), Error> {
+        let db = (*self.state.database).clone();
+        let index_data_queries = IndexDataQueryRepository::new(db.clone()).all(None).await?;
+        let data_source_repository = DataSourceRepository::new(db);
+
+        let mut wanted = HashMap::new();
+        for index_data_query in index_data_queries {
+            let Some(channel) = index_data_query.notify_channel.clone() else {
+                continue;
+            };
+            match data_source_repository.get(index_data_query.data_source_id).await {
+                Some(data_source) if self.state.connectors.get(&data_source.database_type).is_some_and(|connector| connector.supports_notify()) => {
+                    wanted.insert(index_data_query.id, (channel, data_source.id));
+                }
+                _ => warn!(
+                    "Index data query {}: notify channel requires a data source with LISTEN/NOTIFY support",
+                    index_data_query.id
+                ),
+            }
+        }
+
+        running.retain(|id, listener| {
+            let keep = !listener.handle.is_finished()
+                && wanted.get(id) == Some(&(listener.channel.clone(), listener.data_source_id));
+            if !keep {
+                info!("Stopping notify listener for index data query {}", id);
+                listener.handle.abort();
+            }
+            keep
+        });
+
+        for (id, (channel, data_source_id)) in wanted {
+            if running.contains_key(&id) {
+                continue;
+            }
+            info!("Starting notify listener for index data query {} on channel '{}'", id, channel);
+            let handle = tokio::spawn(Self::listen(self.state.clone(), id, channel.clone()));
+            running.insert(id, RunningListener { channel, data_source_id, handle });
+        }
+
+        Ok(())
+    }
+
+    /// Чтение уведомлений и отправка пачек идут параллельно: чтение никогда не прерывается
+    async fn listen(state: AppState, index_data_query_id: u32, channel: String) {
+        let (sender, receiver) = mpsc::channel::<String>(MAX_PENDING_KEYS * 10);
+
+        tokio::join!(
+            Self::receive(state.clone(), index_data_query_id, channel, sender),
+            Self::flush_loop(state, index_data_query_id, receiver),
+        );
+    }
+
+    async fn receive(state: AppState, index_data_query_id: u32, channel: String, sender: mpsc::Sender<String>) {
+        let mut delay = Duration::from_secs(1);
+
+        loop {
+            match Self::connect(&state, index_data_query_id, &channel).await {
+                Ok(mut listener) => {
+                    delay = Duration::from_secs(1);
+                    loop {
+                        match listener.try_recv().await {
+                            Ok(Some(notification)) => {
+                                if sender.send(notification.payload().to_string()).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Ok(None) => warn!(
+                                "Notify channel '{}' lost its connection, reconnecting; changes made meanwhile are missed",
+                                channel
+                            ),
+                            Err(e) => {
+                                error!("Notify channel '{}' failed: {}", channel, e);
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => error!("Cannot listen on channel '{}': {}", channel, e),
+            }
+
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    }
+
+    async fn connect(state: &AppState, index_data_query_id: u32, channel: &str) -> Result<PgListener, Error> {
+        let db = (*state.database).clone();
+        let index_data_query = IndexDataQueryRepository::new(db.clone())
+            .get(index_data_query_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", index_data_query_id))?;
+        let data_source = DataSourceRepository::new(db)
+            .get(index_data_query.data_source_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Data source {} not found", index_data_query.data_source_id))?;
+
+        // Слушатель занимает одно подключение общего пула источника: POOL_MAX_CONNECTIONS соблюдается,
+        // а переподключения не плодят новых пулов
+        let pool = state.connections.pool(&data_source).map_err(Error::msg)?;
+        let mut listener = PgListener::connect_with(&pool).await?;
+        listener.listen(channel).await?;
+        info!("Listening on channel '{}'", channel);
+
+        Ok(listener)
+    }
+
+    /// Копит ключи, пока в канале не наступит пауза DEBOUNCE или не наберётся MAX_PENDING_KEYS
+    async fn flush_loop(state: AppState, index_data_query_id: u32, mut receiver: mpsc::Receiver<String>) {
+        while let Some(first) = receiver.recv().await {
+            let mut pending = HashSet::from([first]);
+            let mut closed = false;
+
+            while pending.len() < MAX_PENDING_KEYS {
+                match tokio::time::timeout(DEBOUNCE, receiver.recv()).await {
+                    Ok(Some(key)) => {
+                        pending.insert(key);
+                    }
+                    Ok(None) => {
+                        closed = true;
+                        break;
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            let recorder = SyncRunRecorder::new((*state.database).clone());
+            let run_id = recorder.start(index_data_query_id, SyncTrigger::Notify).await;
+            // Не пишет в индекс, пока идёт синхронизация или переиндексация того же запроса
+            let lock = state.sync_locks.acquire(index_data_query_id).await;
+            let result = Self::flush(&state, index_data_query_id, pending).await;
+            drop(lock);
+            recorder.finish(run_id, &result).await;
+
+            if let Err(e) = result {
+                error!("Index data query {}: failed to apply notified changes: {}", index_data_query_id, e);
+            }
+            if closed {
+                return;
+            }
+        }
+    }
+
+    /// Перечитывает строки по ключам: найденные обновляет, пропавшие удаляет из индекса
+    async fn flush(state: &AppState, index_data_query_id: u32, pending: HashSet<String>) -> Result<SyncRunStats, Error> {
+        let db = (*state.database).clone();
+        let index_data_query = IndexDataQueryRepository::new(db.clone())
+            .get(index_data_query_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Index data query {} not found", index_data_query_id))?;
+        let data_source = DataSourceRepository::new(db)
+            .get(index_data_query.data_source_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Data source {} not found", index_data_query.data_source_id))?;
+
+        let key = DocumentKey::for_query(&index_data_query).map_err(Error::msg)?;
+        let keys: Vec<String> = pending.into_iter().collect();
+
+        let query_executor = state
+            .query_executor()
+            .with_epoch_timestamps(index_data_query.epoch_timestamps)
+            .with_params(index_data_query.params.clone());
+        let mut rows = query_executor
+            .fetch_rows_by_keys(&data_source, &index_data_query.query, &key, &keys)
+            .await
+            .map_err(Error::msg)?;
+
+        let mut found = HashSet::new();
+        for row in rows.iter_mut() {
+            if let Some(value) = IndexDocumentReader::key_to_string(&key.take_key(row).map_err(Error::msg)?) {
+                found.insert(value);
+            }
+        }
+        let missing: Vec<&String> = keys.iter().filter(|value| !found.contains(*value)).collect();
+
+        ChildQueryAssembler::attach(&query_executor, &data_source, &index_data_query.child_queries, key.field(), &mut rows)
+            .await
+            .map_err(Error::msg)?;
+
+        let documents = match &index_data_query.transformation {
+            Some(spec) => DocumentTransformer::apply_all(spec, rows).map_err(Error::msg)?,
+            None => rows,
+        };
+
+        let mut outcome = SyncRunStats {
+            rows_read: documents.len() as u64,
+            ..SyncRunStats::default()
+        };
+
+        let index = state.meilisearch_client.index(&index_data_query.index_uid);
+        if !documents.is_empty() {
+            let task = index.add_documents(&documents, Some(key.field())).await?;
+            info!("Notify: {} documents updated in '{}', task {}", documents.len(), index.uid, task.task_uid);
+            outcome.documents_sent += documents.len() as u64;
+            outcome.task_uids.push(task.task_uid);
+        }
+        if !missing.is_empty() {
+            // Обновления уже ушли в индекс: неудачный запуск всё равно записывается с ними
+            let task = index.delete_documents(&missing).await.map_err(|e| {
+                Error::new(PartialSyncError { stats: outcome.clone(), message: e.to_string() })
+            })?;
+            info!("Notify: {} documents deleted from '{}', task {}", missing.len(), index.uid, task.task_uid);
+            outcome.documents_sent += missing.len() as u64;
+            outcome.task_uids.push(task.task_uid);
+        }
+
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Блокировки запросов на время записи в их индекс. Синхронизация, переиндексация через теневой индекс
+/// слушатель LISTEN/NOTIFY и сверка удалений одного запроса выполняются по очереди: иначе обмен индексов
+/// отбросил бы документы, записанные в живой индекс во время переиндексации, а сверка удалила бы
+/// документы, добавленные после снимка ключей источника.
+#[derive(Default)]
+pub struct SyncLocks {
+    locks: Mutex<HashMap<u32, Arc<AsyncMutex<()>>>>,
+}
+
+impl SyncLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ждёт, пока запрос освободится; блокировка держится, пока жив guard
+    pub async fn acquire(&self, index_data_query_id: u32) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            locks.entry(index_data_query_id).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn sync_during_reindex_waits_for_swap() {
+        let locks = Arc::new(SyncLocks::new());
+        let reindex = locks.acquire(1).await;
+
+        let sync = tokio::spawn({
+            let locks = locks.clone();
+            async move { locks.acquire(1).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!sync.is_finished());
+
+        // Другой запрос не ждёт
+        drop(tokio::time::timeout(Duration::from_millis(50), locks.acquire(2)).await.unwrap());
+
+        drop(reindex);
+        tokio::time::timeout(Duration::from_secs(1), sync).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::Error;
+use tracing::error;
+use crate::database::Database;
+use crate::domain::data_source::entities::sync_run::{PartialSyncError, SyncRunStats, SyncRunStatus, SyncTrigger};
+use crate::domain::repository::sync_run_repository_trait::SyncRunRepositoryTrait;
+use crate::infrastructure::repositories::sync_run_repository::SyncRunRepository;
+
+/// Пишет запуски в sync_runs. Ошибки записи истории только логируются:
+/// из-за них синхронизация не должна падать
+pub struct SyncRunRecorder {
+    repository: SyncRunRepository,
+}
+
+impl SyncRunRecorder {
+    pub fn new(db: Database) -> Self {
+        Self { repository: SyncRunRepository::new(db) }
+    }
+
+    /// Возвращает id запуска или None, если записать его не удалось
+    pub async fn start(&self, index_data_query_id: u32, trigger: SyncTrigger) -> Option<u32> {
+        match self.repository.start(index_data_query_id, trigger).await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                error!("Failed to record sync run start for index data query {}: {}", index_data_query_id, e);
+                None
+            }
+        }
+    }
+
+    /// Неудачный запуск записывается с тем, что успел сделать, если ошибка это знает (PartialSyncError)
+    pub async fn finish(&self, run_id: Option<u32>, result: &Result<SyncRunStats, Error>) {
+        let Some(run_id) = run_id else {
+            return;
+        };
+
+        let recorded = match result {
+            Ok(stats) => {
+                self.repository
+                    .finish(run_id, SyncRunStatus::Succeeded, stats, None)
+                    .await
+            }
+            Err(e) => {
+                let stats = e
+                    .downcast_ref::<PartialSyncError>()
+                    .map(|partial| partial.stats.clone())
+                    .unwrap_or_default();
+                self.repository
+                    .finish(run_id, SyncRunStatus::Failed, &stats, Some(e.to_string()))
+                    .await
+            }
+        };
+
+        if let Err(e) = recorded {
+            error!("Failed to record sync run {} result: {}", run_id, e);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+use std::time::Duration;
+use anyhow::Error;
+use chrono::Utc;
+use tracing::{error, info, warn};
+use crate::domain::data_source::entities::sync_run::SyncTrigger;
+use crate::domain::data_source::services::sync_schedule::SyncSchedule;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::infrastructure::repositories::index_data_query_repository::IndexDataQueryRepository;
+use crate::queues::application::queue_service::JobService;
+use crate::queues::domain::value_objects::queue_name::QueueName;
+use crate::requests::index_data_query::insert_data_index_data_query_request::InsertDataIndexDataQueryRequest;
+use crate::state::AppState;
+
+/// Как часто планировщик пересматривает расписания
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Планировщик синхронизаций по cron: для каждого запроса с расписанием держит в очереди
+/// ровно одну отложенную задачу на ближайшее время запуска
+pub struct SyncScheduler {
+    state: AppState,
+}
+
+impl SyncScheduler {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Бесконечный цикл; запускается через tokio::spawn вместе с сервером
+    pub async fn run(self) {
+        info!("Sync scheduler started");
+
+        loop {
+            if let Err(e) = self.tick().await {
+                error!("Sync scheduler tick failed: {}", e);
+            }
+            tokio::time::sleep(TICK_INTERVAL).await;
+        }
+    }
+
+    async fn tick(&self) -> Result<(), Error> {
+        let repository = IndexDataQueryRepository::new((*self.state.database).clone());
+        let index_data_queries = repository.all(None).await?;
+
+        let busy = busy_queries(&self.state.job_service).await?;
+
+        for index_data_query in index_data_queries {
+            let Some(expression) = &index_data_query.cron_expression else {
+                continue;
+            };
+            if busy.contains(&index_data_query.id) {
+                continue;
+            }
+
+            let schedule = match SyncSchedule::parse(expression, index_data_query.timezone.as_deref()) {
+                Ok(schedule) => schedule,
+                Err(e) => {
+                    warn!("Index data query {}: {}", index_data_query.id, e);
+                    continue;
+                }
+            };
+            let Some(next_run_at) = schedule.next_after(Utc::now()) else {
+                continue;
+            };
+
+            let payload = InsertDataIndexDataQueryRequest {
+                id: index_data_query.id,
+                batch_size: None,
+                dry_run: false,
+                trigger: SyncTrigger::Schedule,
+                params: None,
+            }.serialise()?;
+            self.state.job_service
+                .enqueue_delayed(QueueName::IndexDocuments, payload, next_run_at)
+                .await?;
+
+            info!("Index data query {} scheduled for {}", index_data_query.id, next_run_at);
+        }
+
+        Ok(())
+    }
+}
+
+/// Запросы, у которых уже есть ожидающая или выполняющаяся синхронизация либо переиндексация
+async fn busy_queries(job_service: &JobService) -> Result<HashSet<u32>, Error> {
+    let mut active_jobs = job_service.list_active_jobs(&QueueName::IndexDocuments).await?;
+    active_jobs.extend(job_service.list_active_jobs(&QueueName::ReindexAll).await?);
+
+    Ok(active_jobs
+        .iter()
+        .filter_map(|job| serde_json::from_str::<InsertDataIndexDataQueryRequest>(&job.payload).ok())
+        .map(|request| request.id)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::queues::domain::entities::job::Job;
+
+    fn payload(id: u32) -> String {
+        InsertDataIndexDataQueryRequest {
+            id,
+            batch_size: None,
+            dry_run: false,
+            trigger: SyncTrigger::Schedule,
+            params: None,
+        }.serialise().unwrap()
+    }
+
+    #[tokio::test]
+    async fn orphaned_running_job_does_not_block_schedule() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let database = Database::new(file.path().to_str().unwrap()).await.unwrap();
+        let job_service = AppState::get_job_service(database).await;
+
+        // Задача, которую выполнял воркер до остановки сервера
+        let orphaned = job_service.enqueue(Job::new(QueueName::IndexDocuments, payload(1))).await.unwrap();
+        job_service.start_job(orphaned).await.unwrap();
+        job_service.enqueue(Job::new(QueueName::ReindexAll, payload(2))).await.unwrap();
+        assert_eq!(busy_queries(&job_service).await.unwrap(), HashSet::from([1, 2]));
+
+        assert_eq!(job_service.fail_interrupted_jobs(&QueueName::IndexDocuments).await.unwrap(), 1);
+        assert_eq!(job_service.fail_interrupted_jobs(&QueueName::ReindexAll).await.unwrap(), 0);
+        assert_eq!(busy_queries(&job_service).await.unwrap(), HashSet::from([2]));
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+use anyhow::Error;
+use tracing::{error, info};
+use crate::application::use_cases::index_data_query::insert_data_index_data_query_request_use_case::InsertDataIndexDataQueryRequestUseCase;
+use crate::application::use_cases::index_data_query::reindex_index_data_query_use_case::ReindexIndexDataQueryUseCase;
+use crate::application::workers::sync_run_recorder::SyncRunRecorder;
+use crate::domain::data_source::entities::sync_run::SyncRunStats;
+use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
+use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
+use crate::infrastructure::repositories::data_source_repository::DataSourceRepository;
+use crate::infrastructure::repositories::index_data_query_repository::IndexDataQueryRepository;
+use crate::queues::domain::entities::job::Job;
+use crate::queues::domain::value_objects::queue_name::QueueName;
+use crate::requests::index_data_query::insert_data_index_data_query_request::InsertDataIndexDataQueryRequest;
+use crate::state::AppState;
+
+/// Пауза между опросами пустой очереди
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Фоновый обработчик очередей синхронизации: IndexDocuments (обычная синхронизация)
+/// и ReindexAll (полная переиндексация через теневой индекс). Задачи выполняются по одной.
+pub struct SyncWorker {
+    state: AppState,
+    queue_name: QueueName,
+}
+
+impl SyncWorker {
+    pub fn new(state: AppState, queue_name: QueueName) -> Self {
+        Self { state, queue_name }
+    }
+
+    /// Бесконечный цикл обработки; запускается через tokio::spawn вместе с сервером
+    pub async fn run(self) {
+        info!("Worker for queue '{}' started", self.queue_name);
+
+        loop {
+            match self.state.job_service.get_next_job(&self.queue_name).await {
+                Ok(Some(job)) => self.process(job).await,
+                Ok(None) => tokio::time::sleep(POLL_INTERVAL).await,
+                Err(e) => {
+                    error!("Failed to fetch next job from '{}': {}", self.queue_name, e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn process(&self, job: Job) {
+        let job = match self.state.job_service.start_job(job).await {
+            Ok(job) => job,
+            Err(e) => {
+                error!("Failed to start job: {}", e);
+                return;
+            }
+        };
+
+        let result = match self.sync(&job).await {
+            Ok(()) => self.state.job_service.complete_job(job).await.map(|_| ()),
+            Err(e) => self.state.job_service.fail_job(job, e.to_string()).await,
+        };
+
+        if let Err(e) = result {
+            error!("Failed to record job result: {}", e);
+        }
+    }
+
+    /// Выполняет задачу и записывает запуск в историю синхронизаций
+    async fn sync(&self, job: &Job) -> Result<(), Error> {
+        let payload: InsertDataIndexDataQueryRequest = serde_json::from_str(&job.payload)?;
+        // Синхронизация ждёт, пока переиндексация того же запроса закончит обмен индексов, и наоборот
+        let _lock = self.state.sync_locks.acquire(payload.id).await;
+
+        let recorder = SyncRunRecorder::new((*self.state.database).clone());
+        let run_id = recorder.start(payload.id, payload.trigger).await;
+        let result = self.execute(job, &payload).await;
+        recorder.finish(run_id, &result).await;
+
+        result.map(|_| ())
+    }
+
+    async fn execute(&self, job: &Job, payload: &InsertDataIndexDataQueryRequest) -> Result<SyncRunStats, Error> {
+        let db = (*self.state.database).clone();
+        let meilisearch_client = (*self.state.meilisearch_client).clone();
+        let use_case = InsertDataIndexDataQueryRequestUseCase::new(
+            IndexDataQueryRepository::new(db.clone()),
+            DataSourceRepository::new(db.clone()),
+            meilisearch_client.clone(),
+            self.state.config.sync_batch_size,
+            self.state.config.sync_flush_bytes,
+            self.state.task_throttle(),
+            self.state.query_executor(),
+        ).await;
+
+        let report = match self.queue_name {
+            QueueName::ReindexAll => {
+                ReindexIndexDataQueryUseCase::new(IndexDataQueryRepository::new(db), use_case, meilisearch_client)
+                    .execute(payload)
+                    .await?
+            }
+            _ => use_case.execute(payload).await?,
+        };
+
+        info!(
+            "Job {:?}: synced {} rows into '{}' in {} batches",
+            job.id, report.rows_read, report.index_uid, report.batches_sent
+        );
+
+        Ok(report.stats())
+    }
+}
@@ -1,7 +1,33 @@
 use dotenv::dotenv;
 use std::env;
+use std::fmt;
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+/// Размер пачки документов при синхронизации, если SYNC_BATCH_SIZE не задан
+pub const DEFAULT_SYNC_BATCH_SIZE: u32 = 1_000;
+
+/// Размер NDJSON-пачки, после которого она отправляется в Meilisearch, если SYNC_FLUSH_BYTES не задан
+pub const DEFAULT_SYNC_FLUSH_BYTES: usize = 8 * 1024 * 1024;
+
+/// Предел незавершённых задач индекса, выше которого синхронизация ждёт, если SYNC_TASK_WATERMARK не задан
+pub const DEFAULT_SYNC_TASK_WATERMARK: u64 = 20;
+
+/// Предел незавершённых задач одной синхронизации, если SYNC_MAX_IN_FLIGHT_TASKS не задан
+pub const DEFAULT_SYNC_MAX_IN_FLIGHT_TASKS: usize = 4;
+
+/// Предел времени одной инструкции SQL в источнике, если QUERY_TIMEOUT_SECS не задан
+pub const DEFAULT_QUERY_TIMEOUT_SECS: u64 = 30;
+
+/// Предел подключений в пуле одного источника, если POOL_MAX_CONNECTIONS не задан
+pub const DEFAULT_POOL_MAX_CONNECTIONS: u32 = 5;
+
+/// Через сколько секунд простоя закрывается пул источника, если POOL_IDLE_TIMEOUT_SECS не задан
+pub const DEFAULT_POOL_IDLE_TIMEOUT_SECS: u64 = 300;
+
+/// Каталог файловых источников, если FILE_SOURCES_ROOT не задан
+pub const DEFAULT_FILE_SOURCES_ROOT: &str = "files";
+
+#[derive(Clone)]
 pub struct ApplicationConfig {
     pub meilisearch_host: String,
     pub meilisearch_key: String,
@@ -10,6 +36,17 @@ pub struct ApplicationConfig {
     pub db_name: String,
     pub db_user: String,
     pub db_pass: String,
+    pub sync_batch_size: u32,
+    pub sync_flush_bytes: usize,
+    pub sync_task_watermark: u64,
+    pub sync_max_in_flight_tasks: usize,
+    pub query_timeout_secs: u64,
+    pub pool_max_connections: u32,
+    pub pool_idle_timeout_secs: u64,
+    /// Каталог, внутри которого лежат файлы источников `database_type = "file"`
+    pub file_sources_root: String,
+    /// Мастер-ключ шифрования паролей источников (32 байта в base64)
+    pub credentials_master_key: String,
 }
 
 #[derive(Debug)]
@@ -42,9 +79,65 @@ impl ApplicationConfig {
             db_pass: env::var("DB_PASS").map_err(|_| ConfigError {
                 message: "DB_PASS not found in environment".to_string(),
             })?,
+            sync_batch_size: match env::var("SYNC_BATCH_SIZE") {
+                Ok(value) => value.parse::<u32>().ok().filter(|size| *size > 0).ok_or(ConfigError {
+                    message: "SYNC_BATCH_SIZE must be a positive integer".to_string(),
+                })?,
+                Err(_) => DEFAULT_SYNC_BATCH_SIZE,
+            },
+            sync_flush_bytes: match env::var("SYNC_FLUSH_BYTES") {
+                Ok(value) => value.parse::<usize>().ok().filter(|size| *size > 0).ok_or(ConfigError {
+                    message: "SYNC_FLUSH_BYTES must be a positive integer".to_string(),
+                })?,
+                Err(_) => DEFAULT_SYNC_FLUSH_BYTES,
+            },
+            sync_task_watermark: match env::var("SYNC_TASK_WATERMARK") {
+                Ok(value) => value.parse::<u64>().ok().filter(|count| *count > 0).ok_or(ConfigError {
+                    message: "SYNC_TASK_WATERMARK must be a positive integer".to_string(),
+                })?,
+                Err(_) => DEFAULT_SYNC_TASK_WATERMARK,
+            },
+            sync_max_in_flight_tasks: match env::var("SYNC_MAX_IN_FLIGHT_TASKS") {
+                Ok(value) => value.parse::<usize>().ok().filter(|count| *count > 0).ok_or(ConfigError {
+                    message: "SYNC_MAX_IN_FLIGHT_TASKS must be a positive integer".to_string(),
+                })?,
+                Err(_) => DEFAULT_SYNC_MAX_IN_FLIGHT_TASKS,
+            },
+            query_timeout_secs: match env::var("QUERY_TIMEOUT_SECS") {
+                Ok(value) => value.parse::<u64>().ok().filter(|secs| *secs > 0).ok_or(ConfigError {
+                    message: "QUERY_TIMEOUT_SECS must be a positive integer".to_string(),
+                })?,
+                Err(_) => DEFAULT_QUERY_TIMEOUT_SECS,
+            },
+            pool_max_connections: match env::var("POOL_MAX_CONNECTIONS") {
+                Ok(value) => value.parse::<u32>().ok().filter(|size| *size > 0).ok_or(ConfigError {
+                    message: "POOL_MAX_CONNECTIONS must be a positive integer".to_string(),
+                })?,
+                Err(_) => DEFAULT_POOL_MAX_CONNECTIONS,
+            },
+            pool_idle_timeout_secs: match env::var("POOL_IDLE_TIMEOUT_SECS") {
+                Ok(value) => value.parse::<u64>().ok().filter(|secs| *secs > 0).ok_or(ConfigError {
+                    message: "POOL_IDLE_TIMEOUT_SECS must be a positive integer".to_string(),
+                })?,
+                Err(_) => DEFAULT_POOL_IDLE_TIMEOUT_SECS,
+            },
+            file_sources_root: env::var("FILE_SOURCES_ROOT").unwrap_or_else(|_| DEFAULT_FILE_SOURCES_ROOT.to_string()),
+            credentials_master_key: env::var("CREDENTIALS_MASTER_KEY").map_err(|_| ConfigError {
+                message: "CREDENTIALS_MASTER_KEY not found in environment".to_string(),
+            })?,
         })
     }
 
+    /// statement_timeout для запросов к источникам данных
+    pub fn query_timeout(&self) -> Duration {
+        Duration::from_secs(self.query_timeout_secs)
+    }
+
+    /// Время простоя, после которого закрывается пул источника
+    pub fn pool_idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.pool_idle_timeout_secs)
+    }
+
     pub fn get_meilisearch_url(&self) -> String {
         format!("{}:{}", self.meilisearch_host, self.meilisearch_port)
     }
@@ -60,4 +153,28 @@ impl ApplicationConfig {
     pub fn get_db_pass(&self) -> String {
         format!("{}", self.db_pass,)
     }
-}
\ No newline at end of file
+}
+
+/// Ключи и пароли из окружения не выводятся
+impl fmt::Debug for ApplicationConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApplicationConfig")
+            .field("meilisearch_host", &self.meilisearch_host)
+            .field("meilisearch_key", &"***")
+            .field("meilisearch_port", &self.meilisearch_port)
+            .field("db_path", &self.db_path)
+            .field("db_name", &self.db_name)
+            .field("db_user", &self.db_user)
+            .field("db_pass", &"***")
+            .field("sync_batch_size", &self.sync_batch_size)
+            .field("sync_flush_bytes", &self.sync_flush_bytes)
+            .field("sync_task_watermark", &self.sync_task_watermark)
+            .field("sync_max_in_flight_tasks", &self.sync_max_in_flight_tasks)
+            .field("query_timeout_secs", &self.query_timeout_secs)
+            .field("pool_max_connections", &self.pool_max_connections)
+            .field("pool_idle_timeout_secs", &self.pool_idle_timeout_secs)
+            .field("file_sources_root", &self.file_sources_root)
+            .field("credentials_master_key", &"***")
+            .finish()
+    }
+}
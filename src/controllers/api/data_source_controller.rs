@@ -9,6 +9,10 @@ use sqlx::{query, query_as};
 use serde_json::json;
 use crate::requests::data_source::test_data_source_request::TestDataSourceRequest;
 use crate::services::data_source_service::DataSourceService;
+use crate::domain::data_source::services::credential_cipher::CredentialCipher;
+use crate::application::use_cases::data_source::introspect_data_source_use_case::IntrospectDataSourceUseCase;
+use crate::infrastructure::repositories::data_source_repository::DataSourceRepository;
+use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
 
 pub struct DataSourceController {}
 
@@ -97,50 +101,37 @@ impl DataSourceController {
             );
         }
 
-        if payload.host.is_empty() {
-            println!("❌ Validation error: host is empty");
-            return (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(json!({
-                    "code": 422,
-                    "success": false,
-                    "message": "Validation failed",
-                    "errors": {
-                        "host": ["Host field is required and cannot be empty"]
-                    }
-                })),
-            );
-        }
-
-        if payload.port == 0 || payload.port > 65535 {
-            println!("❌ Validation error: invalid port {}", payload.port);
-            return (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(json!({
-                    "code": 422,
-                    "success": false,
-                    "message": "Validation failed",
-                    "errors": {
-                        "port": ["Port must be between 1 and 65535"]
-                    }
-                })),
-            );
-        }
+        // Обязательные поля и их формат описывает коннектор типа источника
+        let connector = match state.connectors.require(&payload.database_type) {
+            Ok(connector) => connector,
+            Err(e) => {
+                println!("❌ Validation error: {}", e);
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(json!({
+                        "code": 422,
+                        "success": false,
+                        "message": "Validation failed",
+                        "errors": {
+                            "database_type": [e]
+                        }
+                    })),
+                );
+            }
+        };
 
-        if !["sqlite", "mysql", "postgresql"].contains(&payload.database_type.as_str()) {
-            println!(
-                "❌ Validation error: invalid database_type {}",
-                payload.database_type
-            );
+        let errors = connector
+            .descriptor()
+            .validate(&serde_json::to_value(&payload).unwrap_or_default());
+        if !errors.is_empty() {
+            println!("❌ Validation error: {:?}", errors);
             return (
                 StatusCode::UNPROCESSABLE_ENTITY,
                 Json(json!({
                     "code": 422,
                     "success": false,
                     "message": "Validation failed",
-                    "errors": {
-                        "database_type": ["Database type must be sqlite, mysql, or postgresql"]
-                    }
+                    "errors": errors
                 })),
             );
         }
@@ -161,7 +152,39 @@ impl DataSourceController {
             }
         };
 
-        let sql = "INSERT INTO data_sources (name, host, database, username, password, port, database_path, database_name, database_type) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)";
+        // Пароль и заголовки HTTP хранятся зашифрованными; расшифровываются только при подключении к источнику
+        let encrypted = CredentialCipher::installed().and_then(|cipher| {
+            let http_options = payload
+                .http_options
+                .as_ref()
+                .map(|http_options| http_options.encrypted(cipher))
+                .transpose()?;
+            Ok((cipher.encrypt(&payload.password)?, http_options))
+        });
+        let (password, http_options) = match encrypted {
+            Ok(encrypted) => encrypted,
+            Err(e) => {
+                eprintln!("{} {}", "❌ Failed to encrypt credentials: ".color("Red"), e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "code": 500,
+                        "success": false,
+                        "message": "Failed to encrypt credentials",
+                        "error": e
+                    })),
+                );
+            }
+        };
+
+        let file_options = payload
+            .file_options
+            .as_ref()
+            .map(|file_options| serde_json::to_string(file_options).unwrap_or_default());
+        let http_options = http_options
+            .as_ref()
+            .map(|http_options| serde_json::to_string(http_options).unwrap_or_default());
+        let sql = "INSERT INTO data_sources (name, host, database, username, password, port, database_path, database_name, database_type, file_options, http_options) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
 
         // Выполняем запрос с использованием sqlx
         let result = query(sql)
@@ -169,11 +192,13 @@ impl DataSourceController {
             .bind(&payload.host)
             .bind(&payload.database)
             .bind(&payload.username)
-            .bind(&payload.password)
+            .bind(&password)
             .bind(payload.port)
             .bind(&payload.database_path)
             .bind(&payload.database_name)
             .bind(&payload.database_type)
+            .bind(file_options)
+            .bind(http_options)
             .execute(pool)
             .await;
 
@@ -246,6 +271,10 @@ impl DataSourceController {
                 // Проверяем, была ли удалена хотя бы одна строка
                 let rows_affected = result.rows_affected();
                 if rows_affected > 0 {
+                    // Пул удалённого источника больше не нужен
+                    if let Ok(data_source_id) = id.parse::<u32>() {
+                        state.connections.invalidate(data_source_id);
+                    }
                     // Успешное удаление
                     (
                         StatusCode::OK,
@@ -366,6 +395,59 @@ impl DataSourceController {
 
     pub async fn update() {}
 
+    /// Состояние пулов подключений к источникам данных
+    pub async fn connections(State(state): State<AppState>) -> impl IntoResponse {
+        (
+            StatusCode::OK,
+            Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Connection pools",
+                "result": state.connections.stats()
+            })),
+        )
+    }
+
+    /// Типы источников и поля их формы создания
+    pub async fn connectors(State(state): State<AppState>) -> impl IntoResponse {
+        (
+            StatusCode::OK,
+            Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Data source connectors",
+                "result": state.connectors.descriptors()
+            })),
+        )
+    }
+
+    /// Таблицы (файлы, эндпоинты) источника и их колонки
+    pub async fn introspect(Path(id): Path<u32>, State(state): State<AppState>) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let use_case = IntrospectDataSourceUseCase::new(DataSourceRepository::new(db), state.query_executor());
+
+        match use_case.execute(id).await {
+            Ok(objects) => (
+                StatusCode::OK,
+                Json(json!({
+                    "code": 200,
+                    "success": true,
+                    "message": "Data source introspected",
+                    "result": objects
+                })),
+            ),
+            Err(e) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "code": 422,
+                    "success": false,
+                    "message": "Failed to introspect data source",
+                    "error": format!("{}", e)
+                })),
+            ),
+        }
+    }
+
     pub async fn test(
         State(state): State<AppState>,
         Json(payload): Json<TestDataSourceRequest>,
@@ -387,7 +469,7 @@ impl DataSourceController {
         };
 
         // Создаем сервис с клонированным пулом соединений
-        let data_source_service = DataSourceService::new(pool.clone());
+        let data_source_service = DataSourceService::new(pool.clone(), state.query_executor());
 
         // Вызываем метод test_data_source и ждем результат
         match data_source_service.test_data_source(payload).await {
@@ -1,6 +1,26 @@
-use sqlx::{sqlite::{SqlitePool, SqlitePoolOptions}, Sqlite, Pool, query};
+use sqlx::{sqlite::{SqlitePool, SqlitePoolOptions}, Sqlite, Pool, query, Row};
 use anyhow::Result;
 
+/// Колонки таблицы источников данных. Порт 0 — у источников без порта (SQLite, файлы, HTTP API),
+/// `file_options` и `http_options` — JSON FileSourceOptions и HttpSourceOptions.
+/// Допустимые `database_type` определяет ConnectorRegistry, а не CHECK в схеме.
+const DATA_SOURCES_COLUMNS: &str = "
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    host TEXT NOT NULL,
+    database TEXT NOT NULL,
+    username TEXT NOT NULL,
+    password TEXT NOT NULL,
+    port INTEGER NOT NULL CHECK(port >= 0 AND port <= 65535),
+    database_path TEXT NOT NULL,
+    database_name TEXT NOT NULL,
+    database_type TEXT NOT NULL,
+    file_options TEXT,
+    http_options TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+";
+
 /// Основная структура для работы с базой данных
 /// Инкапсулирует все операции с SQLite
 #[derive(Debug, Clone)]
@@ -32,30 +52,62 @@ impl Database {
         
 
         // Create data_sources table
+        query(&format!("CREATE TABLE IF NOT EXISTS data_sources ({});", DATA_SOURCES_COLUMNS))
+            .execute(&self.pool)
+            .await?;
+        self.relax_data_sources_checks().await?;
+
+        query(
+            "CREATE TABLE IF NOT EXISTS index_data_queries (id INTEGER PRIMARY KEY, data_source_id int, index_uid TEXT, query TEXT, created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now')))"
+        )
+            .execute(&self.pool)
+            .await?;
+
+        // Инкрементальная синхронизация: курсорная колонка и последняя отметка
+        self.ensure_column("index_data_queries", "cursor_column", "TEXT").await?;
+        self.ensure_column("index_data_queries", "cursor_value", "TEXT").await?;
+        // Сверка удалений: включена ли и какую долю документов можно удалить за запуск
+        self.ensure_column("index_data_queries", "delete_orphans", "INTEGER NOT NULL DEFAULT 0").await?;
+        self.ensure_column("index_data_queries", "max_delete_ratio", "REAL NOT NULL DEFAULT 0.1").await?;
+        // Расписание: cron-выражение и часовой пояс
+        self.ensure_column("index_data_queries", "cron_expression", "TEXT").await?;
+        self.ensure_column("index_data_queries", "timezone", "TEXT").await?;
+        // Преобразование строк в документы (JSON TransformationSpec)
+        self.ensure_column("index_data_queries", "transformation", "TEXT").await?;
+        // Первичный ключ документов и шаблон составного ключа
+        self.ensure_column("index_data_queries", "primary_key", "TEXT NOT NULL DEFAULT 'id'").await?;
+        self.ensure_column("index_data_queries", "primary_key_template", "TEXT").await?;
+        // Канал LISTEN/NOTIFY для синхронизации почти в реальном времени
+        self.ensure_column("index_data_queries", "notify_channel", "TEXT").await?;
+        // Добавлять к датам поле с секундами эпохи для сортировки
+        self.ensure_column("index_data_queries", "epoch_timestamps", "INTEGER NOT NULL DEFAULT 0").await?;
+        // Значения по умолчанию для именованных параметров (:name) запроса, JSON-объект
+        self.ensure_column("index_data_queries", "params", "TEXT").await?;
+        // Дочерние запросы для вложенных массивов документа, JSON-массив ChildQuery
+        self.ensure_column("index_data_queries", "child_queries", "TEXT").await?;
+
+        // История запусков синхронизации
         query(
-            "CREATE TABLE IF NOT EXISTS data_sources (
+            "CREATE TABLE IF NOT EXISTS sync_runs (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                host TEXT NOT NULL,
-                database TEXT NOT NULL,
-                username TEXT NOT NULL,
-                password TEXT NOT NULL,
-                port INTEGER NOT NULL CHECK(port > 0 AND port <= 65535),
-                database_path TEXT NOT NULL,
-                database_name TEXT NOT NULL,
-                database_type TEXT NOT NULL CHECK(database_type IN ('sqlite', 'mysql', 'postgresql')),
-                created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+                index_data_query_id INTEGER NOT NULL,
+                trigger_source TEXT NOT NULL,
+                status TEXT NOT NULL,
+                started_at TEXT NOT NULL DEFAULT (datetime('now')),
+                finished_at TEXT,
+                rows_read INTEGER NOT NULL DEFAULT 0,
+                documents_sent INTEGER NOT NULL DEFAULT 0,
+                task_uids TEXT NOT NULL DEFAULT '[]',
+                error TEXT
             );"
         )
         .execute(&self.pool)
         .await?;
-
-        query(
-            "CREATE TABLE IF NOT EXISTS index_data_queries (id INTEGER PRIMARY KEY, data_source_id int, index_uid TEXT, query TEXT, created_at TEXT NOT NULL DEFAULT (datetime('now')), updated_at TEXT NOT NULL DEFAULT (datetime('now')))"
-        )
+        query("CREATE INDEX IF NOT EXISTS idx_sync_runs_query ON sync_runs (index_data_query_id, id)")
             .execute(&self.pool)
             .await?;
+        // Время ожидания очереди задач Meilisearch (обратное давление)
+        self.ensure_column("sync_runs", "throttled_ms", "INTEGER NOT NULL DEFAULT 0").await?;
 
         let migration_sql = include_str!("./modules/queue/storage/migrations/001_initial.sql");
         let mut transaction = self.pool.begin().await?;
@@ -69,6 +121,57 @@ impl Database {
         Ok(())
     }
 
+    /// Старая схема data_sources запрещала порт 0 (у SQLite, файлов и HTTP API нет порта)
+    /// и перечисляла типы источников в CHECK. CHECK в SQLite не изменить через ALTER TABLE,
+    /// поэтому таблица пересоздаётся с теми же id.
+    async fn relax_data_sources_checks(&self) -> Result<(), anyhow::Error> {
+        let schema: String = query("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'data_sources'")
+            .fetch_one(&self.pool)
+            .await?
+            .get("sql");
+        if !schema.contains("database_type IN") {
+            return Ok(());
+        }
+
+        let columns = query("PRAGMA table_info(data_sources)")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(|row| row.get::<String, _>("name"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let mut transaction = self.pool.begin().await?;
+        query(&format!("CREATE TABLE data_sources_new ({});", DATA_SOURCES_COLUMNS))
+            .execute(&mut *transaction)
+            .await?;
+        query(&format!("INSERT INTO data_sources_new ({columns}) SELECT {columns} FROM data_sources", columns = columns))
+            .execute(&mut *transaction)
+            .await?;
+        query("DROP TABLE data_sources").execute(&mut *transaction).await?;
+        query("ALTER TABLE data_sources_new RENAME TO data_sources").execute(&mut *transaction).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Добавляет колонку в существующую таблицу, если её ещё нет
+    /// SQLite не поддерживает ADD COLUMN IF NOT EXISTS, поэтому сверяемся с PRAGMA table_info
+    async fn ensure_column(&self, table: &str, column: &str, definition: &str) -> Result<(), anyhow::Error> {
+        let columns = query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(&self.pool)
+            .await?;
+
+        if columns.iter().any(|row| row.get::<String, _>("name") == column) {
+            return Ok(());
+        }
+
+        query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn get_pool_connection(&self) -> Result<&Pool<Sqlite>, anyhow::Error> {
         Ok(&self.pool)
     }
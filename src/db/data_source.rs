@@ -1,7 +1,10 @@
+use std::fmt;
 use anyhow::{Context, Result};
 use sqlx::{Pool, Sqlite, query, query_as, FromRow};
+use crate::domain::data_source::entities::secret::Secret;
+use crate::domain::data_source::services::credential_cipher::CredentialCipher;
 
-#[derive(Debug, FromRow)]
+#[derive(FromRow)]
 pub struct DataSource {
     pub id: u32,
     pub name: String,
@@ -21,12 +24,16 @@ impl DataSource {
     pub async fn store(&self, pool: &Pool<Sqlite>) -> Result<i32, anyhow::Error> {
         let sql = "INSERT INTO data_sources (name, host, database, username, password, port, database_path, database_name, database_type, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
 
+        let password = CredentialCipher::installed()
+            .and_then(|cipher| cipher.encrypt(&self.password))
+            .map_err(anyhow::Error::msg)?;
+
         let result = query(sql)
             .bind(&self.name)
             .bind(&self.host)
             .bind(&self.database)
             .bind(&self.username)
-            .bind(&self.password)
+            .bind(&password)
             .bind(self.port)
             .bind(&self.database_path)
             .bind(&self.database_name)
@@ -53,3 +60,23 @@ impl DataSource {
         Ok(data_sources)
     }
 }
+
+/// Пароль не выводится даже в зашифрованном виде
+impl fmt::Debug for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataSource")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("host", &self.host)
+            .field("database", &self.database)
+            .field("username", &self.username)
+            .field("password", &Secret::from_stored(self.password.clone()))
+            .field("port", &self.port)
+            .field("database_path", &self.database_path)
+            .field("database_name", &self.database_name)
+            .field("database_type", &self.database_type)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .finish()
+    }
+}
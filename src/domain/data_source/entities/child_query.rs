@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// Дочерний запрос: строки связанной таблицы (один-ко-многим), которые собираются
+/// в массив внутри родительского документа, например `variants[]` или `tags[]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildQuery {
+    /// Поле документа, в которое попадает массив дочерних строк
+    pub field: String,
+    /// Запрос с параметром `:ids` — значениями ключа родительских строк пачки,
+    /// например `SELECT product_id, name FROM variants WHERE product_id = ANY(:ids)`;
+    /// в MySQL массив раскрывается в список, поэтому там пишется `product_id IN (:ids)`
+    pub query: String,
+    /// Колонка дочерних строк со ссылкой на родителя
+    pub parent_column: String,
+    /// Поле родительской строки, значения которого передаются в `:ids`; по умолчанию — первичный ключ
+    #[serde(default)]
+    pub parent_key: Option<String>,
+}
@@ -1,3 +1,6 @@
+use crate::domain::data_source::entities::file_source_options::FileSourceOptions;
+use crate::domain::data_source::entities::http_source_options::HttpSourceOptions;
+use crate::domain::data_source::entities::secret::Secret;
 use crate::domain::data_source::errors::data_source_error::DataSourceError;
 
 #[derive(Debug, Clone)]
@@ -7,11 +10,16 @@ pub struct DataSource {
     pub host: String,
     pub database: String,
     pub username: String,
-    pub password: String,
+    /// Хранится зашифрованным; открытое значение — только при подключении
+    pub password: Secret,
     pub port: u16,
     pub database_path: String,
     pub database_name: String,
     pub database_type: String,
+    /// Настройки чтения для файловых источников
+    pub file_options: FileSourceOptions,
+    /// Заголовки, путь к записям и пагинация для HTTP-источников
+    pub http_options: HttpSourceOptions,
 }
 
 
@@ -34,11 +42,13 @@ impl DataSource {
             host,
             database,
             username,
-            password,
+            password: Secret::from_stored(password),
             port,
             database_path,
             database_name,
             database_type,
+            file_options: FileSourceOptions::default(),
+            http_options: HttpSourceOptions::default(),
         })
     }
 }
\ No newline at end of file
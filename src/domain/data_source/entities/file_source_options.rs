@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// Формат файлов файлового источника
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileFormat {
+    /// CSV со строкой заголовков
+    Csv,
+    /// JSON-массив объектов
+    Json,
+    /// Один JSON-объект на строку
+    Ndjson,
+}
+
+/// Настройки чтения файлового источника (`database_type = "file"`).
+/// Путь или glob-шаблон файлов хранится в `database_path`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileSourceOptions {
+    /// Формат; если не задан, определяется по расширению каждого файла
+    #[serde(default)]
+    pub format: Option<FileFormat>,
+    /// Разделитель полей CSV, по умолчанию `,`
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    /// Кавычка CSV, по умолчанию `"`
+    #[serde(default)]
+    pub quote: Option<char>,
+    /// Символ экранирования кавычки внутри поля CSV; без него кавычка удваивается (`""`)
+    #[serde(default)]
+    pub escape: Option<char>,
+    /// Оставить значения CSV строками, не угадывая числа и логические значения
+    #[serde(default)]
+    pub keep_strings: bool,
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::domain::data_source::entities::secret::Secret;
+use crate::domain::data_source::services::credential_cipher::CredentialCipher;
+
+/// Как перейти к следующей странице ответа HTTP API
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HttpPagination {
+    /// Все записи приходят одним ответом
+    #[default]
+    None,
+    /// Номер страницы в параметре `param`, начиная со `start`; пустая страница или повтор предыдущей — конец
+    PageNumber {
+        #[serde(default = "default_page_param")]
+        param: String,
+        #[serde(default = "default_page_start")]
+        start: u64,
+        /// Параметр и значение размера страницы, если API его принимает
+        #[serde(default)]
+        size_param: Option<String>,
+        #[serde(default)]
+        size: Option<u32>,
+    },
+    /// Смещение в `offset_param` и размер страницы `limit` в `limit_param`;
+    /// страница короче `limit` или повтор предыдущей — конец
+    Offset {
+        #[serde(default = "default_offset_param")]
+        offset_param: String,
+        #[serde(default = "default_limit_param")]
+        limit_param: String,
+        #[serde(default = "default_limit")]
+        limit: u32,
+    },
+    /// Курсор следующей страницы из ответа (JSONPath `cursor_path`) передаётся в параметре `param`;
+    /// пустой курсор — конец
+    Cursor {
+        cursor_path: String,
+        #[serde(default = "default_cursor_param")]
+        param: String,
+    },
+    /// Адрес следующей страницы из заголовка `Link` с `rel="next"` (RFC 8288)
+    LinkHeader,
+}
+
+fn default_page_param() -> String {
+    "page".to_string()
+}
+
+fn default_page_start() -> u64 {
+    1
+}
+
+fn default_offset_param() -> String {
+    "offset".to_string()
+}
+
+fn default_limit_param() -> String {
+    "limit".to_string()
+}
+
+fn default_limit() -> u32 {
+    100
+}
+
+fn default_cursor_param() -> String {
+    "cursor".to_string()
+}
+
+/// Настройки HTTP-источника (`database_type = "http"`). Базовый URL хранится в `database_path`,
+/// bearer-токен — в `password`, чтобы он хранился и скрывался как секрет.
+/// Debug не показывает значения заголовков: они выводятся через Secret.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HttpSourceOptions {
+    /// Дополнительные заголовки каждого запроса. Значения часто несут ключи (`X-Api-Key`, `Cookie`),
+    /// поэтому хранятся зашифрованными, как и пароль
+    #[serde(default)]
+    pub headers: BTreeMap<String, Secret>,
+    /// JSONPath к массиву записей в ответе (`$.data.items`); по умолчанию весь ответ — массив
+    #[serde(default)]
+    pub records_path: Option<String>,
+    #[serde(default)]
+    pub pagination: HttpPagination,
+    /// Предел числа страниц за одно чтение — защита от API, которое не сообщает о конце
+    #[serde(default)]
+    pub max_pages: Option<u32>,
+}
+
+impl HttpSourceOptions {
+    /// Настройки для сохранения: значения заголовков зашифрованы
+    pub fn encrypted(&self, cipher: &CredentialCipher) -> Result<HttpSourceOptions, String> {
+        self.map_headers(|value| value.encrypted(cipher))
+    }
+
+    /// Значения заголовков перешифрованы с ключа `current` на `next`
+    pub fn rotated(&self, current: &CredentialCipher, next: &CredentialCipher) -> Result<HttpSourceOptions, String> {
+        self.map_headers(|value| value.rotated(current, next))
+    }
+
+    fn map_headers(&self, map: impl Fn(&Secret) -> Result<Secret, String>) -> Result<HttpSourceOptions, String> {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), map(value)?)))
+            .collect::<Result<BTreeMap<String, Secret>, String>>()?;
+        Ok(HttpSourceOptions { headers, ..self.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+
+    #[test]
+    fn headers_are_stored_encrypted_and_hidden_from_debug() {
+        let options: HttpSourceOptions = serde_json::from_str(r#"{"headers": {"X-Api-Key": "k3y"}}"#).unwrap();
+        assert!(!format!("{:?}", options).contains("k3y"));
+
+        let cipher = CredentialCipher::from_base64(KEY).unwrap();
+        let stored = serde_json::to_string(&options.encrypted(&cipher).unwrap()).unwrap();
+        assert!(!stored.contains("k3y"));
+
+        let restored: HttpSourceOptions = serde_json::from_str(&stored).unwrap();
+        let value = serde_json::to_value(&restored.headers["X-Api-Key"]).unwrap();
+        assert_eq!(cipher.decrypt(value.as_str().unwrap()).unwrap(), "k3y");
+    }
+}
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::domain::data_source::entities::child_query::ChildQuery;
+use crate::domain::data_source::entities::transformation_spec::TransformationSpec;
+use crate::domain::data_source::services::named_params::QueryParams;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct IndexDataQuery {
@@ -6,4 +9,31 @@ pub struct IndexDataQuery {
     pub data_source_id: u32,
     pub index_uid: String,
     pub query: String,
+    /// Колонка для инкрементальной синхронизации (например, updated_at).
+    /// Должна расти в порядке фиксации транзакций, см. CursorWindow
+    pub cursor_column: Option<String>,
+    /// Последняя синхронизированная отметка курсора
+    pub cursor_value: Option<String>,
+    /// Удалять из индекса документы, которых больше нет в источнике
+    pub delete_orphans: bool,
+    /// Максимальная доля документов индекса, которую можно удалить за один запуск
+    pub max_delete_ratio: f64,
+    /// Cron-выражение для автоматической синхронизации (None — только вручную)
+    pub cron_expression: Option<String>,
+    /// Часовой пояс расписания (IANA, например Europe/Moscow); по умолчанию UTC
+    pub timezone: Option<String>,
+    /// Преобразование строк результата в документы индекса
+    pub transformation: Option<TransformationSpec>,
+    /// Поле первичного ключа документов в индексе
+    pub primary_key: String,
+    /// Шаблон составного ключа, например `{tenant_id}-{order_id}`
+    pub primary_key_template: Option<String>,
+    /// Канал Postgres NOTIFY; если задан, сервер слушает его и обновляет изменённые строки
+    pub notify_channel: Option<String>,
+    /// Добавлять к TIMESTAMP/TIMESTAMPTZ/DATE поле `<колонка>_epoch` (секунды) для сортировки
+    pub epoch_timestamps: bool,
+    /// Значения по умолчанию для параметров `:name` в тексте запроса
+    pub params: QueryParams,
+    /// Дочерние запросы, строки которых вкладываются в документ массивами
+    pub child_queries: Vec<ChildQuery>,
 }
\ No newline at end of file
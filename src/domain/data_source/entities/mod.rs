@@ -1,2 +1,9 @@
 pub mod data_source;
-pub mod index_data_query;
\ No newline at end of file
+pub mod index_data_query;
+pub mod transformation_spec;
+
+pub mod sync_run;
+pub mod child_query;
+pub mod file_source_options;
+pub mod http_source_options;
+pub mod secret;
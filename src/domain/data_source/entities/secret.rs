@@ -0,0 +1,49 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::domain::data_source::services::credential_cipher::CredentialCipher;
+
+/// Пароль или токен источника в том виде, в каком он хранится в `data_sources.password`.
+/// Расшифровывается только при подключении (`reveal`); в Debug и логах не показывается.
+/// В JSON (например, в `http_options`) пишется сохранённое значение, то есть шифротекст.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Значение из базы: зашифрованное или старое, открытым текстом
+    pub fn from_stored(stored: String) -> Self {
+        Self(stored)
+    }
+
+    /// Секрет не задан
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Секрет для сохранения: открытое значение шифруется, уже зашифрованное остаётся как есть
+    pub fn encrypted(&self, cipher: &CredentialCipher) -> Result<Secret, String> {
+        if CredentialCipher::is_encrypted(&self.0) {
+            return Ok(self.clone());
+        }
+        Ok(Self(cipher.encrypt(&self.0)?))
+    }
+
+    /// Тот же секрет под другим ключом: расшифровывается `current` и шифруется `next`
+    pub fn rotated(&self, current: &CredentialCipher, next: &CredentialCipher) -> Result<Secret, String> {
+        Ok(Self(next.encrypt(&current.decrypt(&self.0)?)?))
+    }
+
+    /// Открытое значение для строки подключения или заголовка авторизации
+    pub fn reveal(&self) -> Result<String, String> {
+        if self.is_empty() || !CredentialCipher::is_encrypted(&self.0) {
+            return Ok(self.0.clone());
+        }
+        CredentialCipher::installed()?.decrypt(&self.0)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.is_empty() { "\"\"" } else { "\"***\"" })
+    }
+}
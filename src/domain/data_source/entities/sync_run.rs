@@ -0,0 +1,92 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Что запустило синхронизацию
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncTrigger {
+    /// Запрос к API insert-data
+    #[default]
+    Manual,
+    /// Cron-расписание запроса
+    Schedule,
+    /// Полная переиндексация через теневой индекс
+    Reindex,
+    /// Уведомление Postgres NOTIFY
+    Notify,
+}
+
+impl SyncTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncTrigger::Manual => "manual",
+            SyncTrigger::Schedule => "schedule",
+            SyncTrigger::Reindex => "reindex",
+            SyncTrigger::Notify => "notify",
+        }
+    }
+}
+
+/// Состояние запуска синхронизации
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl SyncRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncRunStatus::Running => "running",
+            SyncRunStatus::Succeeded => "succeeded",
+            SyncRunStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Запись истории синхронизаций (таблица sync_runs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRun {
+    pub id: u32,
+    pub index_data_query_id: u32,
+    pub trigger: String,
+    pub status: String,
+    pub started_at: String,
+    /// None, пока запуск выполняется
+    pub finished_at: Option<String>,
+    pub rows_read: u64,
+    pub documents_sent: u64,
+    /// Задачи Meilisearch, созданные запуском
+    pub task_uids: Vec<u32>,
+    /// Текст ошибки для неудачного запуска
+    pub error: Option<String>,
+    /// Сколько отправка ждала разгрузки очереди задач Meilisearch
+    pub throttled_ms: u64,
+}
+
+/// Итог запуска, который записывается в историю
+#[derive(Debug, Clone, Default)]
+pub struct SyncRunStats {
+    pub rows_read: u64,
+    pub documents_sent: u64,
+    pub task_uids: Vec<u32>,
+    pub throttled_ms: u64,
+}
+
+/// Ошибка запуска вместе с тем, что он успел сделать до неё: прочитанные строки,
+/// отправленные документы и созданные задачи попадают в историю и у неудачного запуска
+#[derive(Debug)]
+pub struct PartialSyncError {
+    pub stats: SyncRunStats,
+    pub message: String,
+}
+
+impl fmt::Display for PartialSyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for PartialSyncError {}
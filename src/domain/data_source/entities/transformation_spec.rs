@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// Описание преобразования строки результата запроса в документ индекса.
+/// Шаги применяются в порядке: rename → concat → split → cast → drop → nest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformationSpec {
+    /// Переименование полей: старое имя → новое
+    #[serde(default)]
+    pub rename: BTreeMap<String, String>,
+    /// Склейка нескольких полей в одно строковое
+    #[serde(default)]
+    pub concat: Vec<ConcatRule>,
+    /// Разбиение строки по разделителю в массив: поле → разделитель
+    #[serde(default)]
+    pub split: BTreeMap<String, String>,
+    /// Приведение типов: поле → тип
+    #[serde(default)]
+    pub cast: BTreeMap<String, CastType>,
+    /// Поля, которые не попадают в документ
+    #[serde(default)]
+    pub drop: Vec<String>,
+    /// Собирать вложенные объекты из имён с точкой (`author.name` → `{"author": {"name": ...}}`)
+    #[serde(default = "default_nest")]
+    pub nest: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcatRule {
+    /// Имя итогового поля
+    pub target: String,
+    /// Исходные поля; null-значения пропускаются
+    pub fields: Vec<String>,
+    #[serde(default = "default_separator")]
+    pub separator: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CastType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+}
+
+fn default_nest() -> bool {
+    true
+}
+
+fn default_separator() -> String {
+    " ".to_string()
+}
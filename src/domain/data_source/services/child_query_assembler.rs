@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+use serde_json::Value;
+use crate::domain::data_source::entities::child_query::ChildQuery;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::index_document_reader::IndexDocumentReader;
+use crate::domain::data_source::services::named_params::NamedParams;
+use crate::domain::data_source::services::query_executor::QueryExecutor;
+
+/// Параметр дочернего запроса со значениями ключа родительских строк пачки
+pub const CHILD_IDS_PARAM: &str = "ids";
+
+/// Вложенные документы: каждый дочерний запрос выполняется один раз на пачку,
+/// его строки группируются по ссылке на родителя и попадают в документ массивом
+pub struct ChildQueryAssembler;
+
+impl ChildQueryAssembler {
+    pub fn validate(child_queries: &[ChildQuery], key: &DocumentKey) -> Result<(), String> {
+        let mut fields = HashSet::new();
+        for child in child_queries {
+            if child.field.trim().is_empty() {
+                return Err("Child query field must not be empty".to_string());
+            }
+            if !fields.insert(child.field.as_str()) {
+                return Err(format!("Duplicate child query field '{}'", child.field));
+            }
+            if child.parent_column.trim().is_empty() {
+                return Err(format!("Child query '{}' must name its parent column", child.field));
+            }
+            // Отрендеренный составной ключ не совпадает ни с одной колонкой дочерней таблицы
+            if child.parent_key.is_none() && key.template().is_some() {
+                return Err(format!("Child query '{}' needs parent_key when the primary key is composite", child.field));
+            }
+
+            let compiled = NamedParams::compile(&child.query)?;
+            if !compiled.names.iter().any(|name| name == CHILD_IDS_PARAM) {
+                return Err(format!("Child query '{}' must filter by :{}", child.field, CHILD_IDS_PARAM));
+            }
+        }
+        Ok(())
+    }
+
+    /// Выполняет дочерние запросы для пачки и вкладывает найденные строки в документы.
+    /// Документ без дочерних строк получает пустой массив.
+    pub async fn attach(
+        query_executor: &QueryExecutor,
+        data_source: &DataSource,
+        child_queries: &[ChildQuery],
+        primary_key: &str,
+        documents: &mut [Value],
+    ) -> Result<(), String> {
+        for child in child_queries {
+            let parent_key = child.parent_key.as_deref().unwrap_or(primary_key);
+            let ids = Self::parent_ids(documents, parent_key)?;
+            let rows = if ids.is_empty() {
+                Vec::new()
+            } else {
+                query_executor
+                    .clone()
+                    .with_param(CHILD_IDS_PARAM, Value::Array(ids))
+                    .fetch_all(data_source, &child.query)
+                    .await
+                    .map_err(|e| format!("Child query '{}' failed: {}", child.field, e))?
+            };
+            Self::nest(documents, child, parent_key, rows);
+        }
+        Ok(())
+    }
+
+    /// Различные значения ключа родителей в порядке появления; null пропускаются
+    fn parent_ids(documents: &[Value], parent_key: &str) -> Result<Vec<Value>, String> {
+        let mut seen = HashSet::new();
+        let mut ids = Vec::new();
+        for value in documents.iter().filter_map(|document| document.get(parent_key)) {
+            if value.is_null() {
+                continue;
+            }
+            let Some(raw) = IndexDocumentReader::key_to_string(value) else {
+                return Err(format!("Parent key '{}' must be a number or a string, got {}", parent_key, value));
+            };
+            if seen.insert(raw) {
+                ids.push(value.clone());
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Ключи сравниваются как строки: `int4` дочерней таблицы совпадает с `int8` родителя
+    fn nest(documents: &mut [Value], child: &ChildQuery, parent_key: &str, rows: Vec<Value>) {
+        let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+        for row in rows {
+            if let Some(parent) = row.get(&child.parent_column).and_then(IndexDocumentReader::key_to_string) {
+                groups.entry(parent).or_default().push(row);
+            }
+        }
+
+        for document in documents.iter_mut() {
+            let children = document
+                .get(parent_key)
+                .and_then(IndexDocumentReader::key_to_string)
+                .and_then(|parent| groups.get(&parent).cloned())
+                .unwrap_or_default();
+            if let Some(fields) = document.as_object_mut() {
+                fields.insert(child.field.clone(), Value::Array(children));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn child(query: &str) -> ChildQuery {
+        ChildQuery {
+            field: "variants".to_string(),
+            query: query.to_string(),
+            parent_column: "product_id".to_string(),
+            parent_key: None,
+        }
+    }
+
+    #[test]
+    fn nests_child_rows_by_parent_key() {
+        let mut documents = vec![json!({"id": 1, "name": "Chair"}), json!({"id": 2, "name": "Table"})];
+        let rows = vec![
+            json!({"product_id": 1, "sku": "CH-RED"}),
+            json!({"product_id": 1, "sku": "CH-BLUE"}),
+            json!({"product_id": 3, "sku": "ORPHAN"}),
+        ];
+
+        assert_eq!(ChildQueryAssembler::parent_ids(&documents, "id").unwrap(), vec![json!(1), json!(2)]);
+        ChildQueryAssembler::nest(&mut documents, &child(""), "id", rows);
+
+        assert_eq!(documents[0]["variants"], json!([
+            {"product_id": 1, "sku": "CH-RED"},
+            {"product_id": 1, "sku": "CH-BLUE"},
+        ]));
+        assert_eq!(documents[1]["variants"], json!([]));
+    }
+
+    #[test]
+    fn requires_ids_parameter() {
+        let key = DocumentKey::new("id", None).unwrap();
+        assert!(ChildQueryAssembler::validate(&[child("SELECT * FROM variants WHERE product_id = ANY(:ids)")], &key).is_ok());
+        assert!(ChildQueryAssembler::validate(&[child("SELECT * FROM variants")], &key).is_err());
+
+        let composite = DocumentKey::new("id", Some("{tenant_id}-{id}")).unwrap();
+        assert!(ChildQueryAssembler::validate(&[child("SELECT * FROM variants WHERE product_id = ANY(:ids)")], &composite).is_err());
+    }
+}
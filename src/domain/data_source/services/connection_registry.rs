@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::mysql::{MySqlPool, MySqlPoolOptions};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use tracing::info;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::mysql_connector::MySqlConnector;
+use crate::domain::data_source::services::postgres_connector::PostgresConnector;
+use crate::domain::data_source::services::sqlite_query_executor::SqliteQueryExecutor;
+
+/// Как часто проверять простаивающие пулы
+const EVICTION_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Пулы подключений к источникам данных: по одному ограниченному пулу на источник.
+/// Пул создаётся при первом обращении, пересоздаётся при смене параметров подключения
+/// и закрывается, если им долго не пользовались.
+pub struct ConnectionRegistry {
+    pools: Mutex<HashMap<u32, PoolEntry>>,
+    max_connections: u32,
+    idle_timeout: Duration,
+}
+
+/// Пул источника: Postgres, MySQL/MariaDB или файл SQLite
+#[derive(Clone)]
+enum SourcePool {
+    Postgres(PgPool),
+    MySql(MySqlPool),
+    Sqlite(SqlitePool),
+}
+
+impl SourcePool {
+    fn size(&self) -> u32 {
+        match self {
+            SourcePool::Postgres(pool) => pool.size(),
+            SourcePool::MySql(pool) => pool.size(),
+            SourcePool::Sqlite(pool) => pool.size(),
+        }
+    }
+
+    fn num_idle(&self) -> usize {
+        match self {
+            SourcePool::Postgres(pool) => pool.num_idle(),
+            SourcePool::MySql(pool) => pool.num_idle(),
+            SourcePool::Sqlite(pool) => pool.num_idle(),
+        }
+    }
+
+    async fn close(&self) {
+        match self {
+            SourcePool::Postgres(pool) => pool.close().await,
+            SourcePool::MySql(pool) => pool.close().await,
+            SourcePool::Sqlite(pool) => pool.close().await,
+        }
+    }
+}
+
+struct PoolEntry {
+    pool: SourcePool,
+    /// Хэш строки подключения: по нему видно, что изменились хост или учётные данные
+    fingerprint: u64,
+    created_at: DateTime<Utc>,
+    last_used: Instant,
+}
+
+/// Состояние пула одного источника
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStats {
+    pub data_source_id: u32,
+    /// Открытые подключения, включая занятые
+    pub size: u32,
+    pub idle: usize,
+    pub max_connections: u32,
+    pub created_at: DateTime<Utc>,
+    /// Сколько секунд прошло с последнего обращения к пулу
+    pub idle_secs: u64,
+}
+
+impl ConnectionRegistry {
+    pub fn new(max_connections: u32, idle_timeout: Duration) -> Self {
+        Self {
+            pools: Mutex::new(HashMap::new()),
+            max_connections,
+            idle_timeout,
+        }
+    }
+
+    /// Пул источника Postgres; подключения открываются лениво, при первом запросе
+    pub fn pool(&self, data_source: &DataSource) -> Result<PgPool, String> {
+        let options = PostgresConnector::connect_options(data_source)?;
+        let pool = self.entry(data_source, || {
+            SourcePool::Postgres(
+                PgPoolOptions::new()
+                    .max_connections(self.max_connections)
+                    .idle_timeout(self.idle_timeout)
+                    .connect_lazy_with(options),
+            )
+        })?;
+        match pool {
+            SourcePool::Postgres(pool) => Ok(pool),
+            _ => Err(format!("Data source {} is not a PostgreSQL database", data_source.id)),
+        }
+    }
+
+    /// Пул источника MySQL/MariaDB
+    pub fn mysql_pool(&self, data_source: &DataSource) -> Result<MySqlPool, String> {
+        let options = MySqlConnector::connect_options(data_source)?;
+        let pool = self.entry(data_source, || {
+            SourcePool::MySql(
+                MySqlPoolOptions::new()
+                    .max_connections(self.max_connections)
+                    .idle_timeout(self.idle_timeout)
+                    .connect_lazy_with(options),
+            )
+        })?;
+        match pool {
+            SourcePool::MySql(pool) => Ok(pool),
+            _ => Err(format!("Data source {} is not a MySQL database", data_source.id)),
+        }
+    }
+
+    /// Пул файла SQLite, открытого только для чтения
+    pub fn sqlite_pool(&self, data_source: &DataSource) -> Result<SqlitePool, String> {
+        let pool = self.entry(data_source, || {
+            SourcePool::Sqlite(
+                SqlitePoolOptions::new()
+                    .max_connections(self.max_connections)
+                    .idle_timeout(self.idle_timeout)
+                    .connect_lazy_with(SqliteQueryExecutor::connect_options(data_source)),
+            )
+        })?;
+        match pool {
+            SourcePool::Sqlite(pool) => Ok(pool),
+            _ => Err(format!("Data source {} is not a SQLite database", data_source.id)),
+        }
+    }
+
+    /// Существующий пул источника или новый, созданный `create`. Отпечаток включает тип источника,
+    /// поэтому смена типа базы тоже пересоздаёт пул.
+    fn entry(
+        &self,
+        data_source: &DataSource,
+        create: impl FnOnce() -> SourcePool,
+    ) -> Result<SourcePool, String> {
+        let fingerprint = Self::fingerprint(data_source)?;
+        let mut pools = self.pools.lock().map_err(|e| e.to_string())?;
+
+        if let Some(entry) = pools.get_mut(&data_source.id) {
+            if entry.fingerprint == fingerprint {
+                entry.last_used = Instant::now();
+                return Ok(entry.pool.clone());
+            }
+            info!("Connection settings of data source {} changed, recreating pool", data_source.id);
+        }
+
+        let pool = create();
+        let entry = PoolEntry {
+            pool: pool.clone(),
+            fingerprint,
+            created_at: Utc::now(),
+            last_used: Instant::now(),
+        };
+        if let Some(previous) = pools.insert(data_source.id, entry) {
+            Self::close(previous.pool);
+        }
+
+        Ok(pool)
+    }
+
+    /// Закрывает пул источника, например после его удаления
+    pub fn invalidate(&self, data_source_id: u32) {
+        let removed = match self.pools.lock() {
+            Ok(mut pools) => pools.remove(&data_source_id),
+            Err(_) => None,
+        };
+        if let Some(entry) = removed {
+            Self::close(entry.pool);
+        }
+    }
+
+    /// Закрывает пулы, к которым не обращались дольше `idle_timeout` и в которых нет занятых подключений.
+    /// Возвращает число закрытых пулов.
+    pub fn evict_idle(&self) -> usize {
+        let evicted: Vec<PoolEntry> = match self.pools.lock() {
+            Ok(mut pools) => {
+                let expired: Vec<u32> = pools
+                    .iter()
+                    .filter(|(_, entry)| {
+                        entry.last_used.elapsed() >= self.idle_timeout
+                            && entry.pool.num_idle() == entry.pool.size() as usize
+                    })
+                    .map(|(id, _)| *id)
+                    .collect();
+                expired.iter().filter_map(|id| pools.remove(id)).collect()
+            }
+            Err(_) => Vec::new(),
+        };
+
+        let count = evicted.len();
+        for entry in evicted {
+            Self::close(entry.pool);
+        }
+        count
+    }
+
+    pub fn stats(&self) -> Vec<PoolStats> {
+        let Ok(pools) = self.pools.lock() else {
+            return Vec::new();
+        };
+        let mut stats: Vec<PoolStats> = pools
+            .iter()
+            .map(|(id, entry)| PoolStats {
+                data_source_id: *id,
+                size: entry.pool.size(),
+                idle: entry.pool.num_idle(),
+                max_connections: self.max_connections,
+                created_at: entry.created_at,
+                idle_secs: entry.last_used.elapsed().as_secs(),
+            })
+            .collect();
+        stats.sort_by_key(|stats| stats.data_source_id);
+        stats
+    }
+
+    /// Фоновая очистка простаивающих пулов
+    pub async fn run_eviction(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+        loop {
+            interval.tick().await;
+            let evicted = self.evict_idle();
+            if evicted > 0 {
+                info!("Closed {} idle data source pools", evicted);
+            }
+        }
+    }
+
+    /// Хэш параметров подключения; пароль берётся расшифрованным, потому что шифротекст
+    /// меняется при каждом сохранении и смене мастер-ключа
+    fn fingerprint(data_source: &DataSource) -> Result<u64, String> {
+        let mut hasher = DefaultHasher::new();
+        data_source.database_type.hash(&mut hasher);
+        data_source.host.hash(&mut hasher);
+        data_source.port.hash(&mut hasher);
+        data_source.username.hash(&mut hasher);
+        data_source.password.reveal()?.hash(&mut hasher);
+        data_source.database_name.hash(&mut hasher);
+        data_source.database_path.hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    /// Занятые подключения дорабатывают свои запросы, закрытие ждёт их в фоне
+    fn close(pool: SourcePool) {
+        tokio::spawn(async move { pool.close().await });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::data_source::entities::secret::Secret;
+
+    fn data_source(password: &str) -> DataSource {
+        DataSource {
+            id: 7,
+            name: "orders".to_string(),
+            host: "localhost".to_string(),
+            database: "postgres".to_string(),
+            username: "reader".to_string(),
+            password: Secret::from_stored(password.to_string()),
+            port: 5432,
+            database_path: String::new(),
+            database_name: "shop".to_string(),
+            database_type: "postgresql".to_string(),
+            file_options: Default::default(),
+            http_options: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reuses_pool_until_credentials_change() {
+        let registry = ConnectionRegistry::new(3, Duration::from_secs(60));
+
+        let first = registry.pool(&data_source("secret")).unwrap();
+        let same = registry.pool(&data_source("secret")).unwrap();
+        assert!(!first.is_closed() && !same.is_closed());
+        assert_eq!(registry.stats().len(), 1);
+        assert_eq!(registry.stats()[0].max_connections, 3);
+
+        registry.pool(&data_source("rotated")).unwrap();
+        tokio::task::yield_now().await;
+        assert!(first.is_closed());
+        assert_eq!(registry.stats().len(), 1);
+
+        registry.invalidate(7);
+        assert!(registry.stats().is_empty());
+    }
+
+    #[tokio::test]
+    async fn evicts_only_pools_idle_longer_than_timeout() {
+        let registry = ConnectionRegistry::new(3, Duration::from_millis(50));
+        let idle = registry.pool(&data_source("secret")).unwrap();
+        let recent = DataSource { id: 8, ..data_source("secret") };
+        registry.pool(&recent).unwrap();
+        assert_eq!(registry.evict_idle(), 0);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        registry.pool(&recent).unwrap();
+        assert_eq!(registry.evict_idle(), 1);
+        tokio::task::yield_now().await;
+
+        assert!(idle.is_closed());
+        let remaining: Vec<u32> = registry.stats().iter().map(|stats| stats.data_source_id).collect();
+        assert_eq!(remaining, vec![8]);
+    }
+}
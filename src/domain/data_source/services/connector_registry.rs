@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use crate::domain::data_source::services::data_source_connector::{ConnectorDescriptor, DataSourceConnector};
+use crate::domain::data_source::services::file_connector::FileConnector;
+use crate::domain::data_source::services::http_connector::HttpConnector;
+use crate::domain::data_source::services::mysql_connector::MySqlConnector;
+use crate::domain::data_source::services::postgres_connector::PostgresConnector;
+use crate::domain::data_source::services::sqlite_connector::SqliteConnector;
+
+/// Реализации DataSourceConnector по значению `database_type`.
+/// Новый тип источника — это новый коннектор, зарегистрированный здесь.
+#[derive(Clone, Default)]
+pub struct ConnectorRegistry {
+    connectors: BTreeMap<&'static str, Arc<dyn DataSourceConnector>>,
+}
+
+impl ConnectorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Реестр со всеми встроенными типами источников
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(PostgresConnector));
+        registry.register(Arc::new(MySqlConnector));
+        registry.register(Arc::new(SqliteConnector));
+        registry.register(Arc::new(FileConnector));
+        registry.register(Arc::new(HttpConnector));
+        registry
+    }
+
+    /// Добавляет коннектор; коннектор того же типа заменяется
+    pub fn register(&mut self, connector: Arc<dyn DataSourceConnector>) {
+        self.connectors.insert(connector.descriptor().database_type, connector);
+    }
+
+    pub fn get(&self, database_type: &str) -> Option<Arc<dyn DataSourceConnector>> {
+        self.connectors.get(database_type).cloned()
+    }
+
+    /// Коннектор типа источника или ошибка со списком поддерживаемых типов
+    pub fn require(&self, database_type: &str) -> Result<Arc<dyn DataSourceConnector>, String> {
+        self.get(database_type).ok_or_else(|| {
+            format!(
+                "Unsupported data source type: {}. Supported types: {}",
+                database_type,
+                self.connectors.keys().copied().collect::<Vec<_>>().join(", ")
+            )
+        })
+    }
+
+    /// Описания всех типов — по ним UI строит форму создания источника
+    pub fn descriptors(&self) -> Vec<ConnectorDescriptor> {
+        self.connectors.values().map(|connector| connector.descriptor()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_builtin_connectors_by_type() {
+        let registry = ConnectorRegistry::with_builtin();
+        for database_type in ["postgresql", "mysql", "sqlite", "file", "http"] {
+            assert_eq!(registry.require(database_type).unwrap().descriptor().database_type, database_type);
+        }
+
+        let error = registry.require("oracle").err().unwrap();
+        assert_eq!(error, "Unsupported data source type: oracle. Supported types: file, http, mysql, postgresql, sqlite");
+        assert_eq!(registry.descriptors().len(), 5);
+    }
+}
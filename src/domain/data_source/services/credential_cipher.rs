@@ -0,0 +1,131 @@
+use std::fmt;
+use std::sync::OnceLock;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// Префикс зашифрованного значения в `data_sources.password`; значения без него — старые, открытым текстом
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// Длина nonce AES-GCM в байтах
+const NONCE_LEN: usize = 12;
+
+/// Шифр, которым приложение расшифровывает учётные данные при подключении (см. CredentialCipher::install)
+static INSTALLED: OnceLock<CredentialCipher> = OnceLock::new();
+
+/// Шифрует учётные данные источников AES-256-GCM. Ключ — 32 байта в base64 из переменной окружения;
+/// каждое значение получает свой случайный nonce, а подмена шифротекста обнаруживается при расшифровке.
+#[derive(Clone)]
+pub struct CredentialCipher {
+    cipher: Aes256Gcm,
+}
+
+impl CredentialCipher {
+    /// Шифр по мастер-ключу в base64 (32 байта, например `openssl rand -base64 32`)
+    pub fn from_base64(master_key: &str) -> Result<Self, String> {
+        let key = STANDARD
+            .decode(master_key.trim())
+            .map_err(|e| format!("Master key is not valid base64: {}", e))?;
+        if key.len() != 32 {
+            return Err(format!("Master key must be 32 bytes, got {}", key.len()));
+        }
+        Ok(Self { cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)) })
+    }
+
+    /// Делает шифр общим для процесса; повторная установка игнорируется
+    pub fn install(cipher: CredentialCipher) {
+        let _ = INSTALLED.set(cipher);
+    }
+
+    /// Шифр, установленный при запуске
+    pub fn installed() -> Result<&'static CredentialCipher, String> {
+        INSTALLED
+            .get()
+            .ok_or_else(|| "Credentials master key is not configured".to_string())
+    }
+
+    /// Значение уже зашифровано (а не сохранено старой версией открытым текстом)
+    pub fn is_encrypted(stored: &str) -> bool {
+        stored.starts_with(ENCRYPTED_PREFIX)
+    }
+
+    /// Шифрует секрет; пустая строка остаётся пустой — это «пароль не задан»
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        if plaintext.is_empty() {
+            return Ok(String::new());
+        }
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| "Failed to encrypt credentials".to_string())?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(payload)))
+    }
+
+    /// Расшифровывает сохранённое значение; старые значения без префикса возвращаются как есть
+    pub fn decrypt(&self, stored: &str) -> Result<String, String> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let payload = STANDARD
+            .decode(encoded)
+            .map_err(|_| "Encrypted credentials are corrupted".to_string())?;
+        if payload.len() <= NONCE_LEN {
+            return Err("Encrypted credentials are corrupted".to_string());
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "Failed to decrypt credentials: wrong master key or tampered value".to_string())?;
+        String::from_utf8(plaintext).map_err(|_| "Decrypted credentials are not valid UTF-8".to_string())
+    }
+}
+
+impl fmt::Debug for CredentialCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CredentialCipher(***)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+    const OTHER_KEY: &str = "ZmVkY2JhOTg3NjU0MzIxMGZlZGNiYTk4NzY1NDMyMTA=";
+
+    #[test]
+    fn round_trips_and_rejects_wrong_key_or_tampering() {
+        let cipher = CredentialCipher::from_base64(KEY).unwrap();
+        let first = cipher.encrypt("s3cret").unwrap();
+        let second = cipher.encrypt("s3cret").unwrap();
+        assert!(CredentialCipher::is_encrypted(&first));
+        assert!(!first.contains("s3cret"));
+        assert_ne!(first, second);
+        assert_eq!(cipher.decrypt(&first).unwrap(), "s3cret");
+
+        let other = CredentialCipher::from_base64(OTHER_KEY).unwrap();
+        assert!(other.decrypt(&first).is_err());
+
+        let mut payload = STANDARD.decode(first.strip_prefix(ENCRYPTED_PREFIX).unwrap()).unwrap();
+        let last = payload.len() - 1;
+        payload[last] ^= 1;
+        let tampered = format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(payload));
+        assert!(cipher.decrypt(&tampered).is_err());
+    }
+
+    #[test]
+    fn keeps_empty_and_legacy_values() {
+        let cipher = CredentialCipher::from_base64(KEY).unwrap();
+        assert_eq!(cipher.encrypt("").unwrap(), "");
+        assert_eq!(cipher.decrypt("").unwrap(), "");
+        assert_eq!(cipher.decrypt("plain-old-password").unwrap(), "plain-old-password");
+        assert!(CredentialCipher::from_base64("c2hvcnQ=").is_err());
+        assert_eq!(format!("{:?}", cipher), "CredentialCipher(***)");
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::connection_registry::ConnectionRegistry;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::named_params::QueryParams;
+use crate::domain::data_source::services::query_executor::{CursorWindow, QueryPreview, DEFAULT_FILE_ROOT, DEFAULT_STATEMENT_TIMEOUT};
+use crate::domain::data_source::services::row_filter::RowFilter;
+
+/// Настройки выполнения, общие для всех типов источников; их задаёт QueryExecutor
+#[derive(Clone)]
+pub struct QueryContext {
+    /// Добавлять к датам поле `<колонка>_epoch`
+    pub epoch_timestamps: bool,
+    /// Значения именованных параметров запроса
+    pub params: QueryParams,
+    /// Предел времени выполнения одной инструкции или одного HTTP-запроса
+    pub statement_timeout: Duration,
+    /// Общие пулы подключений; без реестра каждый вызов открывает своё подключение
+    pub connections: Option<Arc<ConnectionRegistry>>,
+    /// Каталог, внутри которого файловые источники ищут свои файлы
+    pub file_root: PathBuf,
+}
+
+impl Default for QueryContext {
+    fn default() -> Self {
+        Self {
+            epoch_timestamps: false,
+            params: QueryParams::new(),
+            statement_timeout: DEFAULT_STATEMENT_TIMEOUT,
+            connections: None,
+            file_root: PathBuf::from(DEFAULT_FILE_ROOT),
+        }
+    }
+}
+
+/// Как UI отображает поле формы и как оно проверяется при сохранении
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    Text,
+    Number,
+    /// Целое от 1 до 65535
+    Port,
+    /// Секрет: не показывается после сохранения
+    Password,
+    /// Путь к файлу или glob-шаблон на сервере
+    Path,
+    /// Адрес `http://` или `https://`
+    Url,
+    /// Одно из значений `options`
+    Select,
+    Boolean,
+    /// Произвольный JSON-объект
+    Json,
+}
+
+/// Поле формы создания источника
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionField {
+    /// Имя поля в запросе на создание; вложенные настройки — через точку (`http_options.records_path`)
+    pub name: &'static str,
+    pub label: &'static str,
+    #[serde(rename = "type")]
+    pub kind: FieldKind,
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<&'static str>,
+}
+
+impl ConnectionField {
+    pub fn new(name: &'static str, label: &'static str, kind: FieldKind) -> Self {
+        Self {
+            name,
+            label,
+            kind,
+            required: false,
+            default: None,
+            options: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn with_default(mut self, default: Value) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    pub fn with_options(mut self, options: &[&'static str]) -> Self {
+        self.options = options.to_vec();
+        self
+    }
+
+    pub fn with_help(mut self, help: &'static str) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    /// Поля подключения к серверной базе: хост, порт, имя базы и учётные данные
+    pub fn server_fields(default_port: u16) -> Vec<ConnectionField> {
+        vec![
+            Self::new("host", "Host", FieldKind::Text).required(),
+            Self::new("port", "Port", FieldKind::Port).required().with_default(Value::from(default_port)),
+            Self::new("database_name", "Database", FieldKind::Text),
+            Self::new("username", "Username", FieldKind::Text),
+            Self::new("password", "Password", FieldKind::Password),
+        ]
+    }
+
+    /// Ошибка проверки значения поля или None, если значение подходит
+    fn check(&self, value: Option<&Value>) -> Option<String> {
+        let value = match value {
+            None | Some(Value::Null) => None,
+            Some(Value::String(text)) if text.trim().is_empty() => None,
+            Some(Value::Number(number)) if self.kind == FieldKind::Port && number.as_u64() == Some(0) => None,
+            Some(value) => Some(value),
+        };
+        let Some(value) = value else {
+            return self.required.then(|| format!("{} is required", self.label));
+        };
+
+        let valid = match self.kind {
+            FieldKind::Port => value.as_u64().is_some_and(|port| (1..=65535).contains(&port)),
+            FieldKind::Number => value.is_number(),
+            FieldKind::Boolean => value.is_boolean(),
+            FieldKind::Url => value.as_str().is_some_and(|url| url.starts_with("http://") || url.starts_with("https://")),
+            FieldKind::Select => value.as_str().is_some_and(|option| self.options.contains(&option)),
+            FieldKind::Text | FieldKind::Password | FieldKind::Path | FieldKind::Json => true,
+        };
+        if valid {
+            return None;
+        }
+        Some(match self.kind {
+            FieldKind::Port => format!("{} must be between 1 and 65535", self.label),
+            FieldKind::Url => format!("{} must start with http:// or https://", self.label),
+            FieldKind::Select => format!("{} must be one of: {}", self.label, self.options.join(", ")),
+            _ => format!("{} has an invalid value", self.label),
+        })
+    }
+}
+
+/// Описание типа источника: значение `database_type`, название и поля формы создания
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectorDescriptor {
+    pub database_type: &'static str,
+    pub label: &'static str,
+    pub fields: Vec<ConnectionField>,
+}
+
+impl ConnectorDescriptor {
+    /// Проверяет запрос на создание источника по описанию полей.
+    /// Возвращает ошибки по именам полей; пустой результат — запрос корректен.
+    pub fn validate(&self, payload: &Value) -> BTreeMap<String, Vec<String>> {
+        let mut errors: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for field in &self.fields {
+            let value = payload.pointer(&format!("/{}", field.name.replace('.', "/")));
+            if let Some(error) = field.check(value) {
+                errors.entry(field.name.to_string()).or_default().push(error);
+            }
+        }
+        errors
+    }
+}
+
+/// Колонка таблицы (файла, ответа API) источника
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceColumn {
+    pub name: String,
+    /// Тип в источнике; у файлов и HTTP API — тип значения JSON
+    pub source_type: String,
+}
+
+/// Таблица или представление базы, файл или эндпоинт API вместе с колонками
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceObject {
+    pub name: String,
+    pub columns: Vec<SourceColumn>,
+}
+
+impl SourceObject {
+    /// Группирует строки (объект, колонка, тип) по объектам, сохраняя их порядок
+    pub(crate) fn group(rows: Vec<(String, String, String)>) -> Vec<SourceObject> {
+        let mut objects: Vec<SourceObject> = Vec::new();
+        for (object, column, source_type) in rows {
+            if objects.last().is_none_or(|last| last.name != object) {
+                objects.push(SourceObject { name: object, columns: Vec::new() });
+            }
+            if let Some(last) = objects.last_mut() {
+                last.columns.push(SourceColumn { name: column, source_type });
+            }
+        }
+        objects
+    }
+
+    /// Объект без собственной схемы: колонки выводятся по прочитанным строкам
+    pub(crate) fn from_rows(name: String, rows: &[Value]) -> SourceObject {
+        let columns = RowFilter::preview_columns(rows)
+            .into_iter()
+            .map(|column| SourceColumn { name: column.name, source_type: column.json_type })
+            .collect();
+        SourceObject { name, columns }
+    }
+}
+
+/// Тип источника данных. Реализация знает, как подключиться к источнику и прочитать его строки;
+/// QueryExecutor выбирает её по `database_type` через ConnectorRegistry.
+/// Курсор и ключ трактуются так же, как в QueryExecutor.
+#[async_trait]
+pub trait DataSourceConnector: Send + Sync {
+    /// Значение `database_type`, название и поля формы создания
+    fn descriptor(&self) -> ConnectorDescriptor;
+
+    /// Источник умеет присылать уведомления об изменениях (LISTEN/NOTIFY) и перечитывать строки по ключам
+    fn supports_notify(&self) -> bool {
+        false
+    }
+
+    /// Проверяет, что источник доступен с сохранёнными настройками
+    async fn test_connection(&self, context: &QueryContext, data_source: &DataSource) -> Result<(), String>;
+
+    /// Таблицы (файлы, эндпоинты) источника и их колонки
+    async fn introspect(&self, context: &QueryContext, data_source: &DataSource) -> Result<Vec<SourceObject>, String>;
+
+    /// Первые `limit` строк запроса со схемой колонок
+    async fn preview(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String>;
+
+    /// Передаёт строки запроса в `sender` по одной; возвращает число прочитанных строк
+    #[allow(clippy::too_many_arguments)]
+    async fn stream_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String>;
+
+    /// Строки запроса, при наличии `limit` — не больше указанного числа
+    async fn fetch_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Value>, String>;
+
+    /// Максимум курсорной колонки как текст и её тип
+    async fn fetch_cursor_bounds(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String>;
+
+    /// Все значения первичного ключа в виде строк
+    async fn fetch_keys(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String>;
+
+    /// Строки с указанными значениями ключа; нужно только слушателю LISTEN/NOTIFY
+    async fn fetch_rows_by_keys(
+        &self,
+        _context: &QueryContext,
+        _data_source: &DataSource,
+        _query: &str,
+        _key: &DocumentKey,
+        _keys: &[String],
+    ) -> Result<Vec<Value>, String> {
+        Err("Fetching rows by key is only supported for PostgreSQL data sources".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validates_payload_against_fields() {
+        let descriptor = ConnectorDescriptor {
+            database_type: "test",
+            label: "Test",
+            fields: vec![
+                ConnectionField::new("host", "Host", FieldKind::Text).required(),
+                ConnectionField::new("port", "Port", FieldKind::Port).required(),
+                ConnectionField::new("database_path", "Base URL", FieldKind::Url),
+                ConnectionField::new("file_options.format", "Format", FieldKind::Select).with_options(&["csv", "json"]),
+            ],
+        };
+
+        let errors = descriptor.validate(&json!({
+            "host": " ",
+            "port": 70000,
+            "database_path": "ftp://example.com",
+            "file_options": {"format": "xml"}
+        }));
+        assert_eq!(errors["host"], vec!["Host is required"]);
+        assert_eq!(errors["port"], vec!["Port must be between 1 and 65535"]);
+        assert_eq!(errors["database_path"], vec!["Base URL must start with http:// or https://"]);
+        assert_eq!(errors["file_options.format"], vec!["Format must be one of: csv, json"]);
+
+        let errors = descriptor.validate(&json!({"host": "db", "port": 5432, "database_path": "", "file_options": null}));
+        assert!(errors.is_empty());
+        assert_eq!(descriptor.validate(&json!({"host": "db", "port": 0}))["port"], vec!["Port is required"]);
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+use meilisearch_sdk::client::Client;
+use meilisearch_sdk::tasks::Task;
+use serde::Serialize;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::entities::index_data_query::IndexDataQuery;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::index_document_reader::IndexDocumentReader;
+use crate::domain::data_source::services::query_executor::QueryExecutor;
+
+/// Сколько ждать завершения одной задачи удаления
+const TASK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Итог сверки удалений между источником и индексом
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DeletionReport {
+    pub source_keys: u64,
+    pub index_documents: u64,
+    pub orphans: u64,
+    pub deleted: u64,
+    pub task_uids: Vec<u32>,
+}
+
+/// Удаляет из индекса документы, которых больше нет в источнике
+pub struct DeletionReconciler {
+    meilisearch_client: Client,
+}
+
+impl DeletionReconciler {
+    pub fn new(meilisearch_client: Client) -> Self {
+        Self { meilisearch_client }
+    }
+
+    /// Сравнивает первичные ключи источника с id документов индекса и удаляет сирот пачками.
+    /// Если сирот больше, чем разрешает `max_delete_ratio` запроса, ничего не удаляется.
+    /// Параметры `query_executor` должны совпадать с параметрами синхронизации, иначе сравнивается другая выборка.
+    pub async fn reconcile(
+        &self,
+        data_source: &DataSource,
+        index_data_query: &IndexDataQuery,
+        key: &DocumentKey,
+        query_executor: &QueryExecutor,
+        batch_size: u32,
+    ) -> Result<DeletionReport, String> {
+        let source_keys = query_executor
+            .fetch_keys(data_source, &index_data_query.query, key)
+            .await?;
+
+        let index = self.meilisearch_client.index(&index_data_query.index_uid);
+        let index_keys = IndexDocumentReader::fetch_primary_keys(&index, key.field(), batch_size).await?;
+
+        let mut orphans: Vec<String> = index_keys.difference(&source_keys).cloned().collect();
+        orphans.sort();
+
+        let mut report = DeletionReport {
+            source_keys: source_keys.len() as u64,
+            index_documents: index_keys.len() as u64,
+            orphans: orphans.len() as u64,
+            ..DeletionReport::default()
+        };
+
+        if orphans.is_empty() {
+            return Ok(report);
+        }
+
+        let ratio = orphans.len() as f64 / index_keys.len() as f64;
+        if ratio > index_data_query.max_delete_ratio {
+            return Err(format!(
+                "Refusing to delete {} of {} documents from '{}' ({:.1}% > allowed {:.1}%)",
+                orphans.len(),
+                index_keys.len(),
+                index_data_query.index_uid,
+                ratio * 100.0,
+                index_data_query.max_delete_ratio * 100.0
+            ));
+        }
+
+        let mut tasks = Vec::new();
+        for chunk in orphans.chunks(batch_size.max(1) as usize) {
+            let task_info = index
+                .delete_documents(chunk)
+                .await
+                .map_err(|e| format!("Error enqueueing delete task: {}", e))?;
+            println!("🗑️ Delete batch sent: {} documents, task {}", chunk.len(), task_info.task_uid);
+            report.task_uids.push(task_info.task_uid);
+            tasks.push((task_info, chunk.len() as u64));
+        }
+
+        for (task_info, count) in tasks {
+            let task_uid = task_info.task_uid;
+            match self.meilisearch_client.wait_for_task(task_info, None, Some(TASK_TIMEOUT)).await {
+                Ok(Task::Failed { content }) => {
+                    return Err(format!("Meilisearch delete task {} failed: {:?}", task_uid, content.error));
+                }
+                Ok(_) => report.deleted += count,
+                Err(e) => return Err(format!("Error waiting for delete task {}: {}", task_uid, e)),
+            }
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,180 @@
+use serde_json::Value;
+use crate::domain::data_source::entities::index_data_query::IndexDataQuery;
+
+/// Первичный ключ документов, если у запроса он не задан
+pub const DEFAULT_PRIMARY_KEY: &str = "id";
+
+/// Служебная колонка, в которую SQL отдаёт отрендеренный составной ключ
+pub const RENDERED_KEY_COLUMN: &str = "__milli_key";
+
+/// Максимальная длина id документа в Meilisearch (в байтах)
+const MAX_DOCUMENT_ID_LENGTH: usize = 511;
+
+/// Часть шаблона составного ключа `{tenant_id}-{order_id}`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+    Literal(String),
+    Field(String),
+}
+
+/// Первичный ключ документов запроса: имя поля в индексе и, при необходимости,
+/// шаблон, по которому значение собирается из нескольких колонок
+#[derive(Debug, Clone)]
+pub struct DocumentKey {
+    field: String,
+    template: Option<Vec<TemplatePart>>,
+}
+
+impl DocumentKey {
+    pub fn new(field: &str, template: Option<&str>) -> Result<Self, String> {
+        let field = field.trim();
+        if field.is_empty() {
+            return Err("Primary key must not be empty".to_string());
+        }
+
+        let template = match template.map(str::trim).filter(|template| !template.is_empty()) {
+            Some(template) => Some(Self::parse_template(template)?),
+            None => None,
+        };
+
+        Ok(Self { field: field.to_string(), template })
+    }
+
+    pub fn for_query(index_data_query: &IndexDataQuery) -> Result<Self, String> {
+        Self::new(&index_data_query.primary_key, index_data_query.primary_key_template.as_deref())
+    }
+
+    /// Имя поля первичного ключа в индексе
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Части шаблона, если ключ составной
+    pub fn template(&self) -> Option<&[TemplatePart]> {
+        self.template.as_deref()
+    }
+
+    /// Достаёт из строки результата значение для keyset-пагинации.
+    /// Для составного ключа заодно записывает в документ безопасный для Meilisearch id.
+    pub fn take_key(&self, document: &mut Value) -> Result<Value, String> {
+        let Some(fields) = document.as_object_mut() else {
+            return Err("Query row is not an object".to_string());
+        };
+
+        if self.template.is_none() {
+            return match fields.get(&self.field) {
+                Some(Value::Null) | None => Err(format!(
+                    "Query result must contain a non-null '{}' column for batching", self.field
+                )),
+                Some(key) => Ok(key.clone()),
+            };
+        }
+
+        match fields.remove(RENDERED_KEY_COLUMN) {
+            Some(Value::String(raw)) => {
+                fields.insert(self.field.clone(), Value::String(Self::sanitize(&raw)?));
+                Ok(Value::String(raw))
+            }
+            _ => Err("Composite primary key could not be rendered".to_string()),
+        }
+    }
+
+    /// Приводит строку к допустимому id без склеек: латиница, цифры и `-` остаются как есть,
+    /// любой другой байт (и сам `_`) записывается как `_XX` в hex. Разные ключи дают разные id.
+    /// Id длиннее 511 байт — ошибка, а не обрезка: обрезанные ключи совпали бы.
+    pub fn sanitize(raw: &str) -> Result<String, String> {
+        let mut id = String::with_capacity(raw.len());
+        for byte in raw.bytes() {
+            if byte.is_ascii_alphanumeric() || byte == b'-' {
+                id.push(byte as char);
+            } else {
+                id.push_str(&format!("_{:02X}", byte));
+            }
+        }
+        if id.len() > MAX_DOCUMENT_ID_LENGTH {
+            return Err(format!(
+                "Composite primary key '{}' is longer than {} bytes after encoding", raw, MAX_DOCUMENT_ID_LENGTH
+            ));
+        }
+        Ok(id)
+    }
+
+    fn parse_template(template: &str) -> Result<Vec<TemplatePart>, String> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(TemplatePart::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .map(|offset| start + offset)
+                .ok_or_else(|| format!("Unclosed '{{' in key template '{}'", template))?;
+            let name = rest[start + 1..end].trim();
+            if name.is_empty() || name.contains('{') {
+                return Err(format!("Invalid field name in key template '{}'", template));
+            }
+            parts.push(TemplatePart::Field(name.to_string()));
+            rest = &rest[end + 1..];
+        }
+        if rest.contains('}') {
+            return Err(format!("Unexpected '}}' in key template '{}'", template));
+        }
+        if !rest.is_empty() {
+            parts.push(TemplatePart::Literal(rest.to_string()));
+        }
+
+        if !parts.iter().any(|part| matches!(part, TemplatePart::Field(_))) {
+            return Err(format!("Key template '{}' must reference at least one field", template));
+        }
+
+        Ok(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_template() {
+        let key = DocumentKey::new("id", Some("{tenant_id}-{order_id}")).unwrap();
+        assert_eq!(
+            key.template().unwrap(),
+            &[
+                TemplatePart::Field("tenant_id".to_string()),
+                TemplatePart::Literal("-".to_string()),
+                TemplatePart::Field("order_id".to_string()),
+            ]
+        );
+        assert!(DocumentKey::new("id", Some("{tenant_id")).is_err());
+        assert!(DocumentKey::new("id", Some("static")).is_err());
+    }
+
+    #[test]
+    fn test_composite_key_is_sanitized() {
+        let key = DocumentKey::new("id", Some("{tenant}:{sku}")).unwrap();
+        let mut document = json!({ "tenant": "acme", "sku": "A/1", RENDERED_KEY_COLUMN: "acme:A/1" });
+
+        assert_eq!(key.take_key(&mut document).unwrap(), json!("acme:A/1"));
+        assert_eq!(document, json!({ "tenant": "acme", "sku": "A/1", "id": "acme_3AA_2F1" }));
+    }
+
+    #[test]
+    fn test_sanitized_keys_do_not_collide() {
+        let keys = ["a/b", "a.b", "a_b", "a_2Fb", "é", "ü", "ключ", "ключи"];
+        let ids: Vec<String> = keys.iter().map(|raw| DocumentKey::sanitize(raw).unwrap()).collect();
+        for (i, id) in ids.iter().enumerate() {
+            assert!(id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'), "{}", id);
+            assert!(!ids[i + 1..].contains(id), "{} collides", keys[i]);
+        }
+
+        // Длинные ключи с общим префиксом не обрезаются до одного id
+        let long = "x".repeat(MAX_DOCUMENT_ID_LENGTH);
+        assert_eq!(DocumentKey::sanitize(&long).unwrap(), long);
+        assert!(DocumentKey::sanitize(&format!("{}a", long)).is_err());
+        assert!(DocumentKey::sanitize(&"/".repeat(200)).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use serde_json::{Map, Number, Value};
+use crate::domain::data_source::entities::transformation_spec::{CastType, TransformationSpec};
+
+/// Применяет TransformationSpec к документам, полученным из QueryExecutor
+pub struct DocumentTransformer;
+
+impl DocumentTransformer {
+    /// Преобразование не должно трогать первичный ключ: иначе документ уйдёт в индекс без id
+    /// или с другим id, чем у строки источника
+    pub fn validate(spec: &TransformationSpec, key_field: &str) -> Result<(), String> {
+        let touches = |field: &str| field == key_field;
+        if spec.rename.iter().any(|(from, to)| touches(from) || touches(to)) {
+            return Err(format!("Transformation must not rename the primary key '{}'", key_field));
+        }
+        if spec.concat.iter().any(|rule| touches(&rule.target)) {
+            return Err(format!("Transformation must not overwrite the primary key '{}' with concat", key_field));
+        }
+        if spec.split.contains_key(key_field) {
+            return Err(format!("Transformation must not split the primary key '{}'", key_field));
+        }
+        if spec.drop.iter().any(|field| touches(field)) {
+            return Err(format!("Transformation must not drop the primary key '{}'", key_field));
+        }
+        if spec.nest && key_field.contains('.') {
+            return Err(format!("Primary key '{}' contains a dot and would be nested; set nest to false", key_field));
+        }
+        Ok(())
+    }
+
+    pub fn apply_all(spec: &TransformationSpec, documents: Vec<Value>) -> Result<Vec<Value>, String> {
+        documents
+            .into_iter()
+            .map(|document| Self::apply(spec, document))
+            .collect()
+    }
+
+    pub fn apply(spec: &TransformationSpec, document: Value) -> Result<Value, String> {
+        let Value::Object(mut fields) = document else {
+            return Ok(document);
+        };
+
+        for (from, to) in &spec.rename {
+            if let Some(value) = fields.remove(from) {
+                fields.insert(to.clone(), value);
+            }
+        }
+
+        for rule in &spec.concat {
+            let parts: Vec<String> = rule.fields
+                .iter()
+                .filter_map(|field| fields.get(field))
+                .filter_map(Self::to_text)
+                .collect();
+            fields.insert(rule.target.clone(), Value::String(parts.join(&rule.separator)));
+        }
+
+        for (field, delimiter) in &spec.split {
+            if let Some(Value::String(text)) = fields.get(field) {
+                let items = text
+                    .split(delimiter.as_str())
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| Value::String(item.to_string()))
+                    .collect();
+                fields.insert(field.clone(), Value::Array(items));
+            }
+        }
+
+        for (field, cast_type) in &spec.cast {
+            if let Some(value) = fields.get_mut(field) {
+                *value = Self::cast(value, *cast_type)
+                    .map_err(|e| format!("Cannot cast field '{}': {}", field, e))?;
+            }
+        }
+
+        for field in &spec.drop {
+            fields.remove(field);
+        }
+
+        if spec.nest {
+            fields = Self::nest(fields);
+        }
+
+        Ok(Value::Object(fields))
+    }
+
+    fn to_text(value: &Value) -> Option<String> {
+        match value {
+            Value::Null => None,
+            Value::String(text) => Some(text.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    fn cast(value: &Value, cast_type: CastType) -> Result<Value, String> {
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+
+        match cast_type {
+            CastType::String => Ok(Value::String(Self::to_text(value).unwrap_or_default())),
+            CastType::Integer => match value {
+                Value::Number(number) if number.is_i64() || number.is_u64() => Ok(value.clone()),
+                Value::Number(number) => Ok(Value::from(number.as_f64().unwrap_or_default().trunc() as i64)),
+                Value::Bool(flag) => Ok(Value::from(*flag as i64)),
+                Value::String(text) => text.trim().parse::<i64>()
+                    .map(Value::from)
+                    .map_err(|_| format!("'{}' is not an integer", text)),
+                other => Err(format!("{} is not an integer", other)),
+            },
+            CastType::Float => {
+                let number = match value {
+                    Value::Number(number) => number.as_f64(),
+                    Value::String(text) => text.trim().parse::<f64>().ok(),
+                    _ => None,
+                };
+                number
+                    .and_then(Number::from_f64)
+                    .map(Value::Number)
+                    .ok_or_else(|| format!("{} is not a number", value))
+            }
+            CastType::Boolean => match value {
+                Value::Bool(_) => Ok(value.clone()),
+                Value::Number(number) => Ok(Value::Bool(number.as_f64() != Some(0.0))),
+                Value::String(text) => match text.trim().to_lowercase().as_str() {
+                    "true" | "t" | "1" | "yes" | "y" => Ok(Value::Bool(true)),
+                    "false" | "f" | "0" | "no" | "n" | "" => Ok(Value::Bool(false)),
+                    _ => Err(format!("'{}' is not a boolean", text)),
+                },
+                other => Err(format!("{} is not a boolean", other)),
+            },
+        }
+    }
+
+    /// Разворачивает ключи с точкой во вложенные объекты
+    fn nest(fields: Map<String, Value>) -> Map<String, Value> {
+        let mut nested = Map::new();
+
+        for (key, value) in fields {
+            let mut path: Vec<&str> = key.split('.').collect();
+            if path.len() == 1 || path.iter().any(|segment| segment.is_empty()) {
+                nested.insert(key, value);
+                continue;
+            }
+
+            let leaf = path.pop().unwrap_or_default();
+            let mut current = &mut nested;
+            for segment in path {
+                let entry = current
+                    .entry(segment.to_string())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if !entry.is_object() {
+                    *entry = Value::Object(Map::new());
+                }
+                current = entry.as_object_mut().expect("entry is an object");
+            }
+            current.insert(leaf.to_string(), value);
+        }
+
+        nested
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(value: Value) -> TransformationSpec {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_rename_concat_drop_and_nest() {
+        let spec = spec(json!({
+            "rename": { "author_name": "author.name" },
+            "concat": [{ "target": "title", "fields": ["brand", "model"] }],
+            "drop": ["brand", "model"]
+        }));
+        let document = json!({ "id": 1, "author_name": "Ann", "brand": "Acme", "model": "X1" });
+
+        assert_eq!(
+            DocumentTransformer::apply(&spec, document).unwrap(),
+            json!({ "id": 1, "author": { "name": "Ann" }, "title": "Acme X1" })
+        );
+    }
+
+    #[test]
+    fn test_split_and_cast() {
+        let spec = spec(json!({
+            "split": { "tags": "," },
+            "cast": { "price": "float", "stock": "integer", "active": "boolean" }
+        }));
+        let document = json!({ "tags": "a, b,,c", "price": "9.5", "stock": "12", "active": "yes" });
+
+        assert_eq!(
+            DocumentTransformer::apply(&spec, document).unwrap(),
+            json!({ "tags": ["a", "b", "c"], "price": 9.5, "stock": 12, "active": true })
+        );
+    }
+
+    #[test]
+    fn test_invalid_cast_is_reported() {
+        let spec = spec(json!({ "cast": { "stock": "integer" } }));
+        assert!(DocumentTransformer::apply(&spec, json!({ "stock": "many" })).is_err());
+    }
+
+    #[test]
+    fn test_spec_must_keep_the_primary_key() {
+        assert!(DocumentTransformer::validate(&spec(json!({ "rename": { "name": "title" }, "drop": ["secret"] })), "id").is_ok());
+        assert!(DocumentTransformer::validate(&spec(json!({ "rename": { "id": "sku" } })), "id").is_err());
+        assert!(DocumentTransformer::validate(&spec(json!({ "rename": { "sku": "id" } })), "id").is_err());
+        assert!(DocumentTransformer::validate(&spec(json!({ "drop": ["id"] })), "id").is_err());
+        assert!(DocumentTransformer::validate(&spec(json!({})), "product.id").is_err());
+        assert!(DocumentTransformer::validate(&spec(json!({ "nest": false })), "product.id").is_ok());
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::data_source_connector::{ConnectionField, ConnectorDescriptor, DataSourceConnector, FieldKind, QueryContext, SourceObject};
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::file_query_executor::FileQueryExecutor;
+use crate::domain::data_source::services::file_source_reader::FileSourceReader;
+use crate::domain::data_source::services::query_executor::{CursorWindow, QueryPreview};
+
+/// Сколько строк каждого файла читается, чтобы вывести его колонки
+const INTROSPECT_SAMPLE: u32 = 100;
+
+/// Файлы CSV, JSON и NDJSON на сервере (`database_type = "file"`). Текст запроса не выполняется.
+pub struct FileConnector;
+
+impl FileConnector {
+    fn executor(context: &QueryContext) -> FileQueryExecutor {
+        FileQueryExecutor::new(context.file_root.clone())
+    }
+}
+
+#[async_trait]
+impl DataSourceConnector for FileConnector {
+    fn descriptor(&self) -> ConnectorDescriptor {
+        ConnectorDescriptor {
+            database_type: "file",
+            label: "CSV / JSON / NDJSON files",
+            fields: vec![
+                ConnectionField::new("database_path", "File path", FieldKind::Path)
+                    .required()
+                    .with_help("Path or glob pattern inside FILE_SOURCES_ROOT, e.g. export/*.csv"),
+                ConnectionField::new("file_options.format", "Format", FieldKind::Select)
+                    .with_options(&["csv", "json", "ndjson"])
+                    .with_help("Detected from the file extension when empty"),
+                ConnectionField::new("file_options.delimiter", "CSV delimiter", FieldKind::Text).with_default(Value::from(",")),
+                ConnectionField::new("file_options.quote", "CSV quote", FieldKind::Text).with_default(Value::from("\"")),
+                ConnectionField::new("file_options.escape", "CSV escape", FieldKind::Text)
+                    .with_help("Without it a quote inside a field is doubled"),
+                ConnectionField::new("file_options.keep_strings", "Keep CSV values as strings", FieldKind::Boolean)
+                    .with_default(Value::Bool(false)),
+            ],
+        }
+    }
+
+    /// Файлы есть, и первая строка первого из них читается
+    async fn test_connection(&self, context: &QueryContext, data_source: &DataSource) -> Result<(), String> {
+        FileSourceReader::new(data_source.file_options.clone(), context.file_root.clone()).read(&data_source.database_path, |_| false)?;
+        Ok(())
+    }
+
+    async fn introspect(&self, context: &QueryContext, data_source: &DataSource) -> Result<Vec<SourceObject>, String> {
+        Self::executor(context).introspect(data_source, INTROSPECT_SAMPLE).await
+    }
+
+    async fn preview(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        _query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String> {
+        Self::executor(context).preview(data_source, limit).await
+    }
+
+    async fn stream_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        _query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        _chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        Self::executor(context).stream_query(data_source, key, cursor, sender).await
+    }
+
+    /// Без лимита это дочерний запрос, а связывать файлы между собой нечем
+    async fn fetch_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        _query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Value>, String> {
+        match limit {
+            Some(limit) => Self::executor(context).fetch_all(data_source, limit).await,
+            None => Err("Child queries are not supported for file data sources".to_string()),
+        }
+    }
+
+    async fn fetch_cursor_bounds(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        _query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        Self::executor(context).fetch_cursor_bounds(data_source, cursor_column).await
+    }
+
+    async fn fetch_keys(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        _query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String> {
+        Self::executor(context).fetch_keys(data_source, key).await
+    }
+}
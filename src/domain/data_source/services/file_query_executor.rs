@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Instant;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::data_source_connector::SourceObject;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::file_source_reader::FileSourceReader;
+use crate::domain::data_source::services::query_executor::{CursorWindow, QueryPreview};
+use crate::domain::data_source::services::row_filter::RowFilter;
+
+/// Выборка из файловых источников (CSV, JSON, NDJSON). Файлы — это уже готовые строки,
+/// поэтому текст запроса не выполняется и игнорируется; курсор и ключ применяются при чтении.
+/// Чтение блокирующее и идёт в `spawn_blocking`.
+pub(crate) struct FileQueryExecutor {
+    /// Каталог, за пределы которого пути источников не выходят
+    root: PathBuf,
+}
+
+impl FileQueryExecutor {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Первые `limit` строк файлов
+    pub(crate) async fn fetch_all(&self, data_source: &DataSource, limit: u32) -> Result<Vec<Value>, String> {
+        self.scan(data_source, move |reader, pattern| {
+            let mut rows = Vec::new();
+            reader.read(pattern, |row| {
+                if rows.len() >= limit as usize {
+                    return false;
+                }
+                rows.push(row);
+                rows.len() < limit as usize
+            })?;
+            Ok(rows)
+        })
+        .await
+    }
+
+    /// Предпросмотр; число строк заранее неизвестно, `estimated_total` всегда пуст
+    pub(crate) async fn preview(&self, data_source: &DataSource, limit: u32) -> Result<QueryPreview, String> {
+        let started = Instant::now();
+        let rows = self.fetch_all(data_source, limit).await?;
+        let execution_time_ms = started.elapsed().as_millis() as u64;
+
+        Ok(QueryPreview {
+            columns: RowFilter::preview_columns(&rows),
+            rows,
+            warnings: Vec::new(),
+            estimated_total: None,
+            execution_time_ms,
+        })
+    }
+
+    /// Потоковая выборка в порядке строк файлов; память не зависит от их размера
+    pub(crate) async fn stream_query(
+        &self,
+        data_source: &DataSource,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        let key = key.clone();
+        let cursor = cursor.cloned();
+        self.scan(data_source, move |reader, pattern| {
+            let mut sent = 0u64;
+            let mut stopped = false;
+            reader.read(pattern, |mut row| {
+                if !RowFilter::in_window(&row, cursor.as_ref()) {
+                    return true;
+                }
+                RowFilter::render_key(&key, &mut row);
+                if sender.blocking_send(row).is_err() {
+                    stopped = true;
+                    return false;
+                }
+                sent += 1;
+                true
+            })?;
+            if stopped {
+                return Err("Row consumer stopped".to_string());
+            }
+            Ok(sent)
+        })
+        .await
+    }
+
+    /// Максимум курсорной колонки как текст и его тип: `number` или `string`
+    pub(crate) async fn fetch_cursor_bounds(
+        &self,
+        data_source: &DataSource,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        let cursor_column = cursor_column.to_string();
+        self.scan(data_source, move |reader, pattern| {
+            let mut max: Option<Value> = None;
+            reader.read(pattern, |row| {
+                RowFilter::track_max(&mut max, &row, &cursor_column);
+                true
+            })?;
+            Ok(RowFilter::cursor_bounds(max))
+        })
+        .await
+    }
+
+    /// Все значения первичного ключа в виде строк
+    pub(crate) async fn fetch_keys(&self, data_source: &DataSource, key: &DocumentKey) -> Result<HashSet<String>, String> {
+        let key = key.clone();
+        self.scan(data_source, move |reader, pattern| {
+            let mut keys = HashSet::new();
+            let mut error = None;
+            reader.read(pattern, |mut row| {
+                RowFilter::render_key(&key, &mut row);
+                match RowFilter::key_string(&key, &row) {
+                    Ok(value) => keys.extend(value),
+                    Err(e) => error = Some(e),
+                }
+                error.is_none()
+            })?;
+            match error {
+                Some(e) => Err(e),
+                None => Ok(keys),
+            }
+        })
+        .await
+    }
+
+    /// Каждый файл — отдельный объект; колонки выводятся по первым `sample` строкам файла
+    pub(crate) async fn introspect(&self, data_source: &DataSource, sample: u32) -> Result<Vec<SourceObject>, String> {
+        self.scan(data_source, move |reader, pattern| {
+            reader.paths(pattern)?
+                .iter()
+                .map(|path| {
+                    let path = path.to_string_lossy();
+                    let mut rows = Vec::new();
+                    reader.read(&path, |row| {
+                        rows.push(row);
+                        rows.len() < sample as usize
+                    })?;
+                    Ok(SourceObject::from_rows(path.to_string(), &rows))
+                })
+                .collect()
+        })
+        .await
+    }
+
+    /// Запускает чтение файлов источника в пуле блокирующих задач
+    async fn scan<T: Send + 'static>(
+        &self,
+        data_source: &DataSource,
+        scan: impl FnOnce(&FileSourceReader, &str) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let reader = FileSourceReader::new(data_source.file_options.clone(), self.root.clone());
+        let pattern = data_source.database_path.clone();
+        tokio::task::spawn_blocking(move || scan(&reader, &pattern))
+            .await
+            .map_err(|e| format!("File reader failed: {}", e))?
+    }
+}
@@ -0,0 +1,357 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Component, Path, PathBuf};
+use serde::de::{Deserializer, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+use crate::domain::data_source::entities::file_source_options::{FileFormat, FileSourceOptions};
+
+/// Чтение строк файлового источника: CSV со строкой заголовков, JSON-массив объектов или NDJSON.
+/// Файлы читаются потоком, по одной строке, поэтому размер файла не ограничен памятью.
+/// Чтение синхронное; из async-кода его запускают через `spawn_blocking`.
+pub struct FileSourceReader {
+    options: FileSourceOptions,
+    /// Каталог файловых источников (FILE_SOURCES_ROOT); читать можно только файлы внутри него
+    root: PathBuf,
+}
+
+impl FileSourceReader {
+    pub fn new(options: FileSourceOptions, root: PathBuf) -> Self {
+        Self { options, root }
+    }
+
+    /// Файлы по пути или glob-шаблону, в алфавитном порядке; ни одного файла — ошибка.
+    /// Относительный шаблон отсчитывается от каталога источников, `..` в шаблоне запрещено,
+    /// а каждый найденный файл после разрешения символических ссылок должен лежать внутри каталога.
+    pub fn paths(&self, pattern: &str) -> Result<Vec<PathBuf>, String> {
+        if Path::new(pattern).components().any(|component| component == Component::ParentDir) {
+            return Err(format!("File pattern '{}' must not contain '..'", pattern));
+        }
+        let root = self
+            .root
+            .canonicalize()
+            .map_err(|e| format!("File sources directory {} is not available: {}", self.root.display(), e))?;
+        let full_pattern = if Path::new(pattern).is_absolute() {
+            pattern.to_string()
+        } else {
+            format!("{}/{}", glob::Pattern::escape(&root.to_string_lossy()), pattern)
+        };
+
+        let mut paths = Vec::new();
+        for path in glob::glob(&full_pattern).map_err(|e| format!("Invalid file pattern '{}': {}", pattern, e))? {
+            let path = path.map_err(|e| format!("Cannot read {}: {}", e.path().display(), e.error()))?;
+            if !path.is_file() {
+                continue;
+            }
+            let canonical = path.canonicalize().map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            if !canonical.starts_with(&root) {
+                return Err(format!("{} is outside the file sources directory {}", path.display(), root.display()));
+            }
+            paths.push(canonical);
+        }
+        if paths.is_empty() {
+            return Err(format!("No files match '{}'", pattern));
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Передаёт строки всех файлов по порядку в `row`; `row` возвращает false, чтобы остановить чтение.
+    /// Возвращает число прочитанных строк.
+    pub fn read(&self, pattern: &str, mut row: impl FnMut(Value) -> bool) -> Result<u64, String> {
+        let mut total = 0u64;
+        for path in self.paths(pattern)? {
+            let format = self.format(&path)?;
+            let mut stopped = false;
+            let mut counted = |document: Value| {
+                total += 1;
+                stopped = !row(document);
+                !stopped
+            };
+            match format {
+                FileFormat::Csv => self.read_csv(&path, &mut counted),
+                FileFormat::Json => Self::read_json(&path, &mut counted),
+                FileFormat::Ndjson => Self::read_ndjson(&path, &mut counted),
+            }
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+            if stopped {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    fn format(&self, path: &Path) -> Result<FileFormat, String> {
+        if let Some(format) = self.options.format {
+            return Ok(format);
+        }
+        let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" | "tsv" | "txt" => Ok(FileFormat::Csv),
+            "json" => Ok(FileFormat::Json),
+            "ndjson" | "jsonl" => Ok(FileFormat::Ndjson),
+            _ => Err(format!("Cannot detect the format of {}, set file_options.format", path.display())),
+        }
+    }
+
+    fn read_csv(&self, path: &Path, row: &mut impl FnMut(Value) -> bool) -> Result<(), String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .delimiter(Self::byte(self.options.delimiter, b',')?)
+            .quote(Self::byte(self.options.quote, b'"')?)
+            .escape(self.options.escape.map(|escape| Self::byte(Some(escape), b'\\')).transpose()?)
+            .double_quote(self.options.escape.is_none())
+            .from_path(path)
+            .map_err(|e| e.to_string())?;
+        let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+
+        for record in reader.records() {
+            let record = record.map_err(|e| e.to_string())?;
+            let document: Map<String, Value> = headers
+                .iter()
+                .zip(record.iter())
+                .map(|(header, field)| {
+                    let value = if self.options.keep_strings { Value::String(field.to_string()) } else { Self::infer(field) };
+                    (header.to_string(), value)
+                })
+                .collect();
+            if !row(Value::Object(document)) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_ndjson(path: &Path, row: &mut impl FnMut(Value) -> bool) -> Result<(), String> {
+        let reader = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+        for (number, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let document: Value = serde_json::from_str(&line).map_err(|e| format!("line {}: {}", number + 1, e))?;
+            if !document.is_object() {
+                return Err(format!("line {}: expected a JSON object", number + 1));
+            }
+            if !row(document) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Элементы массива разбираются по одному, без загрузки всего файла
+    fn read_json(path: &Path, row: &mut impl FnMut(Value) -> bool) -> Result<(), String> {
+        let reader = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+        let mut stopped = false;
+        let visitor = ArrayVisitor { row, stopped: &mut stopped };
+        match serde_json::Deserializer::from_reader(reader).deserialize_seq(visitor) {
+            Ok(()) => Ok(()),
+            // Остаток массива после остановки не дочитывается, и serde_json считает это ошибкой
+            Err(_) if stopped => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Значение поля CSV: пустое — null, `true`/`false` — логическое, числа — числом.
+    /// Целые с ведущими нулями (`007`, почтовые индексы, артикулы) и целые вне i64 остаются строками.
+    pub fn infer(field: &str) -> Value {
+        if field.is_empty() {
+            return Value::Null;
+        }
+        if field.eq_ignore_ascii_case("true") {
+            return Value::Bool(true);
+        }
+        if field.eq_ignore_ascii_case("false") {
+            return Value::Bool(false);
+        }
+
+        let digits = field.strip_prefix('-').unwrap_or(field);
+        let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+        let numeric = digits.starts_with(|c: char| c.is_ascii_digit())
+            && digits.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '-' | '+'));
+        let fractional = digits.contains(['.', 'e', 'E']);
+        if numeric && !leading_zero {
+            if let Ok(integer) = field.parse::<i64>() {
+                return Value::from(integer);
+            }
+            if fractional
+                && let Some(number) = field.parse::<f64>().ok().and_then(Number::from_f64)
+            {
+                return Value::Number(number);
+            }
+        }
+        Value::String(field.to_string())
+    }
+
+    fn byte(value: Option<char>, default: u8) -> Result<u8, String> {
+        match value {
+            None => Ok(default),
+            Some(c) if c.is_ascii() => Ok(c as u8),
+            Some(c) => Err(format!("CSV delimiter, quote and escape must be ASCII characters, got '{}'", c)),
+        }
+    }
+}
+
+struct ArrayVisitor<'a, F> {
+    row: &'a mut F,
+    stopped: &'a mut bool,
+}
+
+impl<'de, F: FnMut(Value) -> bool> Visitor<'de> for ArrayVisitor<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON array of objects")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(document) = seq.next_element::<Value>()? {
+            if !document.is_object() {
+                return Err(serde::de::Error::custom("expected an array of JSON objects"));
+            }
+            if !(self.row)(document) {
+                *self.stopped = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn infers_csv_field_types() {
+        assert_eq!(FileSourceReader::infer("42"), json!(42));
+        assert_eq!(FileSourceReader::infer("-19.90"), json!(-19.9));
+        assert_eq!(FileSourceReader::infer("1e3"), json!(1000.0));
+        assert_eq!(FileSourceReader::infer("TRUE"), json!(true));
+        assert_eq!(FileSourceReader::infer(""), Value::Null);
+        assert_eq!(FileSourceReader::infer("007"), json!("007"));
+        assert_eq!(FileSourceReader::infer("0.5"), json!(0.5));
+        assert_eq!(FileSourceReader::infer("2024-01-01"), json!("2024-01-01"));
+        assert_eq!(FileSourceReader::infer("12345678901234567890"), json!("12345678901234567890"));
+    }
+
+    #[test]
+    fn paths_stay_inside_the_root() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("a.csv"), "id\n1\n").unwrap();
+        std::fs::write(outside.path().join("secret.csv"), "id\n1\n").unwrap();
+        let reader = FileSourceReader::new(FileSourceOptions::default(), root.path().to_path_buf());
+
+        let root_path = root.path().canonicalize().unwrap();
+        assert_eq!(reader.paths("*.csv").unwrap(), vec![root_path.join("a.csv")]);
+        assert_eq!(reader.paths(&root_path.join("a.csv").to_string_lossy()).unwrap(), vec![root_path.join("a.csv")]);
+
+        assert!(reader.paths("../*/secret.csv").unwrap_err().contains(".."));
+        assert!(reader.paths(&outside.path().join("*.csv").to_string_lossy()).unwrap_err().contains("outside"));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(outside.path().join("secret.csv"), root.path().join("link.csv")).unwrap();
+            assert!(reader.paths("link.csv").unwrap_err().contains("outside"));
+        }
+    }
+
+    fn reader(root: &Path, options: FileSourceOptions) -> FileSourceReader {
+        FileSourceReader::new(options, root.to_path_buf())
+    }
+
+    fn read_all(reader: &FileSourceReader, pattern: &str) -> Result<Vec<Value>, String> {
+        let mut rows = Vec::new();
+        reader.read(pattern, |row| {
+            rows.push(row);
+            true
+        })?;
+        Ok(rows)
+    }
+
+    #[test]
+    fn reads_csv_with_custom_delimiter_quote_and_escape() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("semicolon.csv"), "id;name;note\n1;'Smith; J.';'\\'quoted\\''\n2;Doe;\n").unwrap();
+        std::fs::write(root.path().join("doubled.csv"), "id,name\n1,\"say \"\"hi\"\"\"\n").unwrap();
+
+        let options = FileSourceOptions {
+            delimiter: Some(';'),
+            quote: Some('\''),
+            escape: Some('\\'),
+            ..FileSourceOptions::default()
+        };
+        assert_eq!(
+            read_all(&reader(root.path(), options), "semicolon.csv").unwrap(),
+            vec![
+                json!({"id": 1, "name": "Smith; J.", "note": "'quoted'"}),
+                json!({"id": 2, "name": "Doe", "note": null}),
+            ]
+        );
+
+        let keep_strings = FileSourceOptions { keep_strings: true, ..FileSourceOptions::default() };
+        assert_eq!(
+            read_all(&reader(root.path(), keep_strings), "doubled.csv").unwrap(),
+            vec![json!({"id": "1", "name": "say \"hi\""})]
+        );
+    }
+
+    #[test]
+    fn reads_json_array_and_stops_early() {
+        let root = tempfile::tempdir().unwrap();
+        // После третьего элемента файл испорчен: остановленное чтение до него не доходит
+        std::fs::write(root.path().join("rows.json"), r#"[{"id": 1}, {"id": 2}, {"id": 3}, oops"#).unwrap();
+        std::fs::write(root.path().join("scalars.json"), "[1, 2]").unwrap();
+        let reader = reader(root.path(), FileSourceOptions::default());
+
+        let mut rows = Vec::new();
+        let total = reader
+            .read("rows.json", |row| {
+                rows.push(row);
+                rows.len() < 2
+            })
+            .unwrap();
+        assert_eq!((total, rows), (2, vec![json!({"id": 1}), json!({"id": 2})]));
+
+        assert!(read_all(&reader, "rows.json").is_err());
+        assert!(read_all(&reader, "scalars.json").unwrap_err().contains("array of JSON objects"));
+    }
+
+    #[test]
+    fn reads_ndjson_skipping_blank_lines() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("rows.ndjson"), "{\"id\": 1}\n\n   \n{\"id\": 2}\n").unwrap();
+        std::fs::write(root.path().join("broken.ndjson"), "{\"id\": 1}\n[1, 2]\n").unwrap();
+        let reader = reader(root.path(), FileSourceOptions::default());
+
+        assert_eq!(read_all(&reader, "rows.ndjson").unwrap(), vec![json!({"id": 1}), json!({"id": 2})]);
+        let error = read_all(&reader, "broken.ndjson").unwrap_err();
+        assert!(error.contains("line 2: expected a JSON object"), "{}", error);
+    }
+
+    #[test]
+    fn reads_glob_matches_in_alphabetical_order() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("export")).unwrap();
+        std::fs::write(root.path().join("export/b.ndjson"), "{\"id\": 3}\n").unwrap();
+        std::fs::write(root.path().join("export/a.csv"), "id\n1\n2\n").unwrap();
+        std::fs::write(root.path().join("export/c.json"), "[{\"id\": 4}]").unwrap();
+        std::fs::write(root.path().join("export/notes.md"), "# not data").unwrap();
+        let reader = reader(root.path(), FileSourceOptions::default());
+
+        let ids: Vec<Value> = read_all(&reader, "export/*.*json")
+            .unwrap()
+            .into_iter()
+            .chain(read_all(&reader, "export/*.csv").unwrap())
+            .map(|row| row["id"].clone())
+            .collect();
+        assert_eq!(ids, vec![json!(3), json!(4), json!(1), json!(2)]);
+
+        let ids: Vec<Value> = read_all(&reader, "export/[abc].*").unwrap().into_iter().map(|row| row["id"].clone()).collect();
+        assert_eq!(ids, vec![json!(1), json!(2), json!(3), json!(4)]);
+        assert!(read_all(&reader, "export/*.md").unwrap_err().contains("Cannot detect the format"));
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::data_source_connector::{ConnectionField, ConnectorDescriptor, DataSourceConnector, FieldKind, QueryContext, SourceObject};
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::http_query_executor::HttpQueryExecutor;
+use crate::domain::data_source::services::query_executor::{CursorWindow, QueryPreview};
+
+/// Сколько записей читается, чтобы вывести колонки ответа
+const INTROSPECT_SAMPLE: u32 = 100;
+
+/// JSON API по HTTP (`database_type = "http"`). Текст запроса — путь относительно базового URL.
+pub struct HttpConnector;
+
+impl HttpConnector {
+    fn executor(context: &QueryContext) -> HttpQueryExecutor {
+        HttpQueryExecutor::new(context.statement_timeout)
+    }
+}
+
+#[async_trait]
+impl DataSourceConnector for HttpConnector {
+    fn descriptor(&self) -> ConnectorDescriptor {
+        ConnectorDescriptor {
+            database_type: "http",
+            label: "HTTP JSON API",
+            fields: vec![
+                ConnectionField::new("database_path", "Base URL", FieldKind::Url).required(),
+                ConnectionField::new("password", "Bearer token", FieldKind::Password),
+                ConnectionField::new("http_options.headers", "Headers", FieldKind::Json)
+                    .with_help("Extra request headers, e.g. {\"Accept\": \"application/json\"}"),
+                ConnectionField::new("http_options.records_path", "Records path", FieldKind::Text)
+                    .with_help("JSONPath to the array of records, e.g. $.data.items; the whole response by default"),
+                ConnectionField::new("http_options.pagination", "Pagination", FieldKind::Json)
+                    .with_help("{\"type\": \"none\" | \"page_number\" | \"offset\" | \"cursor\" | \"link_header\", ...}"),
+                ConnectionField::new("http_options.max_pages", "Max pages", FieldKind::Number),
+            ],
+        }
+    }
+
+    /// Первая страница отвечает, и записи находятся по records_path
+    async fn test_connection(&self, context: &QueryContext, data_source: &DataSource) -> Result<(), String> {
+        Self::executor(context).fetch_all(data_source, "", 1).await?;
+        Ok(())
+    }
+
+    async fn introspect(&self, context: &QueryContext, data_source: &DataSource) -> Result<Vec<SourceObject>, String> {
+        Self::executor(context).introspect(data_source, INTROSPECT_SAMPLE).await
+    }
+
+    async fn preview(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String> {
+        Self::executor(context).preview(data_source, query, limit).await
+    }
+
+    async fn stream_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        _chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        Self::executor(context).stream_query(data_source, query, key, cursor, sender).await
+    }
+
+    /// Без лимита это дочерний запрос, а связывать ответы API между собой нечем
+    async fn fetch_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Value>, String> {
+        match limit {
+            Some(limit) => Self::executor(context).fetch_all(data_source, query, limit).await,
+            None => Err("Child queries are not supported for http data sources".to_string()),
+        }
+    }
+
+    async fn fetch_cursor_bounds(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        Self::executor(context).fetch_cursor_bounds(data_source, query, cursor_column).await
+    }
+
+    async fn fetch_keys(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String> {
+        Self::executor(context).fetch_keys(data_source, query, key).await
+    }
+}
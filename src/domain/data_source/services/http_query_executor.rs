@@ -0,0 +1,461 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use reqwest::header::{HeaderMap, LINK};
+use reqwest::{Client, Url};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::entities::http_source_options::{HttpPagination, HttpSourceOptions};
+use crate::domain::data_source::services::data_source_connector::SourceObject;
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::json_path::JsonPath;
+use crate::domain::data_source::services::query_executor::{CursorWindow, QueryPreview};
+use crate::domain::data_source::services::row_filter::RowFilter;
+
+/// Выборка из JSON API по HTTP. Текст запроса — путь относительно базового URL источника
+/// (`/articles?status=published`), пустой запрос читает сам базовый URL.
+/// Страницы запрашиваются по одной, statement_timeout ограничивает каждый HTTP-запрос.
+pub(crate) struct HttpQueryExecutor {
+    statement_timeout: Duration,
+}
+
+impl HttpQueryExecutor {
+    pub(crate) fn new(statement_timeout: Duration) -> Self {
+        Self { statement_timeout }
+    }
+
+    /// Первые `limit` записей
+    pub(crate) async fn fetch_all(&self, data_source: &DataSource, query: &str, limit: u32) -> Result<Vec<Value>, String> {
+        let mut pager = self.pager(data_source, query)?;
+        let mut rows = Vec::new();
+        while rows.len() < limit as usize {
+            let Some(page) = pager.next_page().await? else {
+                break;
+            };
+            rows.extend(page);
+        }
+        rows.truncate(limit as usize);
+        Ok(rows)
+    }
+
+    /// Предпросмотр; общее число записей API не сообщает, `estimated_total` всегда пуст
+    pub(crate) async fn preview(&self, data_source: &DataSource, query: &str, limit: u32) -> Result<QueryPreview, String> {
+        let started = Instant::now();
+        let rows = self.fetch_all(data_source, query, limit).await?;
+        let execution_time_ms = started.elapsed().as_millis() as u64;
+
+        Ok(QueryPreview {
+            columns: RowFilter::preview_columns(&rows),
+            rows,
+            warnings: Vec::new(),
+            estimated_total: None,
+            execution_time_ms,
+        })
+    }
+
+    /// Базовый URL — единственный объект источника; колонки выводятся по первым `sample` записям
+    pub(crate) async fn introspect(&self, data_source: &DataSource, sample: u32) -> Result<Vec<SourceObject>, String> {
+        let rows = self.fetch_all(data_source, "", sample).await?;
+        Ok(vec![SourceObject::from_rows(data_source.database_path.clone(), &rows)])
+    }
+
+    /// Потоковая выборка: записи уходят в канал по мере получения страниц
+    pub(crate) async fn stream_query(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        let mut pager = self.pager(data_source, query)?;
+        let mut sent = 0u64;
+        while let Some(page) = pager.next_page().await? {
+            for mut row in page {
+                if !RowFilter::in_window(&row, cursor) {
+                    continue;
+                }
+                RowFilter::render_key(key, &mut row);
+                if sender.send(row).await.is_err() {
+                    return Err("Row consumer stopped".to_string());
+                }
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Максимум курсорного поля как текст и его тип: `number` или `string`
+    pub(crate) async fn fetch_cursor_bounds(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        let mut pager = self.pager(data_source, query)?;
+        let mut max: Option<Value> = None;
+        while let Some(page) = pager.next_page().await? {
+            for row in &page {
+                RowFilter::track_max(&mut max, row, cursor_column);
+            }
+        }
+        Ok(RowFilter::cursor_bounds(max))
+    }
+
+    /// Все значения первичного ключа в виде строк
+    pub(crate) async fn fetch_keys(&self, data_source: &DataSource, query: &str, key: &DocumentKey) -> Result<HashSet<String>, String> {
+        let mut pager = self.pager(data_source, query)?;
+        let mut keys = HashSet::new();
+        while let Some(page) = pager.next_page().await? {
+            for mut row in page {
+                RowFilter::render_key(key, &mut row);
+                keys.extend(RowFilter::key_string(key, &row)?);
+            }
+        }
+        Ok(keys)
+    }
+
+    fn pager(&self, data_source: &DataSource, query: &str) -> Result<HttpPager, String> {
+        let options = &data_source.http_options;
+        let client = Client::builder()
+            .timeout(self.statement_timeout)
+            .build()
+            .map_err(|e| format!("HTTP client error: {}", e))?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &options.headers {
+            headers.insert(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|e| format!("Invalid header '{}': {}", name, e))?,
+                value.reveal()?.parse().map_err(|e| format!("Invalid value of header '{}': {}", name, e))?,
+            );
+        }
+        let token = data_source.password.reveal()?;
+        if !token.is_empty() {
+            headers.insert(
+                reqwest::header::AUTHORIZATION,
+                format!("Bearer {}", token).parse().map_err(|_| "Invalid bearer token".to_string())?,
+            );
+        }
+
+        let records_path = JsonPath::parse(options.records_path.as_deref().unwrap_or("$"))?;
+        let cursor_path = match &options.pagination {
+            HttpPagination::Cursor { cursor_path, .. } => Some(JsonPath::parse(cursor_path)?),
+            _ => None,
+        };
+        let first = HttpPager::first_url(Self::endpoint(&data_source.database_path, query)?, &options.pagination);
+
+        Ok(HttpPager {
+            client,
+            headers,
+            options: options.clone(),
+            records_path,
+            cursor_path,
+            next: Some(first),
+            page: 0,
+            offset: 0,
+            cursor: None,
+            pages: 0,
+            previous: None,
+        })
+    }
+
+    /// Базовый URL с путём из запроса
+    fn endpoint(base_url: &str, query: &str) -> Result<Url, String> {
+        let query = query.trim();
+        let url = if query.is_empty() || query.starts_with('?') {
+            format!("{}{}", base_url.trim(), query)
+        } else {
+            format!("{}/{}", base_url.trim().trim_end_matches('/'), query.trim_start_matches('/'))
+        };
+        let url = Url::parse(&url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(format!("Only http and https URLs are supported, got '{}'", url));
+        }
+        Ok(url)
+    }
+}
+
+/// Последовательное чтение страниц ответа API
+struct HttpPager {
+    client: Client,
+    headers: HeaderMap,
+    options: HttpSourceOptions,
+    records_path: JsonPath,
+    cursor_path: Option<JsonPath>,
+    /// Адрес следующей страницы; None — страниц больше нет
+    next: Option<Url>,
+    page: u64,
+    offset: u64,
+    cursor: Option<String>,
+    pages: u32,
+    /// Записи предыдущей страницы при переходе по номеру или смещению
+    previous: Option<Vec<Value>>,
+}
+
+impl HttpPager {
+    /// Адрес первой страницы: номер и смещение задаются сразу, курсор и Link берутся из ответа
+    fn first_url(endpoint: Url, pagination: &HttpPagination) -> Url {
+        match pagination {
+            HttpPagination::PageNumber { param, start, size_param, size } => {
+                let url = Self::with_param(endpoint, param, &start.to_string());
+                match (size_param, size) {
+                    (Some(size_param), Some(size)) => Self::with_param(url, size_param, &size.to_string()),
+                    _ => url,
+                }
+            }
+            HttpPagination::Offset { offset_param, limit_param, limit } => {
+                Self::with_param(Self::with_param(endpoint, offset_param, "0"), limit_param, &limit.to_string())
+            }
+            _ => endpoint,
+        }
+    }
+
+    /// Записи следующей страницы или None, если страницы кончились
+    async fn next_page(&mut self) -> Result<Option<Vec<Value>>, String> {
+        let Some(url) = self.next.take() else {
+            return Ok(None);
+        };
+        if self.options.max_pages.is_some_and(|max_pages| self.pages >= max_pages) {
+            return Ok(None);
+        }
+
+        let response = self
+            .client
+            .get(url.clone())
+            .headers(self.headers.clone())
+            .send()
+            .await
+            .map_err(|e| format!("HTTP request to {} failed: {}", url, e))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("HTTP request to {} failed with status {}", url, status));
+        }
+        let link = response.headers().get(LINK).and_then(|link| link.to_str().ok()).map(str::to_string);
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Response from {} is not valid JSON: {}", url, e))?;
+        self.pages += 1;
+
+        let records = match self.records_path.select(&body) {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(records)) => records.clone(),
+            Some(_) => return Err(format!("Value at records_path in response from {} is not an array", url)),
+        };
+        if records.iter().any(|record| !record.is_object()) {
+            return Err(format!("Records in response from {} must be JSON objects", url));
+        }
+        // API, которое не понимает номер страницы или смещение, раз за разом отдаёт одну и ту же страницу
+        if matches!(self.options.pagination, HttpPagination::PageNumber { .. } | HttpPagination::Offset { .. }) {
+            if !records.is_empty() && self.previous.as_ref() == Some(&records) {
+                return Ok(None);
+            }
+            self.previous = Some(records.clone());
+        }
+
+        self.next = self.next_url(url, &body, link.as_deref(), records.len())?;
+        Ok(Some(records))
+    }
+
+    fn next_url(&mut self, url: Url, body: &Value, link: Option<&str>, received: usize) -> Result<Option<Url>, String> {
+        if received == 0 {
+            return Ok(None);
+        }
+        match &self.options.pagination {
+            HttpPagination::None => Ok(None),
+            HttpPagination::PageNumber { param, start, size, .. } => {
+                if size.is_some_and(|size| received < size as usize) {
+                    return Ok(None);
+                }
+                self.page += 1;
+                Ok(Some(Self::with_param(url, param, &(start + self.page).to_string())))
+            }
+            HttpPagination::Offset { offset_param, limit, .. } => {
+                if received < *limit as usize {
+                    return Ok(None);
+                }
+                self.offset += received as u64;
+                Ok(Some(Self::with_param(url, offset_param, &self.offset.to_string())))
+            }
+            HttpPagination::Cursor { param, .. } => {
+                let cursor = match self.cursor_path.as_ref().and_then(|path| path.select(body)) {
+                    Some(Value::String(cursor)) => cursor.clone(),
+                    Some(Value::Number(cursor)) => cursor.to_string(),
+                    _ => return Ok(None),
+                };
+                if cursor.is_empty() {
+                    return Ok(None);
+                }
+                if self.cursor.as_deref() == Some(cursor.as_str()) {
+                    return Err(format!("API returned the same cursor '{}' twice", cursor));
+                }
+                self.cursor = Some(cursor.clone());
+                Ok(Some(Self::with_param(url, param, &cursor)))
+            }
+            HttpPagination::LinkHeader => {
+                let Some(next) = link.and_then(Self::next_link) else {
+                    return Ok(None);
+                };
+                let next_url = url.join(&next).map_err(|e| format!("Invalid next link '{}': {}", next, e))?;
+                // Заголовки источника с токеном уходят только на адрес первой страницы
+                if next_url.origin() != url.origin() {
+                    return Err(format!("Next link '{}' leads away from {}", next_url, url.origin().ascii_serialization()));
+                }
+                Ok(Some(next_url))
+            }
+        }
+    }
+
+    /// Адрес из `Link: <…>; rel="next", <…>; rel="last"`
+    fn next_link(header: &str) -> Option<String> {
+        header.split(',').find_map(|link| {
+            let (target, params) = link.split_once(';')?;
+            let is_next = params.split(';').any(|param| {
+                let param = param.trim().replace(' ', "");
+                param == "rel=\"next\"" || param == "rel=next"
+            });
+            let target = target.trim().strip_prefix('<')?.strip_suffix('>')?;
+            is_next.then(|| target.to_string())
+        })
+    }
+
+    /// Заменяет параметр строки запроса, остальные параметры сохраняются
+    fn with_param(mut url: Url, name: &str, value: &str) -> Url {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| key != name)
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs).append_pair(name, value);
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use axum::Json;
+    use axum::extract::Query;
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use serde_json::json;
+
+    /// Пять записей, которые мок-сервер отдаёт страницами по две
+    fn records(from: usize) -> Vec<Value> {
+        (from..5.min(from + 2)).map(|id| json!({"id": id, "title": format!("Item {}", id)})).collect()
+    }
+
+    fn number(params: &HashMap<String, String>, name: &str) -> usize {
+        params.get(name).and_then(|value| value.parse().ok()).unwrap_or(0)
+    }
+
+    fn source(base_url: &str, records_path: &str, pagination: HttpPagination) -> DataSource {
+        let mut data_source = DataSource::new(
+            "api".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            "secret".to_string(),
+            0,
+            base_url.to_string(),
+            String::new(),
+            "http".to_string(),
+        )
+        .unwrap_or_else(|_| unreachable!());
+        data_source.http_options = HttpSourceOptions {
+            records_path: Some(records_path.to_string()),
+            pagination,
+            ..Default::default()
+        };
+        data_source
+    }
+
+    #[tokio::test]
+    async fn reads_all_pages_from_mock_server() {
+        let app = axum::Router::new()
+            .route("/pages", get(|Query(params): Query<HashMap<String, String>>| async move {
+                Json(json!({"data": {"items": records((number(&params, "page") - 1) * 2)}}))
+            }))
+            .route("/offsets", get(|Query(params): Query<HashMap<String, String>>| async move {
+                Json(json!(records(number(&params, "offset"))))
+            }))
+            .route("/cursors", get(|Query(params): Query<HashMap<String, String>>| async move {
+                let from = number(&params, "after");
+                let next = if from + 2 < 5 { json!((from + 2).to_string()) } else { Value::Null };
+                Json(json!({"items": records(from), "next": next}))
+            }))
+            .route("/links", get(|Query(params): Query<HashMap<String, String>>| async move {
+                let from = number(&params, "from");
+                let link = format!("</links?from={}>; rel=\"next\"", from + 2);
+                let headers = if from + 2 < 5 { vec![(LINK, link)] } else { vec![] };
+                (axum::http::HeaderMap::from_iter(headers.into_iter().map(|(name, value)| (name, value.parse().unwrap()))), Json(json!({"items": records(from)})))
+                    .into_response()
+            }))
+            .route("/stuck", get(|| async { Json(json!(records(0))) }))
+            .route("/foreign", get(|| async {
+                ([(LINK, "<http://127.0.0.2:1/collect>; rel=\"next\"")], Json(json!({"items": records(0)}))).into_response()
+            }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let sources = [
+            ("/pages", source(&base_url, "$.data.items", HttpPagination::PageNumber {
+                param: "page".to_string(),
+                start: 1,
+                size_param: None,
+                size: None,
+            })),
+            ("/offsets", source(&base_url, "$", HttpPagination::Offset {
+                offset_param: "offset".to_string(),
+                limit_param: "limit".to_string(),
+                limit: 2,
+            })),
+            ("/cursors", source(&base_url, "$.items", HttpPagination::Cursor {
+                cursor_path: "$.next".to_string(),
+                param: "after".to_string(),
+            })),
+            ("links", source(&base_url, "items", HttpPagination::LinkHeader)),
+        ];
+        let executor = HttpQueryExecutor::new(Duration::from_secs(5));
+        let key = DocumentKey::new("id", None).unwrap();
+
+        for (path, data_source) in &sources {
+            let (sender, mut receiver) = mpsc::channel(16);
+            let sent = executor.stream_query(data_source, path, &key, None, sender).await.unwrap();
+            let mut ids = Vec::new();
+            while let Some(row) = receiver.recv().await {
+                ids.push(row["id"].as_u64().unwrap());
+            }
+            assert_eq!((sent, ids), (5, vec![0, 1, 2, 3, 4]), "{}", path);
+        }
+
+        // Страница, повторяющая предыдущую, завершает чтение, даже если её размер не известен
+        let stuck = [
+            source(&base_url, "$", HttpPagination::PageNumber {
+                param: "page".to_string(),
+                start: 1,
+                size_param: None,
+                size: None,
+            }),
+            source(&base_url, "$", HttpPagination::Offset {
+                offset_param: "offset".to_string(),
+                limit_param: "limit".to_string(),
+                limit: 2,
+            }),
+        ];
+        for data_source in &stuck {
+            let rows = executor.fetch_all(data_source, "/stuck", 100).await.unwrap();
+            assert_eq!(rows, records(0));
+        }
+
+        // Ссылка на другой хост не получает токен и заголовки источника
+        let data_source = source(&base_url, "items", HttpPagination::LinkHeader);
+        let (sender, _receiver) = mpsc::channel(16);
+        let error = executor.stream_query(&data_source, "/foreign", &key, None, sender).await.unwrap_err();
+        assert!(error.contains("leads away from"), "{}", error);
+
+        assert_eq!(HttpPager::next_link("<https://api/x?p=2>; rel=\"next\", <https://api/x?p=9>; rel=\"last\""), Some("https://api/x?p=2".to_string()));
+    }
+}
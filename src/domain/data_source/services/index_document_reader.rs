@@ -0,0 +1,124 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use meilisearch_sdk::documents::DocumentsQuery;
+use meilisearch_sdk::indexes::Index;
+use serde_json::Value;
+
+/// Постраничное чтение документов индекса Meilisearch
+pub struct IndexDocumentReader;
+
+impl IndexDocumentReader {
+    /// Собирает значения первичного ключа всех документов индекса (в виде строк)
+    pub async fn fetch_primary_keys(
+        index: &Index,
+        primary_key: &str,
+        page_size: u32,
+    ) -> Result<HashSet<String>, String> {
+        let mut keys = HashSet::new();
+        let mut offset = 0usize;
+
+        loop {
+            let mut documents_query = DocumentsQuery::new(index);
+            documents_query
+                .with_fields([primary_key])
+                .with_limit(page_size as usize)
+                .with_offset(offset);
+
+            let page = index
+                .get_documents_with::<Value>(&documents_query)
+                .await
+                .map_err(|e| format!("Failed to read documents of index '{}': {}", index.uid, e))?;
+
+            let fetched = page.results.len();
+            keys.extend(page.results.iter().filter_map(|document| {
+                document.get(primary_key).and_then(Self::key_to_string)
+            }));
+
+            offset += fetched;
+            if fetched == 0 || offset >= page.total as usize {
+                break;
+            }
+        }
+
+        Ok(keys)
+    }
+
+    /// Хеши содержимого всех документов индекса по значению первичного ключа:
+    /// для сравнения с источником не нужно держать в памяти сами документы
+    pub async fn fetch_document_hashes(
+        index: &Index,
+        primary_key: &str,
+        page_size: u32,
+    ) -> Result<HashMap<String, u64>, String> {
+        let mut hashes = HashMap::new();
+        let mut offset = 0usize;
+
+        loop {
+            let mut documents_query = DocumentsQuery::new(index);
+            documents_query
+                .with_limit(page_size as usize)
+                .with_offset(offset);
+
+            let page = index
+                .get_documents_with::<Value>(&documents_query)
+                .await
+                .map_err(|e| format!("Failed to read documents of index '{}': {}", index.uid, e))?;
+
+            let fetched = page.results.len();
+            for document in &page.results {
+                if let Some(key) = document.get(primary_key).and_then(Self::key_to_string) {
+                    hashes.insert(key, Self::document_hash(document));
+                }
+            }
+
+            offset += fetched;
+            if fetched == 0 || offset >= page.total as usize {
+                break;
+            }
+        }
+
+        Ok(hashes)
+    }
+
+    /// Один документ индекса по значению первичного ключа
+    pub async fn fetch_document(index: &Index, key: &str) -> Result<Value, String> {
+        index
+            .get_document::<Value>(key)
+            .await
+            .map_err(|e| format!("Failed to read document '{}' of index '{}': {}", key, index.uid, e))
+    }
+
+    /// Хеш содержимого документа; поля объектов serde_json хранит упорядоченными,
+    /// поэтому порядок полей в ответе на хеш не влияет
+    pub fn document_hash(document: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        document.to_string().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Приводит значение первичного ключа к строке, как его хранит Meilisearch
+    pub fn key_to_string(value: &Value) -> Option<String> {
+        match value {
+            Value::String(text) => Some(text.clone()),
+            Value::Number(number) => Some(number.to_string()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn document_hash_ignores_field_order() {
+        let document: Value = serde_json::from_str(r#"{"id": 1, "title": "A", "tags": ["x"]}"#).unwrap();
+        let reordered: Value = serde_json::from_str(r#"{"tags": ["x"], "title": "A", "id": 1}"#).unwrap();
+        assert_eq!(IndexDocumentReader::document_hash(&document), IndexDocumentReader::document_hash(&reordered));
+        assert_ne!(
+            IndexDocumentReader::document_hash(&document),
+            IndexDocumentReader::document_hash(&json!({"id": 1, "title": "B", "tags": ["x"]}))
+        );
+    }
+}
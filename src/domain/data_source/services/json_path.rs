@@ -0,0 +1,83 @@
+use serde_json::Value;
+
+/// Шаг пути: поле объекта или элемент массива
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Field(String),
+    Index(usize),
+}
+
+/// Подмножество JSONPath, которого хватает, чтобы указать на одно значение в ответе API:
+/// `$`, `.field`, `['field']` и `[0]`. Фильтры и `*` не поддерживаются.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    steps: Vec<Step>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let invalid = |reason: &str| format!("Invalid JSONPath '{}': {}", path, reason);
+        // `data.items` без `$.` тоже принимается
+        let trimmed = path.trim();
+        let normalized = match trimmed.strip_prefix('$') {
+            Some(rest) => rest.to_string(),
+            None if trimmed.is_empty() || trimmed.starts_with(['.', '[']) => trimmed.to_string(),
+            None => format!(".{}", trimmed),
+        };
+        let mut rest = normalized.as_str();
+        let mut steps = Vec::new();
+
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix('.') {
+                let end = tail.find(['.', '[']).unwrap_or(tail.len());
+                if end == 0 {
+                    return Err(invalid("empty field name"));
+                }
+                steps.push(Step::Field(tail[..end].to_string()));
+                rest = &tail[end..];
+            } else if let Some(tail) = rest.strip_prefix('[') {
+                let end = tail.find(']').ok_or_else(|| invalid("unclosed '['"))?;
+                let inner = tail[..end].trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|inner| inner.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|inner| inner.strip_suffix('"')));
+                match quoted {
+                    Some(field) => steps.push(Step::Field(field.to_string())),
+                    None => steps.push(Step::Index(inner.parse().map_err(|_| invalid("expected an index or a quoted name"))?)),
+                }
+                rest = &tail[end + 1..];
+            } else {
+                return Err(invalid("expected '.' or '['"));
+            }
+        }
+
+        Ok(Self { steps })
+    }
+
+    /// Значение по пути или None, если его нет
+    pub fn select<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.steps.iter().try_fold(value, |value, step| match step {
+            Step::Field(field) => value.get(field),
+            Step::Index(index) => value.get(index),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn selects_values_by_path() {
+        let response = json!({"data": {"items": [{"id": 1}], "next-cursor": "abc"}});
+
+        assert_eq!(JsonPath::parse("$.data.items[0].id").unwrap().select(&response), Some(&json!(1)));
+        assert_eq!(JsonPath::parse("$.data['next-cursor']").unwrap().select(&response), Some(&json!("abc")));
+        assert_eq!(JsonPath::parse("data.items").unwrap().select(&response), Some(&json!([{"id": 1}])));
+        assert_eq!(JsonPath::parse("$").unwrap().select(&response), Some(&response));
+        assert_eq!(JsonPath::parse("$.missing").unwrap().select(&response), None);
+        assert!(JsonPath::parse("$.data[").is_err());
+    }
+}
@@ -1 +1,33 @@
-pub mod query_executor;
\ No newline at end of file
+pub mod query_executor;
+pub mod index_document_reader;
+pub mod deletion_reconciler;
+pub mod sync_schedule;
+pub mod document_transformer;
+pub mod document_key;
+pub mod notify_trigger;
+pub mod pg_value_decoder;
+pub mod named_params;
+pub mod sql_lexer;
+pub mod read_only_query;
+pub mod connection_registry;
+pub mod child_query_assembler;
+pub mod ndjson_batch;
+pub mod task_throttle;
+pub mod mysql_value_decoder;
+pub mod mysql_query_executor;
+pub mod sqlite_value_decoder;
+pub mod sqlite_query_executor;
+pub mod file_source_reader;
+pub mod file_query_executor;
+pub mod row_filter;
+pub mod json_path;
+pub mod http_query_executor;
+pub mod data_source_connector;
+pub mod connector_registry;
+pub mod postgres_query_executor;
+pub mod postgres_connector;
+pub mod mysql_connector;
+pub mod sqlite_connector;
+pub mod file_connector;
+pub mod http_connector;
+pub mod credential_cipher;
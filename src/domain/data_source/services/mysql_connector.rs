@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::MySqlPool;
+use sqlx::mysql::MySqlConnectOptions;
+use tokio::sync::mpsc;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::data_source_connector::{ConnectionField, ConnectorDescriptor, DataSourceConnector, QueryContext, SourceObject};
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::mysql_query_executor::MySqlQueryExecutor;
+use crate::domain::data_source::services::query_executor::{CursorWindow, QueryPreview};
+
+/// Источники MySQL и MariaDB (`database_type = "mysql"`)
+pub struct MySqlConnector;
+
+impl MySqlConnector {
+    /// Параметры подключения к MySQL и MariaDB. Учётные данные передаются отдельными полями, а не в URL,
+    /// поэтому `@`, `/`, `:` и `%` в пароле не нужно экранировать. Пароль расшифровывается здесь.
+    pub(crate) fn connect_options(data_source: &DataSource) -> Result<MySqlConnectOptions, String> {
+        let mut options = MySqlConnectOptions::new()
+            .host(&data_source.host)
+            .port(data_source.port);
+        if !data_source.username.is_empty() {
+            options = options.username(&data_source.username);
+        }
+        let password = data_source.password.reveal()?;
+        if !password.is_empty() {
+            options = options.password(&password);
+        }
+        if !data_source.database_name.is_empty() {
+            options = options.database(&data_source.database_name);
+        }
+        Ok(options)
+    }
+
+    fn executor(context: &QueryContext) -> MySqlQueryExecutor {
+        MySqlQueryExecutor::new(context.epoch_timestamps, context.params.clone(), context.statement_timeout, context.connections.clone())
+    }
+}
+
+#[async_trait]
+impl DataSourceConnector for MySqlConnector {
+    fn descriptor(&self) -> ConnectorDescriptor {
+        ConnectorDescriptor {
+            database_type: "mysql",
+            label: "MySQL / MariaDB",
+            fields: ConnectionField::server_fields(3306),
+        }
+    }
+
+    /// Отдельное подключение, а не пул из реестра: проверяются именно сохранённые настройки
+    async fn test_connection(&self, _context: &QueryContext, data_source: &DataSource) -> Result<(), String> {
+        let pool = MySqlPool::connect_with(Self::connect_options(data_source)?)
+            .await
+            .map_err(|e| format!("Database connection error: {}", e))?;
+        sqlx::query("SELECT 1")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| format!("Test query error: {}", e))?;
+        pool.close().await;
+        Ok(())
+    }
+
+    async fn introspect(&self, context: &QueryContext, data_source: &DataSource) -> Result<Vec<SourceObject>, String> {
+        Self::executor(context).introspect(data_source).await
+    }
+
+    async fn preview(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String> {
+        Self::executor(context).preview(data_source, query, limit).await
+    }
+
+    async fn stream_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        Self::executor(context).stream_query(data_source, query, key, cursor, chunk_size, sender).await
+    }
+
+    async fn fetch_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Value>, String> {
+        Self::executor(context).fetch_all(data_source, query, limit).await
+    }
+
+    async fn fetch_cursor_bounds(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        Self::executor(context).fetch_cursor_bounds(data_source, query, cursor_column).await
+    }
+
+    async fn fetch_keys(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String> {
+        Self::executor(context).fetch_keys(data_source, query, key).await
+    }
+}
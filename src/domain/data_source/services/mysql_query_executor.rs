@@ -0,0 +1,477 @@
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use serde_json::Value;
+use sqlx::mysql::{MySqlArguments, MySqlPool, MySqlRow};
+use sqlx::query::Query;
+use sqlx::types::Json;
+use sqlx::{Column, Executor, MySql, Row, Transaction, TypeInfo};
+use tokio::sync::mpsc;
+use tracing::warn;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::connection_registry::ConnectionRegistry;
+use crate::domain::data_source::services::data_source_connector::SourceObject;
+use crate::domain::data_source::services::document_key::{DocumentKey, TemplatePart, RENDERED_KEY_COLUMN};
+use crate::domain::data_source::services::mysql_value_decoder::MySqlValueDecoder;
+use crate::domain::data_source::services::named_params::{NamedParams, QueryParams};
+use crate::domain::data_source::services::query_executor::{CursorWindow, PreviewColumn, QueryPreview};
+use crate::domain::data_source::services::mysql_connector::MySqlConnector;
+use crate::domain::data_source::services::read_only_query::ReadOnlyQuery;
+
+/// Выполнение запросов к источникам MySQL/MariaDB; QueryExecutor передаёт сюда всё, что касается таких источников.
+/// Параметры подставляются как `?`, идентификаторы экранируются обратными кавычками,
+/// а серверных курсоров вне процедур нет — потоковая выборка идёт страницами по ключу.
+pub(crate) struct MySqlQueryExecutor {
+    epoch_timestamps: bool,
+    params: QueryParams,
+    statement_timeout: Duration,
+    connections: Option<Arc<ConnectionRegistry>>,
+}
+
+impl MySqlQueryExecutor {
+    pub(crate) fn new(
+        epoch_timestamps: bool,
+        params: QueryParams,
+        statement_timeout: Duration,
+        connections: Option<Arc<ConnectionRegistry>>,
+    ) -> Self {
+        Self {
+            epoch_timestamps,
+            params,
+            statement_timeout,
+            connections,
+        }
+    }
+
+    /// Строки запроса, при наличии `limit` — не больше указанного числа
+    pub(crate) async fn fetch_all(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Value>, String> {
+        let (query, values) = self.compile(query)?;
+        let final_query = match limit {
+            Some(limit) => Self::prepare_query(&query, limit),
+            None => query,
+        };
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let rows = Self::bind_params(sqlx::query(&final_query), values)?
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        Ok(Self::rows_to_json(rows, self.epoch_timestamps))
+    }
+
+    /// Предпросмотр как у Postgres; оценка числа строк берётся из EXPLAIN,
+    /// только если план состоит из одной строки (запрос к одной таблице)
+    pub(crate) async fn preview(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String> {
+        let (query, values) = self.compile(query)?;
+        let final_query = Self::prepare_query(&query, limit);
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let started = Instant::now();
+        let rows = Self::bind_params(sqlx::query(&final_query), values.clone())?
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+        let execution_time_ms = started.elapsed().as_millis() as u64;
+
+        let columns = (&mut *transaction)
+            .describe(&query)
+            .await
+            .map_err(|e| format!("Query describe error: {}", e))?
+            .columns()
+            .iter()
+            .map(|column| PreviewColumn {
+                name: column.name().to_string(),
+                pg_type: column.type_info().name().to_string(),
+                json_type: MySqlValueDecoder::json_type(column.type_info()).to_string(),
+            })
+            .collect();
+
+        let explain_query = format!("EXPLAIN {}", query);
+        let mut estimate_warning = None;
+        let estimated_total = match Self::bind_params(sqlx::query(&explain_query), values)?
+            .fetch_all(&mut *transaction)
+            .await
+        {
+            Ok(plan) if plan.len() == 1 => Self::rows_to_json(plan, false)[0]["rows"].as_i64(),
+            Ok(_) => None,
+            Err(e) => {
+                let message = format!("Could not estimate row count: {}", e);
+                warn!("{}", message);
+                estimate_warning = Some(message);
+                None
+            }
+        };
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        let (rows, mut warnings) = Self::decode_rows(&rows, self.epoch_timestamps);
+        warnings.extend(estimate_warning);
+        Ok(QueryPreview {
+            columns,
+            rows,
+            warnings,
+            estimated_total,
+            execution_time_ms,
+        })
+    }
+
+    /// Потоковая выборка страницами по `chunk_size` строк внутри одной транзакции:
+    /// InnoDB читает все страницы из одного снимка, а таймаут действует на каждую страницу отдельно.
+    /// Страница дочитывается целиком до отправки в канал, чтобы ожидание потребителя
+    /// не держало запрос открытым и не съедало max_execution_time.
+    pub(crate) async fn stream_query(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        let chunk_size = chunk_size.max(1);
+        let (query, values) = self.compile(query)?;
+        let key_column = match key.template() {
+            Some(_) => RENDERED_KEY_COLUMN,
+            None => key.field(),
+        };
+
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let decoder = MySqlValueDecoder::new(self.epoch_timestamps);
+        let mut warnings = BTreeSet::new();
+        let mut total = 0u64;
+        let mut after_key: Option<Value> = None;
+        loop {
+            let mut page_values = values.clone();
+            let page_query = Self::prepare_batch_query(&query, key, after_key.as_ref(), cursor, chunk_size, &mut page_values);
+
+            let rows = Self::bind_params(sqlx::query(&page_query), page_values)?
+                .fetch_all(&mut *transaction)
+                .await
+                .map_err(|e| format!("Query execution error: {}", e))?;
+            let received = rows.len() as u32;
+
+            let mut last_key = None;
+            for row in rows {
+                let document = decoder.decode_row(&row, &mut warnings);
+                last_key = document.get(key_column).cloned();
+                if sender.send(document).await.is_err() {
+                    return Err("Row consumer stopped".to_string());
+                }
+            }
+
+            total += received as u64;
+            if received < chunk_size {
+                break;
+            }
+            match last_key {
+                Some(value) if !value.is_null() => after_key = Some(value),
+                _ => return Err(format!("Key column '{}' is missing or null", key_column)),
+            }
+        }
+
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        Ok(total)
+    }
+
+    /// Максимум курсорной колонки как текст и её тип. Границы окна потом сравниваются
+    /// с колонкой без приведения: MySQL сам приводит строку к типу колонки.
+    pub(crate) async fn fetch_cursor_bounds(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        let (query, values) = self.compile(query)?;
+        let column = format!("batch.{}", Self::quote_identifier(cursor_column));
+        let bounds_query = format!(
+            "SELECT CAST(MAX({column}) AS CHAR) AS max_value, MAX({column}) AS max_typed FROM ({query}) AS batch",
+            column = column,
+            query = query
+        );
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let row = Self::bind_params(sqlx::query(&bounds_query), values)?
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        let max_value: Option<String> = row.try_get_unchecked("max_value").map_err(|e| e.to_string())?;
+        let column_type = row.column(1).type_info().name().to_string();
+
+        Ok(max_value.map(|value| (value, column_type)))
+    }
+
+    /// Все значения первичного ключа результата запроса в виде строк
+    pub(crate) async fn fetch_keys(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String> {
+        let (query, values) = self.compile(query)?;
+        let key_expression = match Self::template_expression(key) {
+            Some(expression) => expression,
+            None => format!("CAST(batch.{} AS CHAR)", Self::quote_identifier(key.field())),
+        };
+        let keys_query = format!("SELECT {} AS `key` FROM ({}) AS batch", key_expression, query);
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let rows = Self::bind_params(sqlx::query(&keys_query), values)?
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        let is_composite = key.template().is_some();
+        rows
+            .iter()
+            .filter_map(|row| row.try_get_unchecked::<Option<String>, _>("key").ok().flatten())
+            .map(|raw| if is_composite { DocumentKey::sanitize(&raw) } else { Ok(raw) })
+            .collect::<Result<HashSet<String>, String>>()
+    }
+
+    /// Таблицы и представления текущей базы с колонками в порядке объявления
+    pub(crate) async fn introspect(&self, data_source: &DataSource) -> Result<Vec<SourceObject>, String> {
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        // В MySQL 8 колонки information_schema бинарные, поэтому приводим их к тексту
+        let rows = sqlx::query(
+            "SELECT CAST(table_name AS CHAR) AS object_name, CAST(column_name AS CHAR) AS column_name, \
+             CAST(column_type AS CHAR) AS data_type \
+             FROM information_schema.columns WHERE table_schema = DATABASE() \
+             ORDER BY table_name, ordinal_position",
+        )
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(|e| format!("Query execution error: {}", e))?;
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        rows.iter()
+            .map(|row| {
+                Ok((
+                    row.try_get_unchecked("object_name")?,
+                    row.try_get_unchecked("column_name")?,
+                    row.try_get_unchecked("data_type")?,
+                ))
+            })
+            .collect::<Result<Vec<(String, String, String)>, sqlx::Error>>()
+            .map(SourceObject::group)
+            .map_err(|e| e.to_string())
+    }
+
+    async fn connect(&self, data_source: &DataSource) -> Result<MySqlPool, String> {
+        match &self.connections {
+            Some(connections) => connections.mysql_pool(data_source),
+            None => MySqlPool::connect_with(MySqlConnector::connect_options(data_source)?)
+                .await
+                .map_err(|e| format!("Database connection error: {}", e)),
+        }
+    }
+
+    async fn release(&self, pool: MySqlPool) {
+        if self.connections.is_none() {
+            pool.close().await;
+        }
+    }
+
+    /// Транзакция READ ONLY с пределом времени каждого SELECT: `max_execution_time` в MySQL,
+    /// `max_statement_time` (в секундах) в MariaDB
+    async fn begin_read_only(
+        pool: &MySqlPool,
+        statement_timeout: Duration,
+    ) -> Result<Transaction<'static, MySql>, String> {
+        let mut transaction = pool
+            .begin_with("START TRANSACTION READ ONLY")
+            .await
+            .map_err(|e| format!("Transaction start error: {}", e))?;
+
+        let mysql_timeout = format!("SET SESSION max_execution_time = {}", statement_timeout.as_millis());
+        if sqlx::query(&mysql_timeout).execute(&mut *transaction).await.is_err() {
+            sqlx::query(&format!("SET SESSION max_statement_time = {}", statement_timeout.as_secs_f64()))
+                .execute(&mut *transaction)
+                .await
+                .map_err(|e| format!("Transaction start error: {}", e))?;
+        }
+
+        Ok(transaction)
+    }
+
+    fn compile(&self, query: &str) -> Result<(String, Vec<Value>), String> {
+        NamedParams::positional(&ReadOnlyQuery::prepare(query)?, &self.params)
+    }
+
+    fn prepare_query(query: &str, limit: u32) -> String {
+        format!("SELECT * FROM ({}) AS limited LIMIT {}", query, limit)
+    }
+
+    /// Страница keyset-пагинации; значения ключа и границ окна дописываются в `values`
+    /// в том же порядке, в каком их `?` стоят в запросе
+    fn prepare_batch_query(
+        query: &str,
+        key: &DocumentKey,
+        after_key: Option<&Value>,
+        cursor: Option<&CursorWindow>,
+        limit: u32,
+        values: &mut Vec<Value>,
+    ) -> String {
+        let mut conditions = Vec::new();
+        let template = Self::template_expression(key);
+        let columns = match &template {
+            Some(expression) => format!("batch.*, {} AS {}", expression, Self::quote_identifier(RENDERED_KEY_COLUMN)),
+            None => String::from("*"),
+        };
+        let key_expression = template.unwrap_or_else(|| format!("batch.{}", Self::quote_identifier(key.field())));
+
+        if let Some(after_key) = after_key {
+            conditions.push(format!("{} > ?", key_expression));
+            values.push(after_key.clone());
+        }
+        if let Some(cursor) = cursor {
+            let column = format!("batch.{}", Self::quote_identifier(&cursor.column));
+            if let Some(after) = &cursor.after {
+                conditions.push(format!("{} > ?", column));
+                values.push(Value::String(after.clone()));
+            }
+            conditions.push(format!("{} <= ?", column));
+            values.push(Value::String(cursor.up_to.clone()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+
+        format!(
+            "SELECT {} FROM ({}) AS batch{} ORDER BY {} LIMIT {}",
+            columns, query, where_clause, key_expression, limit
+        )
+    }
+
+    /// Составной ключ через CONCAT; NULL-колонки дают пустую строку
+    fn template_expression(key: &DocumentKey) -> Option<String> {
+        let parts: Vec<String> = key.template()?
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => format!("'{}'", text.replace('\\', "\\\\").replace('\'', "''")),
+                TemplatePart::Field(field) => format!("COALESCE(CAST(batch.{} AS CHAR), '')", Self::quote_identifier(field)),
+            })
+            .collect();
+
+        Some(format!("CONCAT({})", parts.join(", ")))
+    }
+
+    fn quote_identifier(identifier: &str) -> String {
+        format!("`{}`", identifier.replace('`', "``"))
+    }
+
+    /// Массивы к этому моменту уже раскрыты в отдельные `?`, объекты передаются как JSON
+    fn bind_params(
+        mut query: Query<'_, MySql, MySqlArguments>,
+        values: Vec<Value>,
+    ) -> Result<Query<'_, MySql, MySqlArguments>, String> {
+        for value in values {
+            query = match value {
+                Value::Null => query.bind(None::<String>),
+                Value::Bool(value) => query.bind(value),
+                Value::Number(number) => match (number.as_i64(), number.as_u64()) {
+                    (Some(integer), _) => query.bind(integer),
+                    (None, Some(unsigned)) => query.bind(unsigned),
+                    (None, None) => query.bind(number.as_f64()),
+                },
+                Value::String(text) => query.bind(text),
+                Value::Array(items) => return Err(format!("Nested arrays are not supported as query parameters: {}", Value::Array(items))),
+                object @ Value::Object(_) => query.bind(Json(object)),
+            };
+        }
+        Ok(query)
+    }
+
+    fn rows_to_json(rows: Vec<MySqlRow>, epoch_timestamps: bool) -> Vec<Value> {
+        let (documents, warnings) = Self::decode_rows(&rows, epoch_timestamps);
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+        documents
+    }
+
+    /// Строки результата в JSON и предупреждения о неразобранных колонках, по одному на колонку
+    fn decode_rows(rows: &[MySqlRow], epoch_timestamps: bool) -> (Vec<Value>, Vec<String>) {
+        let decoder = MySqlValueDecoder::new(epoch_timestamps);
+        let mut warnings = BTreeSet::new();
+        let documents = rows.iter().map(|row| decoder.decode_row(row, &mut warnings)).collect();
+        (documents, warnings.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pages_by_composite_key_and_cursor_window() {
+        let key = DocumentKey::new("id", Some("{tenant_id}-{id}")).unwrap();
+        let cursor = CursorWindow {
+            column: "updated_at".to_string(),
+            column_type: "DATETIME".to_string(),
+            after: Some("2024-01-01 00:00:00".to_string()),
+            up_to: "2024-02-01 00:00:00".to_string(),
+        };
+        let mut values = vec![json!(7)];
+
+        let query = MySqlQueryExecutor::prepare_batch_query(
+            "SELECT * FROM products WHERE shop_id = ?",
+            &key,
+            Some(&json!("1-10")),
+            Some(&cursor),
+            500,
+            &mut values,
+        );
+
+        assert_eq!(
+            query,
+            "SELECT batch.*, CONCAT(COALESCE(CAST(batch.`tenant_id` AS CHAR), ''), '-', COALESCE(CAST(batch.`id` AS CHAR), '')) AS `__milli_key` \
+             FROM (SELECT * FROM products WHERE shop_id = ?) AS batch \
+             WHERE CONCAT(COALESCE(CAST(batch.`tenant_id` AS CHAR), ''), '-', COALESCE(CAST(batch.`id` AS CHAR), '')) > ? \
+             AND batch.`updated_at` > ? AND batch.`updated_at` <= ? \
+             ORDER BY CONCAT(COALESCE(CAST(batch.`tenant_id` AS CHAR), ''), '-', COALESCE(CAST(batch.`id` AS CHAR), '')) LIMIT 500"
+        );
+        assert_eq!(values, vec![json!(7), json!("1-10"), json!("2024-01-01 00:00:00"), json!("2024-02-01 00:00:00")]);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeSet;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_json::Value;
+use sqlx::mysql::types::MySqlTime;
+use sqlx::mysql::{MySqlRow, MySqlTypeInfo};
+use sqlx::types::BigDecimal;
+use sqlx::{Column, Decode, MySql, Row, Type, TypeInfo};
+use crate::domain::data_source::services::pg_value_decoder::{PgValueDecoder, EPOCH_FIELD_SUFFIX};
+
+/// Преобразует значения колонок MySQL/MariaDB в JSON по тем же правилам, что и PgValueDecoder:
+/// DECIMAL без потерь, BIGINT UNSIGNED числом, SET — массивом строк, двоичные данные — base64,
+/// DATETIME/TIMESTAMP — строками RFC 3339. Неподдерживаемые типы дают null и предупреждение.
+pub struct MySqlValueDecoder {
+    epoch_timestamps: bool,
+}
+
+impl MySqlValueDecoder {
+    /// `epoch_timestamps` — добавлять к DATETIME/TIMESTAMP/DATE поле `<колонка>_epoch` для сортировки
+    pub fn new(epoch_timestamps: bool) -> Self {
+        Self { epoch_timestamps }
+    }
+
+    /// Строка результата как JSON-объект; предупреждения о неразобранных колонках добавляются в `warnings`
+    pub fn decode_row(&self, row: &MySqlRow, warnings: &mut BTreeSet<String>) -> Value {
+        let mut document = serde_json::Map::new();
+
+        for column in row.columns() {
+            let name = column.name();
+            let type_name = column.type_info().name();
+            let value = match Self::decode_column(row, column.ordinal(), type_name) {
+                Ok(value) => value,
+                Err(reason) => {
+                    warnings.insert(format!("Column '{}' ({}): {}; value set to null", name, type_name, reason));
+                    Value::Null
+                }
+            };
+
+            if self.epoch_timestamps
+                && let Some(epoch) = Self::epoch_seconds(type_name, &value)
+            {
+                document.insert(format!("{}{}", name, EPOCH_FIELD_SUFFIX), Value::from(epoch));
+            }
+            document.insert(name.to_string(), value);
+        }
+
+        Value::Object(document)
+    }
+
+    fn decode_column(row: &MySqlRow, index: usize, type_name: &str) -> Result<Value, String> {
+        match type_name {
+            "BOOLEAN" => Self::typed(row, index, |value: bool| Ok(Value::Bool(value))),
+            "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => Self::typed(row, index, |value: i64| Ok(Value::from(value))),
+            "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "BIGINT UNSIGNED" => {
+                Self::typed(row, index, |value: u64| Ok(Value::from(value)))
+            }
+            // YEAR и BIT не всегда помечены как UNSIGNED, поэтому без проверки совместимости
+            "YEAR" | "BIT" => Self::unchecked(row, index, |value: u64| Ok(Value::from(value))),
+            // Через строку, чтобы FLOAT 0.1 не превращался в 0.10000000149011612
+            "FLOAT" => Self::typed(row, index, |value: f32| PgValueDecoder::float_to_json(value.to_string().parse().unwrap_or(f64::NAN))),
+            "DOUBLE" => Self::typed(row, index, PgValueDecoder::float_to_json),
+            "DECIMAL" => Self::typed(row, index, |value: BigDecimal| Ok(PgValueDecoder::numeric_to_json(&value))),
+            "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" => {
+                Self::unchecked(row, index, |value: String| Ok(Value::String(value)))
+            }
+            "SET" => Self::unchecked(row, index, |value: String| {
+                Ok(Value::Array(
+                    value.split(',').filter(|item| !item.is_empty()).map(|item| Value::String(item.to_string())).collect(),
+                ))
+            }),
+            "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" => {
+                Self::typed(row, index, |value: Vec<u8>| Ok(Value::String(BASE64.encode(value))))
+            }
+            "JSON" => Self::typed(row, index, |value: Value| Ok(value)),
+            "TIMESTAMP" => Self::typed(row, index, |value: DateTime<Utc>| Ok(PgValueDecoder::timestamp_to_json(value))),
+            // Время без пояса считается UTC
+            "DATETIME" => Self::typed(row, index, |value: NaiveDateTime| Ok(PgValueDecoder::timestamp_to_json(value.and_utc()))),
+            "DATE" => Self::typed(row, index, |value: NaiveDate| Ok(Value::String(value.format("%Y-%m-%d").to_string()))),
+            // TIME в MySQL — интервал: бывает отрицательным и больше суток
+            "TIME" => Self::typed(row, index, |value: MySqlTime| Ok(Value::String(value.to_string()))),
+            "NULL" => Ok(Value::Null),
+            _ => Err("unsupported type".to_string()),
+        }
+    }
+
+    /// Тип JSON, в который превращается колонка этого типа MySQL
+    pub fn json_type(type_info: &MySqlTypeInfo) -> &'static str {
+        Self::json_type_for(type_info.name())
+    }
+
+    fn json_type_for(type_name: &str) -> &'static str {
+        match type_name {
+            "BOOLEAN" => "boolean",
+            "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" | "YEAR" | "BIT" => "integer",
+            "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "BIGINT UNSIGNED" => "integer",
+            "FLOAT" | "DOUBLE" => "number",
+            // Значения, которые f64 не хранит точно, отдаются строкой
+            "DECIMAL" => "number|string",
+            "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" => "string",
+            "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" => "string",
+            "TIMESTAMP" | "DATETIME" | "DATE" | "TIME" => "string",
+            "SET" => "array",
+            "JSON" => "any",
+            _ => "unsupported",
+        }
+    }
+
+    /// Читает колонку как `T` и преобразует значение
+    fn typed<T>(row: &MySqlRow, index: usize, convert: impl Fn(T) -> Result<Value, String>) -> Result<Value, String>
+    where
+        T: for<'r> Decode<'r, MySql> + Type<MySql>,
+    {
+        match row.try_get::<Option<T>, _>(index).map_err(|e| e.to_string())? {
+            Some(value) => convert(value),
+            None => Ok(Value::Null),
+        }
+    }
+
+    /// То же без проверки типа колонки: sqlx не считает ENUM/SET строками, а YEAR/BIT — беззнаковыми
+    fn unchecked<T>(row: &MySqlRow, index: usize, convert: impl Fn(T) -> Result<Value, String>) -> Result<Value, String>
+    where
+        T: for<'r> Decode<'r, MySql> + Type<MySql>,
+    {
+        match row.try_get_unchecked::<Option<T>, _>(index).map_err(|e| e.to_string())? {
+            Some(value) => convert(value),
+            None => Ok(Value::Null),
+        }
+    }
+
+    fn epoch_seconds(type_name: &str, value: &Value) -> Option<i64> {
+        match type_name {
+            "DATETIME" | "TIMESTAMP" => PgValueDecoder::epoch_seconds("TIMESTAMP", value),
+            "DATE" => PgValueDecoder::epoch_seconds("DATE", value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_mysql_types_to_json_types() {
+        assert_eq!(MySqlValueDecoder::json_type_for("BIGINT UNSIGNED"), "integer");
+        assert_eq!(MySqlValueDecoder::json_type_for("DECIMAL"), "number|string");
+        assert_eq!(MySqlValueDecoder::json_type_for("SET"), "array");
+        assert_eq!(MySqlValueDecoder::json_type_for("GEOMETRY"), "unsupported");
+
+        assert_eq!(MySqlValueDecoder::epoch_seconds("DATETIME", &json!("2024-01-01T00:00:10Z")), Some(1_704_067_210));
+        assert_eq!(MySqlValueDecoder::epoch_seconds("VARCHAR", &json!("2024-01-01")), None);
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use serde_json::Value;
+use crate::domain::data_source::services::sql_lexer::{SqlLexer, SqlSegment};
+
+/// Значения именованных параметров запроса (`:tenant_id` → значение)
+pub type QueryParams = BTreeMap<String, Value>;
+
+/// Запрос, в котором именованные параметры заменены на позиционные `$1..$n`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledQuery {
+    pub sql: String,
+    /// Имя параметра для каждого `$i` (индекс i - 1)
+    pub names: Vec<String>,
+}
+
+/// Именованные параметры в сохранённых запросах: `:name` превращается в `$n`
+/// (или в `?` для MySQL), а значения передаются через bind — в текст запроса они не подставляются.
+/// Строки, идентификаторы в кавычках, комментарии, dollar-quoting и приведение `::type` не затрагиваются.
+pub struct NamedParams;
+
+impl NamedParams {
+    pub fn compile(query: &str) -> Result<CompiledQuery, String> {
+        let mut names: Vec<String> = Vec::new();
+        let sql = Self::rewrite(query, |name| {
+            let position = match names.iter().position(|existing| existing == name) {
+                Some(position) => position,
+                None => {
+                    names.push(name.to_string());
+                    names.len() - 1
+                }
+            };
+            Ok(format!("${}", position + 1))
+        })?;
+
+        Ok(CompiledQuery { sql, names })
+    }
+
+    /// Вариант для MySQL: каждое вхождение `:name` становится `?`, значения идут в порядке вхождений.
+    /// Массив раскрывается в список `?, ?, ?` для `IN (:ids)`; пустой массив даёт `NULL`.
+    pub fn positional(query: &str, params: &QueryParams) -> Result<(String, Vec<Value>), String> {
+        let mut values = Vec::new();
+        let sql = Self::rewrite(query, |name| {
+            let value = params
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("Missing value for query parameter :{}", name))?;
+            Ok(match value {
+                Value::Array(items) if items.is_empty() => "NULL".to_string(),
+                Value::Array(items) => {
+                    let placeholders = vec!["?"; items.len()].join(", ");
+                    values.extend(items);
+                    placeholders
+                }
+                value => {
+                    values.push(value);
+                    "?".to_string()
+                }
+            })
+        })?;
+
+        Ok((sql, values))
+    }
+
+    /// Проходит по коду запроса и заменяет каждый `:name` результатом `placeholder`
+    fn rewrite(query: &str, mut placeholder: impl FnMut(&str) -> Result<String, String>) -> Result<String, String> {
+        let mut sql = String::with_capacity(query.len());
+
+        for segment in SqlLexer::split(query)? {
+            let SqlSegment::Code(code) = segment else {
+                sql.push_str(segment.text());
+                continue;
+            };
+
+            let chars: Vec<char> = code.chars().collect();
+            let mut i = 0;
+            while i < chars.len() {
+                let c = chars[i];
+                let next = chars.get(i + 1).copied();
+
+                match c {
+                    '$' if next.is_some_and(|c| c.is_ascii_digit()) => {
+                        return Err("Positional parameters ($1) are not supported, use named parameters (:name)".to_string());
+                    }
+                    ':' if next == Some(':') => {
+                        sql.push_str("::");
+                        i += 2;
+                    }
+                    ':' if next.is_some_and(|c| c.is_ascii_alphabetic() || c == '_') => {
+                        let start = i + 1;
+                        let end = chars[start..]
+                            .iter()
+                            .position(|c| !SqlLexer::is_identifier_char(*c))
+                            .map_or(chars.len(), |offset| start + offset);
+                        let name: String = chars[start..end].iter().collect();
+                        sql.push_str(&placeholder(&name)?);
+                        i = end;
+                    }
+                    _ => {
+                        sql.push(c);
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(sql)
+    }
+
+    /// Значения по умолчанию, поверх которых накладываются значения конкретного запуска
+    pub fn merge(defaults: &QueryParams, overrides: Option<&QueryParams>) -> QueryParams {
+        let mut params = defaults.clone();
+        if let Some(overrides) = overrides {
+            params.extend(overrides.iter().map(|(name, value)| (name.clone(), value.clone())));
+        }
+        params
+    }
+
+    /// Значения в порядке `$1..$n`; параметр без значения — ошибка
+    pub fn values(compiled: &CompiledQuery, params: &QueryParams) -> Result<Vec<Value>, String> {
+        compiled
+            .names
+            .iter()
+            .map(|name| {
+                params
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| format!("Missing value for query parameter :{}", name))
+            })
+            .collect()
+    }
+
+    /// Имена параметров должны быть идентификаторами, иначе на них нельзя сослаться из запроса
+    pub fn validate_names(params: &QueryParams) -> Result<(), String> {
+        for name in params.keys() {
+            let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(SqlLexer::is_identifier_char);
+            if !valid {
+                return Err(format!("Invalid query parameter name '{}'", name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn compiles_named_parameters() {
+        let compiled = NamedParams::compile(
+            "SELECT id, created_at::date FROM orders WHERE tenant_id = :tenant_id AND lang = :lang OR owner = :tenant_id",
+        ).unwrap();
+
+        assert_eq!(
+            compiled.sql,
+            "SELECT id, created_at::date FROM orders WHERE tenant_id = $1 AND lang = $2 OR owner = $1"
+        );
+        assert_eq!(compiled.names, vec!["tenant_id", "lang"]);
+    }
+
+    #[test]
+    fn skips_literals_and_comments() {
+        let query = "SELECT ':a', \"col:b\", E'\\':c', $$ :d $$ -- :e\n/* :f /* :g */ */ FROM t WHERE x = :h::int";
+        let compiled = NamedParams::compile(query).unwrap();
+
+        assert_eq!(compiled.names, vec!["h"]);
+        assert!(compiled.sql.ends_with("WHERE x = $1::int"));
+        assert!(NamedParams::compile("SELECT * FROM t WHERE id = $1").is_err());
+    }
+
+    #[test]
+    fn resolves_values_with_overrides() {
+        let compiled = NamedParams::compile("SELECT :a, :b").unwrap();
+        let defaults = QueryParams::from([("a".to_string(), json!(1)), ("b".to_string(), json!("x"))]);
+        let overrides = QueryParams::from([("b".to_string(), json!("y"))]);
+
+        let params = NamedParams::merge(&defaults, Some(&overrides));
+        assert_eq!(NamedParams::values(&compiled, &params).unwrap(), vec![json!(1), json!("y")]);
+        assert!(NamedParams::values(&compiled, &overrides).is_err());
+    }
+
+    #[test]
+    fn expands_positional_parameters() {
+        let params = QueryParams::from([("ids".to_string(), json!([1, 2, 3])), ("lang".to_string(), json!("en"))]);
+        let (sql, values) = NamedParams::positional(
+            "SELECT * FROM variants WHERE product_id IN (:ids) AND lang = :lang OR alt = :lang",
+            &params,
+        ).unwrap();
+
+        assert_eq!(sql, "SELECT * FROM variants WHERE product_id IN (?, ?, ?) AND lang = ? OR alt = ?");
+        assert_eq!(values, vec![json!(1), json!(2), json!(3), json!("en"), json!("en")]);
+
+        let empty = QueryParams::from([("ids".to_string(), json!([]))]);
+        assert_eq!(NamedParams::positional("SELECT 1 WHERE id IN (:ids)", &empty).unwrap().0, "SELECT 1 WHERE id IN (NULL)");
+    }
+}
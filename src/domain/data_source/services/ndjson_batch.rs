@@ -0,0 +1,69 @@
+use serde_json::Value;
+
+/// Пачка документов в формате NDJSON (по документу на строку), которая копится до порога
+/// по числу строк или по размеру и затем целиком уходит в Meilisearch одной задачей
+pub struct NdjsonBatch {
+    buffer: Vec<u8>,
+    rows: usize,
+    max_rows: usize,
+    max_bytes: usize,
+}
+
+impl NdjsonBatch {
+    pub fn new(max_rows: usize, max_bytes: usize) -> Self {
+        Self {
+            buffer: Vec::new(),
+            rows: 0,
+            max_rows: max_rows.max(1),
+            max_bytes: max_bytes.max(1),
+        }
+    }
+
+    pub fn push(&mut self, document: &Value) -> Result<(), String> {
+        serde_json::to_writer(&mut self.buffer, document).map_err(|e| e.to_string())?;
+        self.buffer.push(b'\n');
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Порог достигнут — пачку пора отправлять
+    pub fn is_full(&self) -> bool {
+        self.rows >= self.max_rows || self.buffer.len() >= self.max_bytes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Забирает накопленные данные; пачка снова пуста
+    pub fn take(&mut self) -> Vec<u8> {
+        self.rows = 0;
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fills_up_by_rows_or_bytes() {
+        let mut by_rows = NdjsonBatch::new(2, 1024);
+        by_rows.push(&json!({"id": 1})).unwrap();
+        assert!(!by_rows.is_full());
+        by_rows.push(&json!({"id": 2})).unwrap();
+        assert!(by_rows.is_full());
+        assert_eq!(by_rows.take(), b"{\"id\":1}\n{\"id\":2}\n".to_vec());
+        assert!(by_rows.is_empty() && !by_rows.is_full());
+
+        let mut by_bytes = NdjsonBatch::new(100, 16);
+        by_bytes.push(&json!({"id": 1, "name": "Chair"})).unwrap();
+        assert!(by_bytes.is_full());
+        assert_eq!(by_bytes.rows(), 1);
+    }
+}
@@ -0,0 +1,86 @@
+/// Генерация SQL триггера, который шлёт NOTIFY с первичным ключом изменённой строки
+pub struct NotifyTrigger;
+
+impl NotifyTrigger {
+    /// Имя канала должно быть простым идентификатором Postgres: его нельзя экранировать в LISTEN
+    pub fn validate_channel(channel: &str) -> Result<(), String> {
+        let valid = !channel.is_empty()
+            && channel.len() <= 63
+            && channel.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            && !channel.starts_with(|c: char| c.is_ascii_digit());
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!(
+                "Invalid notify channel '{}': use lowercase letters, digits and '_' (up to 63 chars)",
+                channel
+            ))
+        }
+    }
+
+    /// SQL функции и триггера для `table` (допускается `schema.table`).
+    /// Полезная нагрузка уведомления — значение `key_column` как текст; при смене ключа
+    /// отправляется и старое значение, чтобы документ со старым id был удалён.
+    pub fn sql(table: &str, key_column: &str, channel: &str) -> Result<String, String> {
+        Self::validate_channel(channel)?;
+
+        let table = table
+            .split('.')
+            .map(Self::quote_identifier)
+            .collect::<Result<Vec<_>, _>>()?
+            .join(".");
+        let key = Self::quote_identifier(key_column)?;
+        let function = format!("\"milli_notify_{}\"", channel);
+
+        Ok(format!(
+            r#"CREATE OR REPLACE FUNCTION {function}() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('{channel}', OLD.{key}::text);
+        RETURN NULL;
+    END IF;
+    IF TG_OP = 'UPDATE' AND OLD.{key} IS DISTINCT FROM NEW.{key} THEN
+        PERFORM pg_notify('{channel}', OLD.{key}::text);
+    END IF;
+    PERFORM pg_notify('{channel}', NEW.{key}::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS {function} ON {table};
+CREATE TRIGGER {function}
+    AFTER INSERT OR UPDATE OR DELETE ON {table}
+    FOR EACH ROW EXECUTE FUNCTION {function}();
+"#
+        ))
+    }
+
+    fn quote_identifier(identifier: &str) -> Result<String, String> {
+        let identifier = identifier.trim();
+        if identifier.is_empty() {
+            return Err("Identifier must not be empty".to_string());
+        }
+        Ok(format!("\"{}\"", identifier.replace('"', "\"\"")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_sql() {
+        let sql = NotifyTrigger::sql("public.products", "id", "products_changes").unwrap();
+        assert!(sql.contains("ON \"public\".\"products\""));
+        assert!(sql.contains("PERFORM pg_notify('products_changes', NEW.\"id\"::text);"));
+        assert!(sql.contains("EXECUTE FUNCTION \"milli_notify_products_changes\"()"));
+    }
+
+    #[test]
+    fn test_channel_validation() {
+        assert!(NotifyTrigger::validate_channel("orders_changes").is_ok());
+        assert!(NotifyTrigger::validate_channel("Orders").is_err());
+        assert!(NotifyTrigger::validate_channel("x'); DROP TABLE y; --").is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use serde_json::{Number, Value};
+use sqlx::postgres::{PgHasArrayType, PgRow, PgTypeInfo, PgTypeKind};
+use sqlx::types::{BigDecimal, Uuid};
+use sqlx::{Column, Decode, Postgres, Row, Type, TypeInfo};
+
+/// Суффикс поля с эпохой (секунды) рядом с колонкой даты/времени
+pub const EPOCH_FIELD_SUFFIX: &str = "_epoch";
+
+/// Преобразует значения колонок Postgres в JSON без потерь:
+/// NUMERIC не проходит через f64, JSON/JSONB становятся вложенными объектами, массивы — JSON-массивами,
+/// даты — строками RFC 3339. Неподдерживаемые типы дают null и предупреждение.
+pub struct PgValueDecoder {
+    epoch_timestamps: bool,
+}
+
+impl PgValueDecoder {
+    /// `epoch_timestamps` — добавлять к TIMESTAMP/TIMESTAMPTZ/DATE поле `<колонка>_epoch` для сортировки
+    pub fn new(epoch_timestamps: bool) -> Self {
+        Self { epoch_timestamps }
+    }
+
+    /// Строка результата как JSON-объект; предупреждения о неразобранных колонках добавляются в `warnings`
+    pub fn decode_row(&self, row: &PgRow, warnings: &mut BTreeSet<String>) -> Value {
+        let mut document = serde_json::Map::new();
+
+        for column in row.columns() {
+            let name = column.name();
+            let type_info = column.type_info();
+            let value = match Self::decode_column(row, column.ordinal(), type_info) {
+                Ok(value) => value,
+                Err(reason) => {
+                    warnings.insert(format!("Column '{}' ({}): {}; value set to null", name, type_info.name(), reason));
+                    Value::Null
+                }
+            };
+
+            if self.epoch_timestamps
+                && let Some(epoch) = Self::epoch_seconds(type_info.name(), &value)
+            {
+                document.insert(format!("{}{}", name, EPOCH_FIELD_SUFFIX), Value::from(epoch));
+            }
+            document.insert(name.to_string(), value);
+        }
+
+        Value::Object(document)
+    }
+
+    fn decode_column(row: &PgRow, index: usize, type_info: &PgTypeInfo) -> Result<Value, String> {
+        let name = type_info.name();
+        let (element, array) = match name.strip_suffix("[]") {
+            Some(element) => (element, true),
+            None => (name, false),
+        };
+
+        match element {
+            "BOOL" => Self::typed(row, index, array, |value: bool| Ok(Value::Bool(value))),
+            "INT2" => Self::typed(row, index, array, |value: i16| Ok(Value::from(value))),
+            "INT4" => Self::typed(row, index, array, |value: i32| Ok(Value::from(value))),
+            "INT8" => Self::typed(row, index, array, |value: i64| Ok(Value::from(value))),
+            // Через строку, чтобы 0.1::real не превращался в 0.10000000149011612
+            "FLOAT4" => Self::typed(row, index, array, |value: f32| Self::float_to_json(value.to_string().parse().unwrap_or(f64::NAN))),
+            "FLOAT8" => Self::typed(row, index, array, Self::float_to_json),
+            "NUMERIC" => Self::typed(row, index, array, |value: BigDecimal| Ok(Self::numeric_to_json(&value))),
+            "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => Self::typed(row, index, array, |value: String| Ok(Value::String(value))),
+            "UUID" => Self::typed(row, index, array, |value: Uuid| Ok(Value::String(value.to_string()))),
+            "JSON" | "JSONB" => Self::typed(row, index, array, |value: Value| Ok(value)),
+            "BYTEA" => Self::typed(row, index, array, |value: Vec<u8>| Ok(Value::String(BASE64.encode(value)))),
+            "TIMESTAMPTZ" => Self::typed(row, index, array, |value: DateTime<Utc>| Ok(Self::timestamp_to_json(value))),
+            // Время без пояса считается UTC
+            "TIMESTAMP" => Self::typed(row, index, array, |value: NaiveDateTime| Ok(Self::timestamp_to_json(value.and_utc()))),
+            "DATE" => Self::typed(row, index, array, |value: NaiveDate| Ok(Value::String(value.format("%Y-%m-%d").to_string()))),
+            "TIME" => Self::typed(row, index, array, |value: NaiveTime| Ok(Value::String(value.to_string()))),
+            _ if !array && matches!(type_info.kind(), PgTypeKind::Enum(_)) => {
+                // Значение перечисления передаётся как его метка
+                row.try_get_unchecked::<Option<String>, _>(index)
+                    .map(|value| value.map(Value::String).unwrap_or(Value::Null))
+                    .map_err(|e| e.to_string())
+            }
+            _ => match row.try_get::<Option<String>, _>(index) {
+                Ok(value) => Ok(value.map(Value::String).unwrap_or(Value::Null)),
+                Err(_) => Err("unsupported type".to_string()),
+            },
+        }
+    }
+
+    /// Тип JSON, в который превращается колонка этого типа Postgres
+    pub fn json_type(type_info: &PgTypeInfo) -> &'static str {
+        if matches!(type_info.kind(), PgTypeKind::Enum(_)) {
+            return "string";
+        }
+        Self::json_type_for(type_info.name())
+    }
+
+    fn json_type_for(type_name: &str) -> &'static str {
+        if type_name.ends_with("[]") {
+            return "array";
+        }
+        match type_name {
+            "BOOL" => "boolean",
+            "INT2" | "INT4" | "INT8" => "integer",
+            "FLOAT4" | "FLOAT8" => "number",
+            // Значения, которые f64 не хранит точно, отдаются строкой
+            "NUMERIC" => "number|string",
+            "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "UUID" | "BYTEA" | "TIMESTAMPTZ" | "TIMESTAMP" | "DATE" | "TIME" => "string",
+            "JSON" | "JSONB" => "any",
+            _ => "unsupported",
+        }
+    }
+
+    /// Читает колонку как `T` (или массив `T`) и преобразует каждое значение
+    fn typed<T>(
+        row: &PgRow,
+        index: usize,
+        array: bool,
+        convert: impl Fn(T) -> Result<Value, String>,
+    ) -> Result<Value, String>
+    where
+        T: for<'r> Decode<'r, Postgres> + Type<Postgres> + PgHasArrayType,
+    {
+        if !array {
+            return match row.try_get::<Option<T>, _>(index).map_err(|e| e.to_string())? {
+                Some(value) => convert(value),
+                None => Ok(Value::Null),
+            };
+        }
+
+        match row.try_get::<Option<Vec<Option<T>>>, _>(index).map_err(|e| e.to_string())? {
+            Some(values) => values
+                .into_iter()
+                .map(|value| value.map_or(Ok(Value::Null), &convert))
+                .collect::<Result<Vec<Value>, String>>()
+                .map(Value::Array),
+            None => Ok(Value::Null),
+        }
+    }
+
+    pub(crate) fn float_to_json(value: f64) -> Result<Value, String> {
+        Number::from_f64(value)
+            .map(Value::Number)
+            .ok_or_else(|| format!("{} is not representable in JSON", value))
+    }
+
+    /// Целые — числом, дроби — числом, только если f64 хранит их точно; иначе строкой со всеми цифрами
+    pub(crate) fn numeric_to_json(value: &BigDecimal) -> Value {
+        if value.is_integer()
+            && let Ok(integer) = value.to_string().parse::<i64>()
+        {
+            return Value::from(integer);
+        }
+
+        let text = value.normalized().to_plain_string();
+        if let Some(number) = text.parse::<f64>().ok().and_then(Number::from_f64)
+            && BigDecimal::from_str(&number.to_string()).is_ok_and(|parsed| &parsed == value)
+        {
+            return Value::Number(number);
+        }
+
+        Value::String(text)
+    }
+
+    pub(crate) fn timestamp_to_json(value: DateTime<Utc>) -> Value {
+        Value::String(value.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+
+    /// Секунды эпохи для уже разобранного значения даты/времени
+    pub(crate) fn epoch_seconds(type_name: &str, value: &Value) -> Option<i64> {
+        let text = value.as_str()?;
+        match type_name {
+            "TIMESTAMP" | "TIMESTAMPTZ" => DateTime::parse_from_rfc3339(text).ok().map(|value| value.timestamp()),
+            "DATE" => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .map(|value| value.and_time(NaiveTime::MIN).and_utc().timestamp()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn numeric_keeps_precision() {
+        let decimal = |text: &str| BigDecimal::from_str(text).unwrap();
+
+        assert_eq!(PgValueDecoder::numeric_to_json(&decimal("42")), json!(42));
+        assert_eq!(PgValueDecoder::numeric_to_json(&decimal("19.90")), json!(19.9));
+        assert_eq!(
+            PgValueDecoder::numeric_to_json(&decimal("12345678901234567890.123456789")),
+            json!("12345678901234567890.123456789")
+        );
+        assert_eq!(
+            PgValueDecoder::numeric_to_json(&decimal("99999999999999999999")),
+            json!("99999999999999999999")
+        );
+    }
+
+    #[test]
+    fn maps_postgres_types_to_json_types() {
+        assert_eq!(PgValueDecoder::json_type_for("INT8"), "integer");
+        assert_eq!(PgValueDecoder::json_type_for("TIMESTAMPTZ"), "string");
+        assert_eq!(PgValueDecoder::json_type_for("JSONB"), "any");
+        assert_eq!(PgValueDecoder::json_type_for("TEXT[]"), "array");
+        assert_eq!(PgValueDecoder::json_type_for("INTERVAL"), "unsupported");
+    }
+
+    #[test]
+    fn epoch_seconds_for_dates_and_timestamps() {
+        assert_eq!(
+            PgValueDecoder::epoch_seconds("TIMESTAMPTZ", &json!("2024-01-01T00:00:10Z")),
+            Some(1_704_067_210)
+        );
+        assert_eq!(PgValueDecoder::epoch_seconds("DATE", &json!("2024-01-01")), Some(1_704_067_200));
+        assert_eq!(PgValueDecoder::epoch_seconds("TEXT", &json!("2024-01-01")), None);
+        assert_eq!(PgValueDecoder::epoch_seconds("TIMESTAMP", &Value::Null), None);
+    }
+}
@@ -0,0 +1,569 @@
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use futures::TryStreamExt;
+use serde_json::Value;
+use sqlx::postgres::{PgArguments, PgPool, PgRow};
+use sqlx::query::Query;
+use sqlx::types::Json;
+use sqlx::{Column, Executor, Postgres, Row, Transaction, TypeInfo};
+use tokio::sync::mpsc;
+use tracing::warn;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::connection_registry::ConnectionRegistry;
+use crate::domain::data_source::services::data_source_connector::SourceObject;
+use crate::domain::data_source::services::document_key::{DocumentKey, TemplatePart, RENDERED_KEY_COLUMN};
+use crate::domain::data_source::services::named_params::{NamedParams, QueryParams};
+use crate::domain::data_source::services::pg_value_decoder::PgValueDecoder;
+use crate::domain::data_source::services::query_executor::{CursorWindow, PreviewColumn, QueryPreview};
+use crate::domain::data_source::services::postgres_connector::PostgresConnector;
+use crate::domain::data_source::services::read_only_query::ReadOnlyQuery;
+
+/// Имя серверного курсора потоковой выборки; живёт только внутри своей транзакции
+const STREAM_CURSOR: &str = "milli_stream";
+
+/// Выполнение запросов к источникам PostgreSQL: транзакции READ ONLY со statement_timeout,
+/// параметры `$n` и потоковая выборка через серверный курсор
+pub(crate) struct PgQueryExecutor {
+    epoch_timestamps: bool,
+    params: QueryParams,
+    statement_timeout: Duration,
+    connections: Option<Arc<ConnectionRegistry>>,
+}
+
+impl PgQueryExecutor {
+    pub(crate) fn new(
+        epoch_timestamps: bool,
+        params: QueryParams,
+        statement_timeout: Duration,
+        connections: Option<Arc<ConnectionRegistry>>,
+    ) -> Self {
+        Self {
+            epoch_timestamps,
+            params,
+            statement_timeout,
+            connections,
+        }
+    }
+
+    /// Строки запроса, при наличии `limit` — не больше указанного числа
+    pub(crate) async fn fetch_all(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Value>, String> {
+        let (query, values) = self.compile(query)?;
+        let final_query = match limit {
+            Some(limit) => Self::prepare_query(&query, limit),
+            None => query,
+        };
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let rows = Self::bind_params(sqlx::query(&final_query), values)?
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+
+        // Завершаем транзакцию и освобождаем подключение
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        Ok(Self::rows_to_json(rows, self.epoch_timestamps))
+    }
+
+
+    /// Первые `limit` строк запроса, схема колонок, оценка общего числа строк и время выполнения.
+    /// Строки разбираются тем же PgValueDecoder, что и при синхронизации.
+    pub(crate) async fn preview(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String> {
+        let (query, values) = self.compile(query)?;
+        let final_query = Self::prepare_query(&query, limit);
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let started = Instant::now();
+        let rows = Self::bind_params(sqlx::query(&final_query), values.clone())?
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+        let execution_time_ms = started.elapsed().as_millis() as u64;
+
+        // Схему берём из описания запроса, чтобы она была и у пустого результата
+        let columns = (&mut *transaction)
+            .describe(&query)
+            .await
+            .map_err(|e| format!("Query describe error: {}", e))?
+            .columns()
+            .iter()
+            .map(|column| PreviewColumn {
+                name: column.name().to_string(),
+                pg_type: column.type_info().name().to_string(),
+                json_type: PgValueDecoder::json_type(column.type_info()).to_string(),
+            })
+            .collect();
+
+        let explain_query = format!("EXPLAIN (FORMAT JSON) {}", query);
+        let mut estimate_warning = None;
+        let estimated_total = match Self::bind_params(sqlx::query(&explain_query), values)?
+            .fetch_one(&mut *transaction)
+            .await
+        {
+            Ok(row) => row
+                .try_get::<Value, _>(0)
+                .ok()
+                .and_then(|plan| plan[0]["Plan"]["Plan Rows"].as_f64())
+                .map(|rows| rows as i64),
+            Err(e) => {
+                let message = format!("Could not estimate row count: {}", e);
+                warn!("{}", message);
+                estimate_warning = Some(message);
+                None
+            }
+        };
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        let (rows, mut warnings) = Self::decode_rows(&rows, self.epoch_timestamps);
+        warnings.extend(estimate_warning);
+        Ok(QueryPreview {
+            columns,
+            rows,
+            warnings,
+            estimated_total,
+            execution_time_ms,
+        })
+    }
+
+    /// Читает результат запроса потоком через серверный курсор и передаёт строки в `sender` по одной.
+    /// Каждый `FETCH` забирает до `chunk_size` строк и ограничен своим statement_timeout,
+    /// поэтому долгая выгрузка не упирается в таймаут. Ограниченный канал не даёт читать
+    /// источник быстрее, чем строки уходят дальше. Возвращает число прочитанных строк.
+    pub(crate) async fn stream_query(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        let (query, values) = self.compile(query)?;
+        let declare = format!(
+            "DECLARE {} NO SCROLL CURSOR FOR {}",
+            STREAM_CURSOR,
+            Self::prepare_batch_query(&query, values.len(), key, cursor)?
+        );
+        let fetch = format!("FETCH FORWARD {} FROM {}", chunk_size.max(1), STREAM_CURSOR);
+        let declare_query = Self::bind_window(Self::bind_params(sqlx::query(&declare), values)?, cursor);
+
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+        declare_query
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+
+        let decoder = PgValueDecoder::new(self.epoch_timestamps);
+        let mut warnings = BTreeSet::new();
+        let mut total = 0u64;
+        loop {
+            let mut received = 0u32;
+            let mut rows = sqlx::query(&fetch).fetch(&mut *transaction);
+            while let Some(row) = rows.try_next().await.map_err(|e| format!("Query execution error: {}", e))? {
+                received += 1;
+                if sender.send(decoder.decode_row(&row, &mut warnings)).await.is_err() {
+                    return Err("Row consumer stopped".to_string());
+                }
+            }
+            drop(rows);
+
+            total += received as u64;
+            if received < chunk_size.max(1) {
+                break;
+            }
+        }
+
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        Ok(total)
+    }
+
+    /// Возвращает текущий максимум курсорной колонки (как текст) и её тип в Postgres.
+    /// Пустой результат запроса даёт `None`.
+    pub(crate) async fn fetch_cursor_bounds(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        let (query, values) = self.compile(query)?;
+        let query_without_semicolon = query.trim().strip_suffix(";").unwrap_or(query.trim());
+        let column = format!("batch.{}", Self::quote_identifier(cursor_column));
+        let bounds_query = format!(
+            "SELECT MAX({column})::text AS max_value, pg_typeof(MAX({column}))::text AS column_type FROM ({query}) AS batch",
+            column = column,
+            query = query_without_semicolon
+        );
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let row = Self::bind_params(sqlx::query(&bounds_query), values)?
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        let max_value: Option<String> = row.try_get("max_value").map_err(|e| e.to_string())?;
+        let column_type: String = row.try_get("column_type").map_err(|e| e.to_string())?;
+
+        Ok(max_value.map(|value| (value, column_type)))
+    }
+
+    /// Возвращает все значения первичного ключа результата запроса в виде строк — так, как их увидит индекс
+    pub(crate) async fn fetch_keys(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String> {
+        let (query, values) = self.compile(query)?;
+        let query_without_semicolon = query.trim().strip_suffix(";").unwrap_or(query.trim());
+        let key_expression = match Self::template_expression(key) {
+            Some(expression) => expression,
+            None => format!("batch.{}::text", Self::quote_identifier(key.field())),
+        };
+        let keys_query = format!(
+            "SELECT {} AS key FROM ({}) AS batch",
+            key_expression,
+            query_without_semicolon
+        );
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let rows = Self::bind_params(sqlx::query(&keys_query), values)?
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        let is_composite = key.template().is_some();
+        rows
+            .iter()
+            .filter_map(|row| row.try_get::<Option<String>, _>("key").ok().flatten())
+            .map(|raw| if is_composite { DocumentKey::sanitize(&raw) } else { Ok(raw) })
+            .collect::<Result<HashSet<String>, String>>()
+    }
+
+    /// Перечитывает строки результата запроса с указанными значениями первичного ключа.
+    /// Составные ключи не поддерживаются: уведомления несут значение одной колонки.
+    /// Нужен только слушателю LISTEN/NOTIFY, поэтому есть только для Postgres.
+    pub(crate) async fn fetch_rows_by_keys(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        keys: &[String],
+    ) -> Result<Vec<Value>, String> {
+        if key.template().is_some() {
+            return Err("Fetching rows by key is not supported for composite primary keys".to_string());
+        }
+
+        let (query, values) = self.compile(query)?;
+        let query_without_semicolon = query.trim().strip_suffix(";").unwrap_or(query.trim());
+        let rows_query = format!(
+            "SELECT * FROM ({}) AS batch WHERE batch.{}::text = ANY(${})",
+            query_without_semicolon,
+            Self::quote_identifier(key.field()),
+            values.len() + 1
+        );
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let rows = Self::bind_params(sqlx::query(&rows_query), values)?
+            .bind(keys)
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?;
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        Ok(Self::rows_to_json(rows, self.epoch_timestamps))
+    }
+
+    /// Таблицы и представления всех пользовательских схем с колонками в порядке объявления
+    pub(crate) async fn introspect(&self, data_source: &DataSource) -> Result<Vec<SourceObject>, String> {
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin_read_only(&pool, self.statement_timeout).await?;
+
+        let rows = sqlx::query(
+            "SELECT table_schema || '.' || table_name AS object_name, column_name::text, data_type::text \
+             FROM information_schema.columns \
+             WHERE table_schema NOT IN ('pg_catalog', 'information_schema') \
+             ORDER BY table_schema, table_name, ordinal_position",
+        )
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(|e| format!("Query execution error: {}", e))?;
+
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))?;
+        self.release(pool).await;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get("object_name")?, row.try_get("column_name")?, row.try_get("data_type")?)))
+            .collect::<Result<Vec<(String, String, String)>, sqlx::Error>>()
+            .map(SourceObject::group)
+            .map_err(|e| e.to_string())
+    }
+
+
+    /// Пул источника из реестра, а без реестра — отдельное подключение на один вызов
+    async fn connect(&self, data_source: &DataSource) -> Result<PgPool, String> {
+        match &self.connections {
+            Some(connections) => connections.pool(data_source),
+            None => PgPool::connect_with(PostgresConnector::connect_options(data_source)?)
+                .await
+                .map_err(|e| format!("Database connection error: {}", e)),
+        }
+    }
+
+    /// Пулы из реестра остаются открытыми для следующих запросов
+    async fn release(&self, pool: PgPool) {
+        if self.connections.is_none() {
+            pool.close().await;
+        }
+    }
+
+    /// Подготавливает запрос с учетом лимита
+    /// Запрос оборачивается в подзапрос, чтобы не дописывать второй LIMIT к запросу, где он уже есть
+    pub(crate) fn prepare_query(query: &str, limit: u32) -> String {
+        format!("SELECT * FROM ({}) AS limited LIMIT {}", query, limit)
+    }
+
+    /// Оборачивает запрос для потокового чтения: сортировка по ключу и окно курсора.
+    /// Составной ключ рендерится в SQL и отдаётся отдельной колонкой RENDERED_KEY_COLUMN.
+    /// `params_count` — сколько позиционных параметров уже занято самим запросом.
+    fn prepare_batch_query(
+        query: &str,
+        params_count: usize,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+    ) -> Result<String, String> {
+        let query_without_semicolon = query.trim().strip_suffix(";").unwrap_or(query.trim());
+        let mut conditions = Vec::new();
+        let (columns, key_expression) = match Self::template_expression(key) {
+            Some(expression) => (
+                format!("batch.*, {} AS {}", expression, Self::quote_identifier(RENDERED_KEY_COLUMN)),
+                expression,
+            ),
+            None => (String::from("*"), format!("batch.{}", Self::quote_identifier(key.field()))),
+        };
+
+        if let Some(cursor) = cursor {
+            if !cursor.column_type.chars().all(|c| c.is_ascii_alphanumeric() || " _[]".contains(c)) {
+                return Err(format!("Unsupported cursor column type: {}", cursor.column_type));
+            }
+            let column = format!("batch.{}", Self::quote_identifier(&cursor.column));
+            if cursor.after.is_some() {
+                conditions.push(format!("{} > CAST(${} AS {})", column, params_count + conditions.len() + 1, cursor.column_type));
+            }
+            conditions.push(format!("{} <= CAST(${} AS {})", column, params_count + conditions.len() + 1, cursor.column_type));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+
+        Ok(format!(
+            "SELECT {} FROM ({}) AS batch{} ORDER BY {}",
+            columns, query_without_semicolon, where_clause, key_expression
+        ))
+    }
+
+    /// Привязывает границы окна курсора; параметры самого запроса идут перед ними
+    fn bind_window<'q>(
+        mut query: Query<'q, Postgres, PgArguments>,
+        cursor: Option<&CursorWindow>,
+    ) -> Query<'q, Postgres, PgArguments> {
+        if let Some(cursor) = cursor {
+            if let Some(after) = &cursor.after {
+                query = query.bind(after.clone());
+            }
+            query = query.bind(cursor.up_to.clone());
+        }
+        query
+    }
+
+    /// SQL-выражение, собирающее составной ключ по шаблону; NULL-колонки дают пустую строку
+    fn template_expression(key: &DocumentKey) -> Option<String> {
+        let parts: Vec<String> = key.template()?
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => format!("'{}'", text.replace('\'', "''")),
+                TemplatePart::Field(field) => format!("COALESCE(batch.{}::text, '')", Self::quote_identifier(field)),
+            })
+            .collect();
+
+        Some(format!("({})", parts.join(" || ")))
+    }
+
+    /// Экранирует имя колонки для подстановки в SQL
+    fn quote_identifier(identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    }
+
+    /// Открывает транзакцию READ ONLY с ограничением времени выполнения каждой инструкции
+    pub(crate) async fn begin_read_only(
+        pool: &PgPool,
+        statement_timeout: Duration,
+    ) -> Result<Transaction<'static, Postgres>, String> {
+        let mut transaction = pool
+            .begin_with("BEGIN READ ONLY")
+            .await
+            .map_err(|e| format!("Transaction start error: {}", e))?;
+
+        // SET не принимает параметры; значение — число миллисекунд
+        sqlx::query(&format!("SET LOCAL statement_timeout = {}", statement_timeout.as_millis()))
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| format!("Transaction start error: {}", e))?;
+
+        Ok(transaction)
+    }
+
+    /// Проверяет, что запрос только читает данные, заменяет `:name` на `$n`
+    /// и собирает значения параметров в порядке номеров
+    fn compile(&self, query: &str) -> Result<(String, Vec<Value>), String> {
+        let compiled = NamedParams::compile(&ReadOnlyQuery::prepare(query)?)?;
+        let values = NamedParams::values(&compiled, &self.params)?;
+        Ok((compiled.sql, values))
+    }
+
+    /// Привязывает значения параметров по типу JSON. Массивы должны быть однородными;
+    /// объекты передаются как jsonb. Для сравнения с колонками других типов используйте приведение (`:from::date`)
+    fn bind_params(
+        mut query: Query<'_, Postgres, PgArguments>,
+        values: Vec<Value>,
+    ) -> Result<Query<'_, Postgres, PgArguments>, String> {
+        for value in values {
+            query = match value {
+                Value::Null => query.bind(None::<String>),
+                Value::Bool(value) => query.bind(value),
+                Value::Number(number) => match number.as_i64() {
+                    Some(integer) => query.bind(integer),
+                    None => query.bind(number.as_f64()),
+                },
+                Value::String(text) => query.bind(text),
+                Value::Array(items) => {
+                    if items.iter().all(Value::is_i64) {
+                        query.bind(items.iter().filter_map(Value::as_i64).collect::<Vec<i64>>())
+                    } else if items.iter().all(Value::is_number) {
+                        query.bind(items.iter().filter_map(Value::as_f64).collect::<Vec<f64>>())
+                    } else if items.iter().all(Value::is_string) {
+                        query.bind(items.iter().filter_map(|item| item.as_str().map(String::from)).collect::<Vec<String>>())
+                    } else if items.iter().all(Value::is_boolean) {
+                        query.bind(items.iter().filter_map(Value::as_bool).collect::<Vec<bool>>())
+                    } else {
+                        return Err(format!("Query parameter arrays must hold values of one type: {}", Value::Array(items)));
+                    }
+                }
+                object @ Value::Object(_) => query.bind(Json(object)),
+            };
+        }
+        Ok(query)
+    }
+
+    /// Преобразует строки результата в JSON; о неразобранных колонках предупреждает один раз на выборку
+    pub(crate) fn rows_to_json(rows: Vec<PgRow>, epoch_timestamps: bool) -> Vec<Value> {
+        let (documents, warnings) = Self::decode_rows(&rows, epoch_timestamps);
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+        documents
+    }
+
+    /// Строки результата в JSON и предупреждения о неразобранных колонках, по одному на колонку
+    fn decode_rows(rows: &[PgRow], epoch_timestamps: bool) -> (Vec<Value>, Vec<String>) {
+        let decoder = PgValueDecoder::new(epoch_timestamps);
+        let mut warnings = BTreeSet::new();
+        let documents = rows.iter().map(|row| decoder.decode_row(row, &mut warnings)).collect();
+        (documents, warnings.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(after: Option<&str>) -> CursorWindow {
+        CursorWindow {
+            column: "updated_at".to_string(),
+            column_type: "timestamp with time zone".to_string(),
+            after: after.map(str::to_string),
+            up_to: "2024-02-01 00:00:00+00".to_string(),
+        }
+    }
+
+    #[test]
+    fn orders_by_key_without_window() {
+        let key = DocumentKey::new("sku", None).unwrap();
+
+        assert_eq!(
+            PgQueryExecutor::prepare_batch_query("SELECT * FROM products;", 0, &key, None).unwrap(),
+            "SELECT * FROM (SELECT * FROM products) AS batch ORDER BY batch.\"sku\""
+        );
+    }
+
+    #[test]
+    fn renders_composite_key_and_numbers_window_after_query_params() {
+        let key = DocumentKey::new("id", Some("{tenant_id}-{id}")).unwrap();
+        let query = PgQueryExecutor::prepare_batch_query(
+            "SELECT * FROM products WHERE shop_id = $1 AND status = $2",
+            2,
+            &key,
+            Some(&window(Some("2024-01-01 00:00:00+00"))),
+        )
+        .unwrap();
+
+        assert_eq!(
+            query,
+            "SELECT batch.*, (COALESCE(batch.\"tenant_id\"::text, '') || '-' || COALESCE(batch.\"id\"::text, '')) AS \"__milli_key\" \
+             FROM (SELECT * FROM products WHERE shop_id = $1 AND status = $2) AS batch \
+             WHERE batch.\"updated_at\" > CAST($3 AS timestamp with time zone) \
+             AND batch.\"updated_at\" <= CAST($4 AS timestamp with time zone) \
+             ORDER BY (COALESCE(batch.\"tenant_id\"::text, '') || '-' || COALESCE(batch.\"id\"::text, ''))"
+        );
+    }
+
+    #[test]
+    fn first_window_has_only_upper_bound_and_rejects_odd_types() {
+        let key = DocumentKey::new("id", None).unwrap();
+        assert_eq!(
+            PgQueryExecutor::prepare_batch_query("SELECT * FROM products", 0, &key, Some(&window(None))).unwrap(),
+            "SELECT * FROM (SELECT * FROM products) AS batch \
+             WHERE batch.\"updated_at\" <= CAST($1 AS timestamp with time zone) ORDER BY batch.\"id\""
+        );
+
+        let mut injected = window(None);
+        injected.column_type = "int); DROP TABLE products; --".to_string();
+        assert!(PgQueryExecutor::prepare_batch_query("SELECT * FROM products", 0, &key, Some(&injected)).is_err());
+    }
+}
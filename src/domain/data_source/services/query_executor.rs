@@ -1,232 +1,204 @@
-use colored::Colorize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::domain::data_source::services::connection_registry::ConnectionRegistry;
+use crate::domain::data_source::services::connector_registry::ConnectorRegistry;
+use crate::domain::data_source::services::data_source_connector::{DataSourceConnector, QueryContext, SourceObject};
+use crate::domain::data_source::services::document_key::DocumentKey;
 use crate::domain::data_source::entities::data_source::DataSource;
 use serde_json::Value;
-use sqlx::postgres::{PgPool, PgRow};
-use sqlx::{Column, Row, TypeInfo};
-use crate::domain::data_source::entities::index_data_query::IndexDataQuery;
-
-pub struct QueryExecutor;
+use serde::Serialize;
+use crate::domain::data_source::services::named_params::QueryParams;
+use tokio::sync::mpsc;
+
+/// Точка входа для чтения источников: выбирает коннектор по `database_type`
+/// и передаёт ему общие параметры, таймаут и пулы
+#[derive(Clone)]
+pub struct QueryExecutor {
+    context: QueryContext,
+    /// Коннекторы по типам источников
+    connectors: Arc<ConnectorRegistry>,
+}
+
+/// statement_timeout по умолчанию, если он не задан явно
+pub const DEFAULT_STATEMENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Каталог файловых источников по умолчанию, относительно рабочего каталога
+pub const DEFAULT_FILE_ROOT: &str = "files";
+
+/// Окно инкрементальной выборки: значения курсорной колонки в диапазоне (after, up_to].
+/// Окно не перекрывается с предыдущим, поэтому колонка должна расти в порядке фиксации транзакций:
+/// строка, закоммиченная позже чтения, но со значением не больше `up_to` (например, `updated_at = now()`
+/// в долгой транзакции), в следующее окно уже не попадёт. Такие таблицы догоняет только полная
+/// переиндексация; надёжнее курсор, который назначается при фиксации (счётчик из триггера, xmin).
+#[derive(Debug, Clone)]
+pub struct CursorWindow {
+    pub column: String,
+    /// Тип колонки в источнике; в Postgres к нему приводятся границы окна
+    pub column_type: String,
+    /// Последняя сохранённая отметка; `None` означает полную выборку
+    pub after: Option<String>,
+    pub up_to: String,
+}
+
+/// Первые строки запроса вместе со схемой колонок — то, что увидит синхронизация
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryPreview {
+    pub columns: Vec<PreviewColumn>,
+    pub rows: Vec<Value>,
+    /// Колонки, которые не удалось разобрать (значение заменено на null), и другие замечания к выборке
+    pub warnings: Vec<String>,
+    /// Оценка числа строк по плану запроса (EXPLAIN), без полного выполнения
+    pub estimated_total: Option<i64>,
+    pub execution_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewColumn {
+    pub name: String,
+    /// Тип колонки в источнике (Postgres, MySQL, SQLite); у файлов совпадает с `json_type`
+    pub pg_type: String,
+    /// Тип значения в документе (см. PgValueDecoder::json_type и MySqlValueDecoder::json_type)
+    pub json_type: String,
+}
 
 impl QueryExecutor {
     pub fn new() -> Self {
-        Self
+        Self {
+            context: QueryContext::default(),
+            connectors: Arc::new(ConnectorRegistry::with_builtin()),
+        }
     }
 
-    /// Выполняет запрос с лимитом для тестирования (возвращает одну запись)
-    pub async fn execute_test_query(
-        &self,
-        data_source: &DataSource,
-        query: &str,
-    ) -> Result<Value, String> {
-        let results = &self.execute_query(&data_source, &query, 1).await?;
-        match results.into_iter().next() {
-            Some(row) => Ok(row.clone()),
-            None => Err("No results found".to_string()),
-        }
+    pub fn with_connections(mut self, connections: Arc<ConnectionRegistry>) -> Self {
+        self.context.connections = Some(connections);
+        self
     }
 
-    /// Выполняет запрос и возвращает все записи или с указанным лимитом
-    pub async fn execute_query(
-        &self,
-        data_source: &DataSource,
-        query: &str,
-        limit: u32,
-    ) -> Result<Vec<Value>, String> {
-        let final_query = Self::prepare_query(query, limit);
-        let connection_string = Self::build_connection_string(data_source);
+    /// Реестр коннекторов вместо встроенного, например с дополнительными типами источников
+    pub fn with_connectors(mut self, connectors: Arc<ConnectorRegistry>) -> Self {
+        self.connectors = connectors;
+        self
+    }
 
-        println!("Connection string: {}", connection_string.color("blue"));
-        println!("Executing query: {}", final_query.color("yellow"));
+    pub fn with_statement_timeout(mut self, statement_timeout: Duration) -> Self {
+        self.context.statement_timeout = statement_timeout;
+        self
+    }
 
-        let pool = PgPool::connect(&connection_string)
-            .await
-            .map_err(|e| format!("Database connection error: {}", e))?;
+    /// Каталог, за пределы которого не выходят пути файловых источников
+    pub fn with_file_root(mut self, file_root: PathBuf) -> Self {
+        self.context.file_root = file_root;
+        self
+    }
 
-        let rows = sqlx::query(&final_query)
-            .fetch_all(&pool)
-            .await
-            .map_err(|e| format!("Query execution error: {}", e))?;
+    /// Добавлять к датам поле `<колонка>_epoch` (см. PgValueDecoder)
+    pub fn with_epoch_timestamps(mut self, epoch_timestamps: bool) -> Self {
+        self.context.epoch_timestamps = epoch_timestamps;
+        self
+    }
 
-        // Закрываем подключение
-        pool.close().await;
+    /// Значения для `:name` в тексте запроса; передаются через bind
+    pub fn with_params(mut self, params: QueryParams) -> Self {
+        self.context.params = params;
+        self
+    }
 
-        Ok(rows.into_iter().map(Self::row_to_json).collect())
+    /// Добавляет (или заменяет) значение одного параметра
+    pub fn with_param(mut self, name: &str, value: Value) -> Self {
+        self.context.params.insert(name.to_string(), value);
+        self
     }
 
-    /// Выполняет запрос и возвращает только первую запись
-    pub async fn execute_single_query(
+    /// Выполняет запрос целиком, без ограничения числа строк
+    pub async fn fetch_all(
+        &self,
         data_source: &DataSource,
         query: &str,
-    ) -> Result<Value, String> {
-        let query_without_semicolon = query.strip_suffix(";").unwrap_or(query);
-        let limited_query = format!("{} LIMIT 1", query_without_semicolon);
-        let connection_string = Self::build_connection_string(data_source);
-
-        println!("Connection string: {}", connection_string.color("blue"));
-
-        let pool = PgPool::connect(&connection_string)
-            .await
-            .map_err(|e| format!("Database connection error: {}", e))?;
-
-        let row = sqlx::query(&limited_query)
-            .fetch_optional(&pool)
-            .await
-            .map_err(|e| format!("Query execution error: {}", e))?;
-
-        pool.close().await;
-
-        Ok(match row {
-            Some(row) => Self::row_to_json(row),
-            None => Value::Null,
-        })
+    ) -> Result<Vec<Value>, String> {
+        self.connector(data_source)?.fetch_rows(&self.context, data_source, query, None).await
     }
 
-    /// Выполняет несколько запросов в транзакции
-    pub async fn execute_batch_queries(
+    /// Первые `limit` строк запроса, схема колонок, оценка общего числа строк и время выполнения.
+    /// Строки разбираются тем же декодером, что и при синхронизации.
+    pub async fn preview(
+        &self,
         data_source: &DataSource,
-        queries: &[String],
-    ) -> Result<Vec<Vec<Value>>, String> {
-        let connection_string = Self::build_connection_string(data_source);
+        query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String> {
+        self.connector(data_source)?.preview(&self.context, data_source, query, limit).await
+    }
 
-        let pool = PgPool::connect(&connection_string)
+    /// Читает результат запроса потоком и передаёт строки в `sender` по одной.
+    /// Источник читается порциями до `chunk_size` строк, и каждая ограничена своим таймаутом,
+    /// поэтому долгая выгрузка не упирается в таймаут. Ограниченный канал не даёт читать
+    /// источник быстрее, чем строки уходят дальше. Возвращает число прочитанных строк.
+    pub async fn stream_query(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        self.connector(data_source)?
+            .stream_rows(&self.context, data_source, query, key, cursor, chunk_size, sender)
             .await
-            .map_err(|e| format!("Database connection error: {}", e))?;
+    }
 
-        let mut transaction = pool
-            .begin()
+    /// Возвращает текущий максимум курсорной колонки (как текст) и её тип в источнике.
+    /// Пустой результат запроса даёт `None`.
+    pub async fn fetch_cursor_bounds(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        self.connector(data_source)?
+            .fetch_cursor_bounds(&self.context, data_source, query, cursor_column)
             .await
-            .map_err(|e| format!("Transaction start error: {}", e))?;
-
-        let mut results = Vec::new();
-
-        for query in queries {
-            let rows = sqlx::query(query)
-                .fetch_all(&mut *transaction)
-                .await
-                .map_err(|e| format!("Query execution error: {}", e))?;
+    }
 
-            results.push(rows.into_iter().map(Self::row_to_json).collect());
-        }
+    /// Возвращает все значения первичного ключа результата запроса в виде строк — так, как их увидит индекс
+    pub async fn fetch_keys(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String> {
+        self.connector(data_source)?.fetch_keys(&self.context, data_source, query, key).await
+    }
 
-        transaction
-            .commit()
+    /// Перечитывает строки результата запроса с указанными значениями первичного ключа.
+    /// Составные ключи не поддерживаются: уведомления несут значение одной колонки.
+    /// Нужен только слушателю LISTEN/NOTIFY, поэтому есть только для Postgres.
+    pub async fn fetch_rows_by_keys(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        keys: &[String],
+    ) -> Result<Vec<Value>, String> {
+        self.connector(data_source)?
+            .fetch_rows_by_keys(&self.context, data_source, query, key, keys)
             .await
-            .map_err(|e| format!("Transaction commit error: {}", e))?;
-
-        pool.close().await;
-        Ok(results)
     }
 
-    /// Тестирует подключение к базе данных
-    pub async fn test_connection(data_source: &DataSource) -> Result<bool, String> {
-        let connection_string = Self::build_connection_string(data_source);
-
-        let pool = PgPool::connect(&connection_string)
-            .await
-            .map_err(|e| format!("Database connection error: {}", e))?;
+    /// Таблицы (файлы, эндпоинты) источника и их колонки
+    pub async fn introspect(&self, data_source: &DataSource) -> Result<Vec<SourceObject>, String> {
+        self.connector(data_source)?.introspect(&self.context, data_source).await
+    }
 
-        // Простой тестовый запрос
-        let _result = sqlx::query("SELECT 1")
-            .fetch_one(&pool)
-            .await
-            .map_err(|e| format!("Test query error: {}", e))?;
-
-        pool.close().await;
-        Ok(true)
-    }
-
-    /// Подготавливает запрос с учетом лимита
-    fn prepare_query(query: &str, limit: u32) -> String {
-        let query_without_semicolon = query.strip_suffix(";").unwrap_or(query);
-
-        format!("{} LIMIT {}", query_without_semicolon, limit)
-    }
-
-    /// Строит строку подключения к PostgreSQL
-    fn build_connection_string(data_source: &DataSource) -> String {
-        format!(
-            "postgresql://{}:{}@{}:{}/{}",
-            data_source.username,
-            data_source.password,
-            data_source.host,
-            data_source.port,
-            data_source.database_name
-        )
-    }
-
-    /// Преобразует строку базы данных в JSON
-    fn row_to_json(row: PgRow) -> Value {
-        let mut json_map = serde_json::Map::new();
-
-        for column in row.columns() {
-            let column_name = column.name();
-            let type_info = column.type_info();
-
-            let value = match type_info.name() {
-                "INT2" => {
-                    match row.try_get::<Option<i16>, _>(column_name) {
-                        Ok(Some(val)) => Value::Number((val as i64).into()),
-                        Ok(None) => Value::Null,
-                        Err(_) => Value::Null,
-                    }
-                },
-                "INT4" => {
-                    match row.try_get::<Option<i32>, _>(column_name) {
-                        Ok(Some(val)) => Value::Number((val as i64).into()),
-                        Ok(None) => Value::Null,
-                        Err(_) => Value::Null,
-                    }
-                },
-                "INT8" => {
-                    match row.try_get::<Option<i64>, _>(column_name) {
-                        Ok(Some(val)) => Value::Number(val.into()),
-                        Ok(None) => Value::Null,
-                        Err(_) => Value::Null,
-                    }
-                },
-                "FLOAT4" | "FLOAT8" | "NUMERIC" => {
-                    match row.try_get::<Option<f64>, _>(column_name) {
-                        Ok(Some(val)) => Value::Number(
-                            serde_json::Number::from_f64(val).unwrap_or(serde_json::Number::from(0))
-                        ),
-                        Ok(None) => Value::Null,
-                        Err(_) => Value::Null,
-                    }
-                },
-                "BOOL" => {
-                    match row.try_get::<Option<bool>, _>(column_name) {
-                        Ok(Some(val)) => Value::Bool(val),
-                        Ok(None) => Value::Null,
-                        Err(_) => Value::Null,
-                    }
-                },
-                "UUID" => {
-                    match row.try_get::<Option<String>, _>(column_name) {
-                        Ok(Some(val)) => Value::String(val.to_string()),
-                        Ok(None) => Value::Null,
-                        Err(_) => Value::Null,
-                    }
-                },
-                "TIMESTAMP" | "TIMESTAMPTZ" => {
-                    match row.try_get::<Option<String>, _>(column_name) {
-                        Ok(Some(val)) => Value::String(val),
-                        Ok(None) => Value::Null,
-                        Err(_) => Value::Null,
-                    }
-                },
-                _ => {
-                    // Для всех остальных типов пытаемся получить как строку
-                    match row.try_get::<Option<String>, _>(column_name) {
-                        Ok(Some(val)) => Value::String(val),
-                        Ok(None) => Value::Null,
-                        Err(_) => Value::Null,
-                    }
-                }
-            };
-
-            json_map.insert(column_name.to_string(), value);
-        }
+    /// Тестирует подключение к источнику
+    pub async fn test_connection(&self, data_source: &DataSource) -> Result<(), String> {
+        self.connector(data_source)?.test_connection(&self.context, data_source).await
+    }
 
-        Value::Object(json_map)
+    /// Коннектор типа источника
+    fn connector(&self, data_source: &DataSource) -> Result<Arc<dyn DataSourceConnector>, String> {
+        self.connectors.require(&data_source.database_type)
     }
-}
\ No newline at end of file
+}
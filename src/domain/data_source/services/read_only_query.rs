@@ -0,0 +1,86 @@
+use crate::domain::data_source::services::sql_lexer::{SqlLexer, SqlSegment};
+
+/// Проверка пользовательского SQL перед выполнением: разрешена одна инструкция SELECT или WITH.
+/// Это первая линия защиты; запросы всё равно выполняются в транзакции READ ONLY,
+/// которая отсечёт и изменяющие CTE, и SELECT INTO.
+pub struct ReadOnlyQuery;
+
+impl ReadOnlyQuery {
+    /// Возвращает запрос без завершающей `;` и хвостовых комментариев, готовый к оборачиванию в подзапрос
+    pub fn prepare(query: &str) -> Result<String, String> {
+        let mut segments = SqlLexer::split(query)?;
+
+        // Всё после первой `;` допустимо, только если там пусто или одни комментарии
+        let terminator = segments.iter().enumerate().find_map(|(position, segment)| match segment {
+            SqlSegment::Code(code) => code.split_once(';').map(|(statement, rest)| (position, statement.to_string(), rest.to_string())),
+            _ => None,
+        });
+        if let Some((position, statement, rest)) = terminator {
+            let rest_is_empty = rest.trim().is_empty()
+                && segments[position + 1..].iter().all(|segment| match segment {
+                    SqlSegment::Code(code) => code.trim().is_empty(),
+                    SqlSegment::Comment(_) => true,
+                    SqlSegment::Literal(_) => false,
+                });
+            if !rest_is_empty {
+                return Err("Only a single SQL statement is allowed".to_string());
+            }
+            segments[position] = SqlSegment::Code(statement);
+            segments.truncate(position + 1);
+        }
+
+        // Хвостовой `-- комментарий` закомментировал бы скобку обёртки `(...) AS batch`
+        while segments.last().is_some_and(|segment| match segment {
+            SqlSegment::Code(code) => code.trim().is_empty(),
+            SqlSegment::Comment(_) => true,
+            SqlSegment::Literal(_) => false,
+        }) {
+            segments.pop();
+        }
+
+        let keyword: String = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                SqlSegment::Code(code) => Some(code.as_str()),
+                _ => None,
+            })
+            .flat_map(|code| code.chars())
+            .skip_while(|c| c.is_whitespace() || *c == '(')
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect::<String>()
+            .to_uppercase();
+        if keyword != "SELECT" && keyword != "WITH" {
+            return Err(format!(
+                "Only SELECT and WITH queries are allowed, got '{}'",
+                if keyword.is_empty() { "empty query" } else { &keyword }
+            ));
+        }
+
+        let statement: String = segments.iter().map(SqlSegment::text).collect();
+        Ok(statement.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_select_and_with() {
+        assert_eq!(ReadOnlyQuery::prepare("SELECT 1;").unwrap(), "SELECT 1");
+        assert_eq!(
+            ReadOnlyQuery::prepare("/* report */ with t AS (SELECT 1) SELECT * FROM t; -- done").unwrap(),
+            "/* report */ with t AS (SELECT 1) SELECT * FROM t"
+        );
+        assert_eq!(ReadOnlyQuery::prepare("(SELECT ';')").unwrap(), "(SELECT ';')");
+        assert_eq!(ReadOnlyQuery::prepare("SELECT 1 -- tail").unwrap(), "SELECT 1");
+    }
+
+    #[test]
+    fn rejects_other_statements() {
+        assert!(ReadOnlyQuery::prepare("DELETE FROM users").is_err());
+        assert!(ReadOnlyQuery::prepare("-- SELECT\nUPDATE users SET a = 1").is_err());
+        assert!(ReadOnlyQuery::prepare("SELECT 1; DROP TABLE users").is_err());
+        assert!(ReadOnlyQuery::prepare("   ").is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use serde_json::{Number, Value};
+use crate::domain::data_source::services::document_key::{DocumentKey, TemplatePart, RENDERED_KEY_COLUMN};
+use crate::domain::data_source::services::index_document_reader::IndexDocumentReader;
+use crate::domain::data_source::services::query_executor::{CursorWindow, PreviewColumn};
+
+/// То, что для SQL-источников делает сам запрос, для источников без SQL (файлы, HTTP API):
+/// окно курсора, составной ключ, порядок ключей и схема колонок по готовым строкам
+pub(crate) struct RowFilter;
+
+impl RowFilter {
+    /// Составной ключ собирается из полей строки так же, как в SQL: отсутствующие поля дают пустую строку
+    pub(crate) fn render_key(key: &DocumentKey, row: &mut Value) {
+        let Some(template) = key.template() else {
+            return;
+        };
+        let Some(fields) = row.as_object_mut() else {
+            return;
+        };
+        let rendered: String = template
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => text.clone(),
+                TemplatePart::Field(field) => match fields.get(field) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(text)) => text.clone(),
+                    Some(value) => value.to_string(),
+                },
+            })
+            .collect();
+        fields.insert(RENDERED_KEY_COLUMN.to_string(), Value::String(rendered));
+    }
+
+    /// Значение ключа keyset-пагинации; для составного ключа строка уже прошла `render_key`
+    pub(crate) fn key_value<'a>(key: &DocumentKey, row: &'a Value) -> &'a Value {
+        let column = if key.template().is_some() { RENDERED_KEY_COLUMN } else { key.field() };
+        row.get(column).unwrap_or(&Value::Null)
+    }
+
+    /// Ключ строки в том виде, в каком он станет id документа
+    pub(crate) fn key_string(key: &DocumentKey, row: &Value) -> Result<Option<String>, String> {
+        let Some(raw) = IndexDocumentReader::key_to_string(Self::key_value(key, row)) else {
+            return Ok(None);
+        };
+        Ok(Some(if key.template().is_some() { DocumentKey::sanitize(&raw)? } else { raw }))
+    }
+
+    /// Строка попадает в окно (after, up_to], если значение курсора сравнимо с границами
+    pub(crate) fn in_window(row: &Value, cursor: Option<&CursorWindow>) -> bool {
+        let Some(cursor) = cursor else {
+            return true;
+        };
+        let value = row.get(&cursor.column).unwrap_or(&Value::Null);
+        let bound = |bound: &str| match cursor.column_type.as_str() {
+            "number" => bound.parse::<f64>().ok().and_then(Number::from_f64).map(Value::Number),
+            _ => Some(Value::String(bound.to_string())),
+        };
+        let (Some(up_to), true) = (bound(&cursor.up_to), Self::same_kind(value, cursor)) else {
+            return false;
+        };
+        let after = match &cursor.after {
+            Some(after) => match bound(after) {
+                Some(after) => Self::compare(value, &after) == Ordering::Greater,
+                None => false,
+            },
+            None => true,
+        };
+        after && Self::compare(value, &up_to) != Ordering::Greater
+    }
+
+    /// Учитывает значение курсорной колонки в максимуме. Значения другого типа,
+    /// чем у первого непустого, не учитываются.
+    pub(crate) fn track_max(max: &mut Option<Value>, row: &Value, cursor_column: &str) {
+        let value = row.get(cursor_column).unwrap_or(&Value::Null);
+        let comparable = match max.as_ref() {
+            None => value.is_number() || value.is_string(),
+            Some(max) => max.is_number() == value.is_number() && max.is_string() == value.is_string(),
+        };
+        if comparable && max.as_ref().is_none_or(|max| Self::compare(value, max) == Ordering::Greater) {
+            *max = Some(value.clone());
+        }
+    }
+
+    /// Максимум курсора как текст и его тип: `number` или `string`
+    pub(crate) fn cursor_bounds(max: Option<Value>) -> Option<(String, String)> {
+        let max = max?;
+        let column_type = if max.is_number() { "number" } else { "string" };
+        IndexDocumentReader::key_to_string(&max).map(|bound| (bound, column_type.to_string()))
+    }
+
+    /// Колонки — все ключи строк в порядке появления, тип выводится по значениям.
+    /// Отдельного типа в источнике нет, поэтому `pg_type` совпадает с `json_type`.
+    pub(crate) fn preview_columns(rows: &[Value]) -> Vec<PreviewColumn> {
+        let mut names: Vec<String> = Vec::new();
+        let mut types: BTreeMap<String, &'static str> = BTreeMap::new();
+        for row in rows.iter().filter_map(Value::as_object) {
+            for (name, value) in row {
+                if !types.contains_key(name) {
+                    names.push(name.clone());
+                }
+                let seen = types.entry(name.clone()).or_insert("null");
+                *seen = Self::merge_types(seen, Self::value_type(value));
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                let json_type = match types[&name] {
+                    "null" => "any",
+                    json_type => json_type,
+                };
+                PreviewColumn { name, pg_type: json_type.to_string(), json_type: json_type.to_string() }
+            })
+            .collect()
+    }
+
+    fn same_kind(value: &Value, cursor: &CursorWindow) -> bool {
+        match cursor.column_type.as_str() {
+            "number" => value.is_number(),
+            _ => value.is_string(),
+        }
+    }
+
+    /// Порядок значений ключа и курсора: null, затем числа, затем строки (как в SQLite)
+    fn compare(a: &Value, b: &Value) -> Ordering {
+        fn rank(value: &Value) -> u8 {
+            match value {
+                Value::Null => 0,
+                Value::Number(_) => 1,
+                Value::String(_) => 2,
+                _ => 3,
+            }
+        }
+        match (a, b) {
+            (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                _ => a.as_f64().partial_cmp(&b.as_f64()).unwrap_or(Ordering::Equal),
+            },
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            _ => rank(a).cmp(&rank(b)),
+        }
+    }
+
+    fn value_type(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(number) if number.is_f64() => "number",
+            Value::Number(_) => "integer",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    /// Общий тип колонки: null не меняет тип, целые вместе с дробными дают number, остальное — any
+    fn merge_types(seen: &'static str, next: &'static str) -> &'static str {
+        match (seen, next) {
+            ("null", next) => next,
+            (seen, "null") => seen,
+            (seen, next) if seen == next => seen,
+            ("integer", "number") | ("number", "integer") => "number",
+            _ => "any",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn filters_rows_by_cursor_window() {
+        let window = CursorWindow {
+            column: "version".to_string(),
+            column_type: "number".to_string(),
+            after: Some("2".to_string()),
+            up_to: "10".to_string(),
+        };
+        assert!(!RowFilter::in_window(&json!({"version": 2}), Some(&window)));
+        assert!(RowFilter::in_window(&json!({"version": 3}), Some(&window)));
+        assert!(RowFilter::in_window(&json!({"version": 10}), Some(&window)));
+        assert!(!RowFilter::in_window(&json!({"version": 11}), Some(&window)));
+        assert!(!RowFilter::in_window(&json!({"version": "5"}), Some(&window)));
+        assert!(!RowFilter::in_window(&json!({}), Some(&window)));
+
+        assert_eq!(RowFilter::merge_types("integer", "number"), "number");
+        assert_eq!(RowFilter::merge_types("null", "string"), "string");
+        assert_eq!(RowFilter::merge_types("string", "integer"), "any");
+    }
+}
@@ -0,0 +1,134 @@
+/// Фрагмент текста SQL-запроса
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlSegment {
+    /// Код запроса, в котором можно искать параметры и ключевые слова
+    Code(String),
+    /// Строка, идентификатор в кавычках (в том числе MySQL-овских `` ` ``) или dollar-quoted строка
+    Literal(String),
+    /// Строчный или блочный комментарий
+    Comment(String),
+}
+
+impl SqlSegment {
+    pub fn text(&self) -> &str {
+        match self {
+            SqlSegment::Code(text) | SqlSegment::Literal(text) | SqlSegment::Comment(text) => text,
+        }
+    }
+}
+
+/// Делит запрос на код, литералы и комментарии, чтобы разбор параметров и ключевых слов
+/// не заглядывал внутрь строк (`':name'`, `'DELETE'`) и комментариев
+pub struct SqlLexer;
+
+impl SqlLexer {
+    pub fn split(query: &str) -> Result<Vec<SqlSegment>, String> {
+        let chars: Vec<char> = query.chars().collect();
+        let mut segments = Vec::new();
+        let mut code = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            let previous_is_identifier = i > 0 && Self::is_identifier_char(chars[i - 1]);
+
+            let end = match c {
+                '\'' => {
+                    // E'...' допускает экранирование обратной косой чертой
+                    let escapes = i > 0 && matches!(chars[i - 1], 'E' | 'e')
+                        && (i < 2 || !Self::is_identifier_char(chars[i - 2]));
+                    Some((Self::quoted_end(&chars, i, '\'', escapes)?, false))
+                }
+                '"' | '`' => Some((Self::quoted_end(&chars, i, c, false)?, false)),
+                '-' if next == Some('-') => {
+                    let end = chars[i..].iter().position(|c| *c == '\n').map_or(chars.len(), |offset| i + offset);
+                    Some((end, true))
+                }
+                '/' if next == Some('*') => Some((Self::comment_end(&chars, i)?, true)),
+                '$' if !previous_is_identifier => Self::dollar_quoted_end(&chars, i).map(|end| (end, false)),
+                _ => None,
+            };
+
+            match end {
+                Some((end, comment)) => {
+                    if !code.is_empty() {
+                        segments.push(SqlSegment::Code(std::mem::take(&mut code)));
+                    }
+                    let text: String = chars[i..end].iter().collect();
+                    segments.push(if comment { SqlSegment::Comment(text) } else { SqlSegment::Literal(text) });
+                    i = end;
+                }
+                None => {
+                    code.push(c);
+                    i += 1;
+                }
+            }
+        }
+
+        if !code.is_empty() {
+            segments.push(SqlSegment::Code(code));
+        }
+        Ok(segments)
+    }
+
+    pub fn is_identifier_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '_'
+    }
+
+    /// Позиция сразу за закрывающей кавычкой; удвоенная кавычка внутри не закрывает строку
+    fn quoted_end(chars: &[char], start: usize, quote: char, escapes: bool) -> Result<usize, String> {
+        let mut i = start + 1;
+        while i < chars.len() {
+            if escapes && chars[i] == '\\' {
+                i += 2;
+                continue;
+            }
+            if chars[i] == quote {
+                if chars.get(i + 1) == Some(&quote) {
+                    i += 2;
+                    continue;
+                }
+                return Ok(i + 1);
+            }
+            i += 1;
+        }
+        Err(format!("Unterminated {} in query", if quote == '\'' { "string literal" } else { "quoted identifier" }))
+    }
+
+    /// Блочные комментарии в Postgres могут быть вложенными
+    fn comment_end(chars: &[char], start: usize) -> Result<usize, String> {
+        let mut depth = 0;
+        let mut i = start;
+        while i + 1 < chars.len() {
+            match (chars[i], chars[i + 1]) {
+                ('/', '*') => {
+                    depth += 1;
+                    i += 2;
+                }
+                ('*', '/') => {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        return Ok(i);
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+        Err("Unterminated comment in query".to_string())
+    }
+
+    /// `$tag$ ... $tag$`; None, если `$` не открывает такую строку
+    fn dollar_quoted_end(chars: &[char], start: usize) -> Option<usize> {
+        let tag_end = chars[start + 1..].iter().position(|c| !Self::is_identifier_char(*c))? + start + 1;
+        if chars[tag_end] != '$' || chars.get(start + 1).is_some_and(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let delimiter: Vec<char> = chars[start..=tag_end].to_vec();
+        let body = tag_end + 1;
+        (body..=chars.len().saturating_sub(delimiter.len()))
+            .find(|i| chars[*i..*i + delimiter.len()] == delimiter[..])
+            .map(|i| i + delimiter.len())
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::sync::mpsc;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::data_source_connector::{ConnectionField, ConnectorDescriptor, DataSourceConnector, FieldKind, QueryContext, SourceObject};
+use crate::domain::data_source::services::document_key::DocumentKey;
+use crate::domain::data_source::services::query_executor::{CursorWindow, QueryPreview};
+use crate::domain::data_source::services::sqlite_query_executor::SqliteQueryExecutor;
+
+/// Файлы SQLite на сервере, открытые только для чтения (`database_type = "sqlite"`)
+pub struct SqliteConnector;
+
+impl SqliteConnector {
+    fn executor(context: &QueryContext) -> SqliteQueryExecutor {
+        SqliteQueryExecutor::new(context.epoch_timestamps, context.params.clone(), context.statement_timeout, context.connections.clone())
+    }
+}
+
+#[async_trait]
+impl DataSourceConnector for SqliteConnector {
+    fn descriptor(&self) -> ConnectorDescriptor {
+        ConnectorDescriptor {
+            database_type: "sqlite",
+            label: "SQLite",
+            fields: vec![ConnectionField::new("database_path", "Database file", FieldKind::Path).required()],
+        }
+    }
+
+    /// Файл существует и открывается только для чтения, как и при синхронизации
+    async fn test_connection(&self, _context: &QueryContext, data_source: &DataSource) -> Result<(), String> {
+        let pool = SqlitePool::connect_with(SqliteQueryExecutor::connect_options(data_source))
+            .await
+            .map_err(|e| format!("Database connection error: {}", e))?;
+        pool.close().await;
+        Ok(())
+    }
+
+    async fn introspect(&self, context: &QueryContext, data_source: &DataSource) -> Result<Vec<SourceObject>, String> {
+        Self::executor(context).introspect(data_source).await
+    }
+
+    async fn preview(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String> {
+        Self::executor(context).preview(data_source, query, limit).await
+    }
+
+    async fn stream_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        Self::executor(context).stream_query(data_source, query, key, cursor, chunk_size, sender).await
+    }
+
+    async fn fetch_rows(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Value>, String> {
+        Self::executor(context).fetch_all(data_source, query, limit).await
+    }
+
+    async fn fetch_cursor_bounds(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        Self::executor(context).fetch_cursor_bounds(data_source, query, cursor_column).await
+    }
+
+    async fn fetch_keys(
+        &self,
+        context: &QueryContext,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String> {
+        Self::executor(context).fetch_keys(data_source, query, key).await
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use serde_json::Value;
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteConnectOptions, SqlitePool, SqliteRow};
+use sqlx::types::Json;
+use sqlx::{Column, Executor, Row, Sqlite, Transaction};
+use tokio::sync::mpsc;
+use tracing::warn;
+use crate::domain::data_source::entities::data_source::DataSource;
+use crate::domain::data_source::services::connection_registry::ConnectionRegistry;
+use crate::domain::data_source::services::data_source_connector::SourceObject;
+use crate::domain::data_source::services::document_key::{DocumentKey, TemplatePart, RENDERED_KEY_COLUMN};
+use crate::domain::data_source::services::named_params::{NamedParams, QueryParams};
+use crate::domain::data_source::services::query_executor::{CursorWindow, PreviewColumn, QueryPreview};
+use crate::domain::data_source::services::read_only_query::ReadOnlyQuery;
+use crate::domain::data_source::services::sqlite_value_decoder::SqliteValueDecoder;
+
+/// Как часто (в инструкциях виртуальной машины SQLite) проверять, не истёк ли таймаут
+const PROGRESS_CHECK_OPS: i32 = 1000;
+
+/// Выполнение запросов к файлам SQLite. Файл открывается только для чтения,
+/// а statement_timeout заменяет progress handler, который прерывает слишком долгую инструкцию.
+pub(crate) struct SqliteQueryExecutor {
+    epoch_timestamps: bool,
+    params: QueryParams,
+    statement_timeout: Duration,
+    connections: Option<Arc<ConnectionRegistry>>,
+}
+
+impl SqliteQueryExecutor {
+    pub(crate) fn new(
+        epoch_timestamps: bool,
+        params: QueryParams,
+        statement_timeout: Duration,
+        connections: Option<Arc<ConnectionRegistry>>,
+    ) -> Self {
+        Self {
+            epoch_timestamps,
+            params,
+            statement_timeout,
+            connections,
+        }
+    }
+
+    /// Параметры подключения к файлу источника: только чтение, без создания отсутствующего файла
+    pub(crate) fn connect_options(data_source: &DataSource) -> SqliteConnectOptions {
+        SqliteConnectOptions::new()
+            .filename(&data_source.database_path)
+            .read_only(true)
+            .create_if_missing(false)
+    }
+
+    /// Строки запроса, при наличии `limit` — не больше указанного числа
+    pub(crate) async fn fetch_all(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Value>, String> {
+        let (query, values) = self.compile(query)?;
+        let final_query = match limit {
+            Some(limit) => Self::prepare_query(&query, limit),
+            None => query,
+        };
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin(&pool).await?;
+
+        let rows = self.fetch_rows(&mut transaction, &final_query, values).await?;
+
+        Self::commit(transaction).await?;
+        self.release(pool).await;
+
+        Ok(Self::rows_to_json(rows, self.epoch_timestamps))
+    }
+
+    /// Предпросмотр; SQLite не оценивает число строк в плане, поэтому `estimated_total` всегда пуст
+    pub(crate) async fn preview(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        limit: u32,
+    ) -> Result<QueryPreview, String> {
+        let (query, values) = self.compile(query)?;
+        let final_query = Self::prepare_query(&query, limit);
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin(&pool).await?;
+
+        let started = Instant::now();
+        let rows = self.fetch_rows(&mut transaction, &final_query, values).await?;
+        let execution_time_ms = started.elapsed().as_millis() as u64;
+
+        let columns = (&mut *transaction)
+            .describe(&query)
+            .await
+            .map_err(|e| format!("Query describe error: {}", e))?
+            .columns()
+            .iter()
+            .map(|column| PreviewColumn {
+                name: column.name().to_string(),
+                pg_type: sqlx::TypeInfo::name(column.type_info()).to_string(),
+                json_type: SqliteValueDecoder::json_type(column.type_info()).to_string(),
+            })
+            .collect();
+
+        Self::commit(transaction).await?;
+        self.release(pool).await;
+
+        let (rows, warnings) = Self::decode_rows(&rows, self.epoch_timestamps);
+        Ok(QueryPreview {
+            columns,
+            rows,
+            warnings,
+            estimated_total: None,
+            execution_time_ms,
+        })
+    }
+
+    /// Потоковая выборка страницами по `chunk_size` строк в одной транзакции чтения,
+    /// поэтому все страницы видят один снимок файла. Страница дочитывается до отправки в канал,
+    /// и таймаут считается только на чтение.
+    pub(crate) async fn stream_query(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+        cursor: Option<&CursorWindow>,
+        chunk_size: u32,
+        sender: mpsc::Sender<Value>,
+    ) -> Result<u64, String> {
+        let chunk_size = chunk_size.max(1);
+        let (query, values) = self.compile(query)?;
+        let key_column = match key.template() {
+            Some(_) => RENDERED_KEY_COLUMN,
+            None => key.field(),
+        };
+
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin(&pool).await?;
+
+        let decoder = SqliteValueDecoder::new(self.epoch_timestamps);
+        let mut warnings = BTreeSet::new();
+        let mut total = 0u64;
+        let mut after_key: Option<Value> = None;
+        loop {
+            let mut page_values = values.clone();
+            let page_query = Self::prepare_batch_query(&query, key, after_key.as_ref(), cursor, chunk_size, &mut page_values);
+            let rows = self.fetch_rows(&mut transaction, &page_query, page_values).await?;
+            let received = rows.len() as u32;
+
+            let mut last_key = None;
+            for row in rows {
+                let document = decoder.decode_row(&row, &mut warnings);
+                last_key = document.get(key_column).cloned();
+                if sender.send(document).await.is_err() {
+                    return Err("Row consumer stopped".to_string());
+                }
+            }
+
+            total += received as u64;
+            if received < chunk_size {
+                break;
+            }
+            match last_key {
+                Some(value) if !value.is_null() => after_key = Some(value),
+                _ => return Err(format!("Key column '{}' is missing or null", key_column)),
+            }
+        }
+
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+
+        Self::commit(transaction).await?;
+        self.release(pool).await;
+
+        Ok(total)
+    }
+
+    /// Максимум курсорной колонки как текст и класс хранения этого значения (`integer`, `real`, `text`);
+    /// по классу границы окна потом привязываются тем же типом
+    pub(crate) async fn fetch_cursor_bounds(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        cursor_column: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        let (query, values) = self.compile(query)?;
+        let column = format!("batch.{}", Self::quote_identifier(cursor_column));
+        let bounds_query = format!(
+            "SELECT CAST(MAX({column}) AS TEXT) AS max_value, typeof(MAX({column})) AS column_type FROM ({query}) AS batch",
+            column = column,
+            query = query
+        );
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin(&pool).await?;
+
+        let mut rows = self.fetch_rows(&mut transaction, &bounds_query, values).await?;
+
+        Self::commit(transaction).await?;
+        self.release(pool).await;
+
+        let Some(row) = rows.pop() else {
+            return Ok(None);
+        };
+        let max_value: Option<String> = row.try_get("max_value").map_err(|e| e.to_string())?;
+        let column_type: String = row.try_get("column_type").map_err(|e| e.to_string())?;
+
+        Ok(max_value.map(|value| (value, column_type)))
+    }
+
+    /// Все значения первичного ключа результата запроса в виде строк
+    pub(crate) async fn fetch_keys(
+        &self,
+        data_source: &DataSource,
+        query: &str,
+        key: &DocumentKey,
+    ) -> Result<HashSet<String>, String> {
+        let (query, values) = self.compile(query)?;
+        let key_expression = match Self::template_expression(key) {
+            Some(expression) => expression,
+            None => format!("CAST(batch.{} AS TEXT)", Self::quote_identifier(key.field())),
+        };
+        let keys_query = format!("SELECT {} AS key FROM ({}) AS batch", key_expression, query);
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin(&pool).await?;
+
+        let rows = self.fetch_rows(&mut transaction, &keys_query, values).await?;
+
+        Self::commit(transaction).await?;
+        self.release(pool).await;
+
+        let is_composite = key.template().is_some();
+        rows
+            .iter()
+            .filter_map(|row| row.try_get::<Option<String>, _>("key").ok().flatten())
+            .map(|raw| if is_composite { DocumentKey::sanitize(&raw) } else { Ok(raw) })
+            .collect::<Result<HashSet<String>, String>>()
+    }
+
+    /// Таблицы и представления файла с колонками в порядке объявления; служебные `sqlite_*` пропускаются
+    pub(crate) async fn introspect(&self, data_source: &DataSource) -> Result<Vec<SourceObject>, String> {
+        let pool = self.connect(data_source).await?;
+        let mut transaction = Self::begin(&pool).await?;
+
+        let rows = self
+            .fetch_rows(
+                &mut transaction,
+                "SELECT m.name AS object_name, p.name AS column_name, p.type AS data_type \
+                 FROM sqlite_master AS m JOIN pragma_table_info(m.name) AS p \
+                 WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%' \
+                 ORDER BY m.name, p.cid",
+                Vec::new(),
+            )
+            .await?;
+
+        Self::commit(transaction).await?;
+        self.release(pool).await;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get("object_name")?, row.try_get("column_name")?, row.try_get("data_type")?)))
+            .collect::<Result<Vec<(String, String, String)>, sqlx::Error>>()
+            .map(SourceObject::group)
+            .map_err(|e| e.to_string())
+    }
+
+    async fn connect(&self, data_source: &DataSource) -> Result<SqlitePool, String> {
+        match &self.connections {
+            Some(connections) => connections.sqlite_pool(data_source),
+            None => SqlitePool::connect_with(Self::connect_options(data_source))
+                .await
+                .map_err(|e| format!("Database connection error: {}", e)),
+        }
+    }
+
+    async fn release(&self, pool: SqlitePool) {
+        if self.connections.is_none() {
+            pool.close().await;
+        }
+    }
+
+    /// Отложенная транзакция: блокировка чтения берётся первой выборкой и держится до конца
+    async fn begin(pool: &SqlitePool) -> Result<Transaction<'static, Sqlite>, String> {
+        pool.begin().await.map_err(|e| format!("Transaction start error: {}", e))
+    }
+
+    /// Снимает progress handler, чтобы подключение вернулось в пул без истёкшего таймаута
+    async fn commit(mut transaction: Transaction<'static, Sqlite>) -> Result<(), String> {
+        transaction
+            .lock_handle()
+            .await
+            .map_err(|e| format!("Transaction commit error: {}", e))?
+            .remove_progress_handler();
+        transaction.commit().await.map_err(|e| format!("Transaction commit error: {}", e))
+    }
+
+    /// Выполняет одну инструкцию; если она идёт дольше statement_timeout, SQLite прерывает её
+    async fn fetch_rows(
+        &self,
+        transaction: &mut Transaction<'static, Sqlite>,
+        query: &str,
+        values: Vec<Value>,
+    ) -> Result<Vec<SqliteRow>, String> {
+        let deadline = Instant::now() + self.statement_timeout;
+        transaction
+            .lock_handle()
+            .await
+            .map_err(|e| format!("Query execution error: {}", e))?
+            .set_progress_handler(PROGRESS_CHECK_OPS, move || Instant::now() < deadline);
+
+        Self::bind_params(sqlx::query(query), values)?
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|e| {
+                if Instant::now() >= deadline {
+                    format!("Query execution error: statement timeout of {} ms exceeded", self.statement_timeout.as_millis())
+                } else {
+                    format!("Query execution error: {}", e)
+                }
+            })
+    }
+
+    fn compile(&self, query: &str) -> Result<(String, Vec<Value>), String> {
+        NamedParams::positional(&ReadOnlyQuery::prepare(query)?, &self.params)
+    }
+
+    fn prepare_query(query: &str, limit: u32) -> String {
+        format!("SELECT * FROM ({}) AS limited LIMIT {}", query, limit)
+    }
+
+    /// Страница keyset-пагинации; значения ключа и границ окна дописываются в `values`
+    /// в том же порядке, в каком их `?` стоят в запросе
+    fn prepare_batch_query(
+        query: &str,
+        key: &DocumentKey,
+        after_key: Option<&Value>,
+        cursor: Option<&CursorWindow>,
+        limit: u32,
+        values: &mut Vec<Value>,
+    ) -> String {
+        let mut conditions = Vec::new();
+        let template = Self::template_expression(key);
+        let columns = match &template {
+            Some(expression) => format!("batch.*, {} AS {}", expression, Self::quote_identifier(RENDERED_KEY_COLUMN)),
+            None => String::from("*"),
+        };
+        let key_expression = template.unwrap_or_else(|| format!("batch.{}", Self::quote_identifier(key.field())));
+
+        if let Some(after_key) = after_key {
+            conditions.push(format!("{} > ?", key_expression));
+            values.push(after_key.clone());
+        }
+        if let Some(cursor) = cursor {
+            let column = format!("batch.{}", Self::quote_identifier(&cursor.column));
+            if let Some(after) = &cursor.after {
+                conditions.push(format!("{} > ?", column));
+                values.push(Self::window_value(after, &cursor.column_type));
+            }
+            conditions.push(format!("{} <= ?", column));
+            values.push(Self::window_value(&cursor.up_to, &cursor.column_type));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+
+        format!(
+            "SELECT {} FROM ({}) AS batch{} ORDER BY {} LIMIT {}",
+            columns, query, where_clause, key_expression, limit
+        )
+    }
+
+    /// Граница окна того же класса хранения, что и значения колонки: в SQLite число всегда меньше текста
+    fn window_value(bound: &str, column_type: &str) -> Value {
+        let number = match column_type {
+            "integer" => bound.parse::<i64>().ok().map(Value::from),
+            "real" => bound.parse::<f64>().ok().map(Value::from),
+            _ => None,
+        };
+        number.unwrap_or_else(|| Value::String(bound.to_string()))
+    }
+
+    /// Составной ключ по шаблону; NULL-колонки дают пустую строку
+    fn template_expression(key: &DocumentKey) -> Option<String> {
+        let parts: Vec<String> = key.template()?
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => format!("'{}'", text.replace('\'', "''")),
+                TemplatePart::Field(field) => format!("COALESCE(CAST(batch.{} AS TEXT), '')", Self::quote_identifier(field)),
+            })
+            .collect();
+
+        Some(format!("({})", parts.join(" || ")))
+    }
+
+    fn quote_identifier(identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    }
+
+    /// Массивы к этому моменту уже раскрыты в отдельные `?`, объекты передаются как JSON-текст
+    fn bind_params<'q>(
+        mut query: Query<'q, Sqlite, SqliteArguments<'q>>,
+        values: Vec<Value>,
+    ) -> Result<Query<'q, Sqlite, SqliteArguments<'q>>, String> {
+        for value in values {
+            query = match value {
+                Value::Null => query.bind(None::<String>),
+                Value::Bool(value) => query.bind(value),
+                Value::Number(number) => match number.as_i64() {
+                    Some(integer) => query.bind(integer),
+                    None => query.bind(number.as_f64()),
+                },
+                Value::String(text) => query.bind(text),
+                Value::Array(items) => return Err(format!("Nested arrays are not supported as query parameters: {}", Value::Array(items))),
+                object @ Value::Object(_) => query.bind(Json(object)),
+            };
+        }
+        Ok(query)
+    }
+
+    fn rows_to_json(rows: Vec<SqliteRow>, epoch_timestamps: bool) -> Vec<Value> {
+        let (documents, warnings) = Self::decode_rows(&rows, epoch_timestamps);
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+        documents
+    }
+
+    /// Строки результата в JSON и предупреждения о неразобранных колонках, по одному на колонку
+    fn decode_rows(rows: &[SqliteRow], epoch_timestamps: bool) -> (Vec<Value>, Vec<String>) {
+        let decoder = SqliteValueDecoder::new(epoch_timestamps);
+        let mut warnings = BTreeSet::new();
+        let documents = rows.iter().map(|row| decoder.decode_row(row, &mut warnings)).collect();
+        (documents, warnings.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pages_by_string_key_within_integer_window() {
+        let key = DocumentKey::new("sku", None).unwrap();
+        let cursor = CursorWindow {
+            column: "version".to_string(),
+            column_type: "integer".to_string(),
+            after: Some("41".to_string()),
+            up_to: "57".to_string(),
+        };
+        let mut values = vec![json!("active")];
+
+        let query = SqliteQueryExecutor::prepare_batch_query(
+            "SELECT * FROM products WHERE status = ?",
+            &key,
+            Some(&json!("AB-10")),
+            Some(&cursor),
+            100,
+            &mut values,
+        );
+
+        assert_eq!(
+            query,
+            "SELECT * FROM (SELECT * FROM products WHERE status = ?) AS batch \
+             WHERE batch.\"sku\" > ? AND batch.\"version\" > ? AND batch.\"version\" <= ? \
+             ORDER BY batch.\"sku\" LIMIT 100"
+        );
+        // Границы окна — числа, иначе SQLite сравнил бы INTEGER с TEXT и отбросил все строки
+        assert_eq!(values, vec![json!("active"), json!("AB-10"), json!(41), json!(57)]);
+    }
+
+    #[test]
+    fn renders_composite_key_with_text_concatenation() {
+        let key = DocumentKey::new("id", Some("{shop}:{id}")).unwrap();
+        let mut values = Vec::new();
+
+        let query = SqliteQueryExecutor::prepare_batch_query("SELECT * FROM products", &key, None, None, 10, &mut values);
+
+        assert_eq!(
+            query,
+            "SELECT batch.*, (COALESCE(CAST(batch.\"shop\" AS TEXT), '') || ':' || COALESCE(CAST(batch.\"id\" AS TEXT), '')) AS \"__milli_key\" \
+             FROM (SELECT * FROM products) AS batch \
+             ORDER BY (COALESCE(CAST(batch.\"shop\" AS TEXT), '') || ':' || COALESCE(CAST(batch.\"id\" AS TEXT), '')) LIMIT 10"
+        );
+        assert!(values.is_empty());
+    }
+}
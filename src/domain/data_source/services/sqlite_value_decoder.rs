@@ -0,0 +1,134 @@
+use std::collections::BTreeSet;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDateTime};
+use serde_json::Value;
+use sqlx::sqlite::{SqliteRow, SqliteTypeInfo};
+use sqlx::{Column, Row, TypeInfo, ValueRef};
+use crate::domain::data_source::services::pg_value_decoder::{PgValueDecoder, EPOCH_FIELD_SUFFIX};
+
+/// Преобразует значения SQLite в JSON. Тип значения в SQLite задаёт не колонка, а само значение
+/// (класс хранения), поэтому решает он: INTEGER и REAL — числа, TEXT — строка, BLOB — base64.
+/// Объявленный тип колонки уточняет разбор: BOOLEAN даёт true/false,
+/// DATETIME/TIMESTAMP в тексте приводятся к RFC 3339, если формат распознан.
+pub struct SqliteValueDecoder {
+    epoch_timestamps: bool,
+}
+
+impl SqliteValueDecoder {
+    /// `epoch_timestamps` — добавлять к DATE/DATETIME поле `<колонка>_epoch` для сортировки
+    pub fn new(epoch_timestamps: bool) -> Self {
+        Self { epoch_timestamps }
+    }
+
+    /// Строка результата как JSON-объект; предупреждения о неразобранных колонках добавляются в `warnings`
+    pub fn decode_row(&self, row: &SqliteRow, warnings: &mut BTreeSet<String>) -> Value {
+        let mut document = serde_json::Map::new();
+
+        for column in row.columns() {
+            let name = column.name();
+            let declared = column.type_info().name();
+            let value = match Self::decode_column(row, column.ordinal(), declared) {
+                Ok(value) => value,
+                Err(reason) => {
+                    warnings.insert(format!("Column '{}' ({}): {}; value set to null", name, declared, reason));
+                    Value::Null
+                }
+            };
+
+            if self.epoch_timestamps
+                && let Some(epoch) = Self::epoch_seconds(declared, &value)
+            {
+                document.insert(format!("{}{}", name, EPOCH_FIELD_SUFFIX), Value::from(epoch));
+            }
+            document.insert(name.to_string(), value);
+        }
+
+        Value::Object(document)
+    }
+
+    fn decode_column(row: &SqliteRow, index: usize, declared: &str) -> Result<Value, String> {
+        let raw = row.try_get_raw(index).map_err(|e| e.to_string())?;
+        if raw.is_null() {
+            return Ok(Value::Null);
+        }
+        let storage = raw.type_info().name().to_string();
+
+        match (storage.as_str(), declared) {
+            ("INTEGER", "BOOLEAN") => Self::get::<i64>(row, index).map(|value| Value::Bool(value != 0)),
+            ("INTEGER", _) => Self::get::<i64>(row, index).map(Value::from),
+            ("REAL", _) => Self::get::<f64>(row, index).and_then(PgValueDecoder::float_to_json),
+            ("TEXT", "DATETIME") => Self::get::<String>(row, index).map(|text| match Self::parse_datetime(&text) {
+                Some(value) => PgValueDecoder::timestamp_to_json(value.and_utc()),
+                None => Value::String(text),
+            }),
+            ("TEXT", _) => Self::get::<String>(row, index).map(Value::String),
+            ("BLOB", _) => Self::get::<Vec<u8>>(row, index).map(|value| Value::String(BASE64.encode(value))),
+            _ => Err("unsupported storage class".to_string()),
+        }
+    }
+
+    /// Тип JSON по объявленному типу колонки; у выражений без объявленного типа он заранее неизвестен
+    pub fn json_type(type_info: &SqliteTypeInfo) -> &'static str {
+        Self::json_type_for(type_info.name())
+    }
+
+    fn json_type_for(type_name: &str) -> &'static str {
+        match type_name {
+            "BOOLEAN" => "boolean",
+            "INTEGER" => "integer",
+            "REAL" => "number",
+            "TEXT" | "BLOB" | "DATE" | "TIME" | "DATETIME" => "string",
+            _ => "any",
+        }
+    }
+
+    /// Значение уже известного класса хранения; проверку объявленного типа sqlx здесь не делает
+    fn get<T>(row: &SqliteRow, index: usize) -> Result<T, String>
+    where
+        T: for<'r> sqlx::Decode<'r, sqlx::Sqlite> + sqlx::Type<sqlx::Sqlite>,
+    {
+        row.try_get_unchecked::<T, _>(index).map_err(|e| e.to_string())
+    }
+
+    /// Форматы, в которых SQLite хранит дату и время в тексте: `YYYY-MM-DD HH:MM:SS[.SSS]`,
+    /// тот же с `T`, и RFC 3339 с поясом
+    fn parse_datetime(text: &str) -> Option<NaiveDateTime> {
+        if let Ok(value) = DateTime::parse_from_rfc3339(text) {
+            return Some(value.naive_utc());
+        }
+        ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    }
+
+    /// Для DATETIME в виде числа значение уже является секундами эпохи
+    fn epoch_seconds(declared: &str, value: &Value) -> Option<i64> {
+        match (declared, value) {
+            ("DATETIME", Value::Number(number)) => number.as_i64(),
+            ("DATETIME", _) => PgValueDecoder::epoch_seconds("TIMESTAMP", value),
+            ("DATE", _) => PgValueDecoder::epoch_seconds("DATE", value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_sqlite_datetime_text() {
+        assert_eq!(
+            SqliteValueDecoder::parse_datetime("2024-01-01 00:00:10").map(|value| value.and_utc().timestamp()),
+            Some(1_704_067_210)
+        );
+        assert!(SqliteValueDecoder::parse_datetime("2024-01-01T03:00:10+03:00").is_some());
+        assert!(SqliteValueDecoder::parse_datetime("yesterday").is_none());
+
+        assert_eq!(SqliteValueDecoder::epoch_seconds("DATETIME", &json!(1_704_067_210)), Some(1_704_067_210));
+        assert_eq!(SqliteValueDecoder::epoch_seconds("DATE", &json!("2024-01-01")), Some(1_704_067_200));
+        assert_eq!(SqliteValueDecoder::json_type_for("NULL"), "any");
+    }
+}
@@ -0,0 +1,70 @@
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+
+/// Часовой пояс расписания, если он не указан
+pub const DEFAULT_TIMEZONE: &str = "UTC";
+
+/// Расписание синхронизации запроса: cron-выражение в заданном часовом поясе
+#[derive(Debug, Clone)]
+pub struct SyncSchedule {
+    schedule: Schedule,
+    timezone: Tz,
+}
+
+impl SyncSchedule {
+    /// Принимает классический cron из 5 полей (`*/15 * * * *`) и формат с секундами (6–7 полей).
+    /// Дни недели надёжнее задавать именами (`MON-FRI`): в числовом виде воскресенье — это 1.
+    pub fn parse(expression: &str, timezone: Option<&str>) -> Result<Self, String> {
+        let expression = expression.trim();
+        let normalized = match expression.split_whitespace().count() {
+            5 => format!("0 {}", expression),
+            6 | 7 => expression.to_string(),
+            _ => return Err(format!("Invalid cron expression '{}': expected 5 to 7 fields", expression)),
+        };
+
+        let schedule = Schedule::from_str(&normalized)
+            .map_err(|e| format!("Invalid cron expression '{}': {}", expression, e))?;
+
+        let timezone = timezone.unwrap_or(DEFAULT_TIMEZONE);
+        let timezone = Tz::from_str(timezone)
+            .map_err(|_| format!("Unknown timezone '{}'", timezone))?;
+
+        Ok(Self { schedule, timezone })
+    }
+
+    /// Ближайшее время запуска строго после `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule
+            .after(&after.with_timezone(&self.timezone))
+            .next()
+            .map(|fire_time| fire_time.with_timezone(&Utc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_five_field_expression() {
+        let schedule = SyncSchedule::parse("*/15 * * * *", None).unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 10, 7, 30).unwrap();
+        assert_eq!(schedule.next_after(now), Some(Utc.with_ymd_and_hms(2025, 1, 1, 10, 15, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_timezone_is_applied() {
+        let schedule = SyncSchedule::parse("0 2 * * *", Some("Europe/Moscow")).unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(schedule.next_after(now), Some(Utc.with_ymd_and_hms(2025, 1, 1, 23, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(SyncSchedule::parse("every minute", None).is_err());
+        assert!(SyncSchedule::parse("0 2 * * *", Some("Mars/Olympus")).is_err());
+    }
+}
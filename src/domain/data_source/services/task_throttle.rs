@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use anyhow::Error;
+use meilisearch_sdk::client::Client;
+use meilisearch_sdk::tasks::TasksSearchQuery;
+use tracing::info;
+
+/// Незавершённые задачи: ещё в очереди или выполняются
+const PENDING_STATUSES: [&str; 2] = ["enqueued", "processing"];
+
+/// Первая пауза перед повторной проверкой очереди; дальше она удваивается
+const MIN_POLL_DELAY: Duration = Duration::from_millis(250);
+const MAX_POLL_DELAY: Duration = Duration::from_secs(5);
+
+/// Пороги, при которых синхронизация приостанавливает отправку пачек
+#[derive(Debug, Clone, Copy)]
+pub struct TaskThrottleSettings {
+    /// Предел незавершённых задач индекса от всех синхронизаций
+    pub watermark: u64,
+    /// Предел незавершённых задач одной синхронизации
+    pub max_in_flight: usize,
+}
+
+/// Обратное давление на очередь задач Meilisearch: перед отправкой пачки ждёт,
+/// пока очередь индекса опустится ниже порога и у синхронизации освободится слот.
+/// Пауза между проверками растёт, пока очередь не разгрузится.
+pub struct TaskThrottle {
+    client: Client,
+    index_uid: String,
+    settings: TaskThrottleSettings,
+    /// Задачи этой синхронизации, которые ещё не завершились
+    in_flight: Vec<u32>,
+    throttled: Duration,
+}
+
+impl TaskThrottle {
+    pub fn new(client: Client, index_uid: &str, settings: TaskThrottleSettings) -> Self {
+        Self {
+            client,
+            index_uid: index_uid.to_string(),
+            settings,
+            in_flight: Vec::new(),
+            throttled: Duration::ZERO,
+        }
+    }
+
+    /// Ждёт разрешения отправить следующую пачку
+    pub async fn acquire(&mut self) -> Result<(), Error> {
+        let started = Instant::now();
+        let mut delay = MIN_POLL_DELAY;
+
+        loop {
+            self.refresh_in_flight().await?;
+            let queued = self.queued().await?;
+            if !self.should_wait(queued) {
+                break;
+            }
+
+            info!(
+                "Throttling sync into '{}': {} tasks queued, {} in flight",
+                self.index_uid, queued, self.in_flight.len()
+            );
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_POLL_DELAY);
+        }
+
+        if delay > MIN_POLL_DELAY {
+            self.throttled += started.elapsed();
+        }
+        Ok(())
+    }
+
+    /// Учитывает задачу, созданную после `acquire`
+    pub fn track(&mut self, task_uid: u32) {
+        self.in_flight.push(task_uid);
+    }
+
+    /// Сколько синхронизация простояла в ожидании очереди
+    pub fn throttled(&self) -> Duration {
+        self.throttled
+    }
+
+    fn should_wait(&self, queued: u64) -> bool {
+        queued >= self.settings.watermark || self.in_flight.len() >= self.settings.max_in_flight
+    }
+
+    /// Убирает из `in_flight` задачи, которые Meilisearch уже завершил
+    async fn refresh_in_flight(&mut self) -> Result<(), Error> {
+        if self.in_flight.is_empty() {
+            return Ok(());
+        }
+
+        let pending: HashSet<u32> = TasksSearchQuery::new(&self.client)
+            .with_uids(self.in_flight.iter())
+            .with_statuses(PENDING_STATUSES)
+            .with_limit(self.in_flight.len() as u32)
+            .execute()
+            .await?
+            .results
+            .iter()
+            .map(|task| task.get_uid())
+            .collect();
+        self.in_flight.retain(|uid| pending.contains(uid));
+        Ok(())
+    }
+
+    /// Число незавершённых задач индекса; нужен только счётчик total
+    async fn queued(&self) -> Result<u64, Error> {
+        let tasks = TasksSearchQuery::new(&self.client)
+            .with_index_uids([self.index_uid.as_str()])
+            .with_statuses(PENDING_STATUSES)
+            .with_limit(1)
+            .execute()
+            .await?;
+        Ok(tasks.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waits_above_watermark_or_in_flight_cap() {
+        let client = Client::new("http://localhost:7700", Some("key")).unwrap();
+        let mut throttle = TaskThrottle::new(client, "products", TaskThrottleSettings { watermark: 10, max_in_flight: 2 });
+
+        assert!(!throttle.should_wait(9));
+        assert!(throttle.should_wait(10));
+
+        throttle.track(1);
+        throttle.track(2);
+        assert!(throttle.should_wait(0));
+    }
+}
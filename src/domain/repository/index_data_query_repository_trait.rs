@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use crate::database::Database;
+use crate::domain::data_source::entities::child_query::ChildQuery;
 use crate::domain::data_source::entities::index_data_query::IndexDataQuery;
+use crate::domain::data_source::entities::transformation_spec::TransformationSpec;
 use crate::presentation::requests::index_data_query::store_index_data_query_request::StoreIndexDataQueryRequest;
 
 pub trait IndexDataQueryRepositoryTrait {
@@ -8,6 +10,16 @@ pub trait IndexDataQueryRepositoryTrait {
     async fn get(&self, id: u32) -> Option<IndexDataQuery>;
     async fn all(&self, filter:Option<HashMap<String,String>>) -> Result<Vec<IndexDataQuery>, anyhow::Error>;
     async fn store(&self, data: &StoreIndexDataQueryRequest) -> Option<IndexDataQuery>;
+    /// Сохранить (или сбросить) отметку инкрементальной синхронизации
+    async fn update_cursor(&self, id: u32, cursor_value: Option<String>) -> Result<(), anyhow::Error>;
+    /// Задать (или убрать) расписание синхронизации
+    async fn update_schedule(&self, id: u32, cron_expression: Option<String>, timezone: Option<String>) -> Result<(), anyhow::Error>;
+    /// Задать (или убрать) преобразование документов
+    async fn update_transformation(&self, id: u32, transformation: Option<TransformationSpec>) -> Result<(), anyhow::Error>;
+    /// Заменить дочерние запросы; пустой список их убирает
+    async fn update_child_queries(&self, id: u32, child_queries: Vec<ChildQuery>) -> Result<(), anyhow::Error>;
+    /// Включить (или выключить) прослушивание канала NOTIFY
+    async fn update_notify_channel(&self, id: u32, notify_channel: Option<String>) -> Result<(), anyhow::Error>;
     fn update(&self, id: i32, data: Self) -> Option<Self> where Self: Sized;
     fn delete(&self, id: i32) -> Option<Self> where Self: Sized;
     fn delete_all(&self) -> Vec<Self> where Self: Sized;
@@ -1,2 +1,3 @@
 pub mod data_source_repository_trait;
-pub mod index_data_query_repository_trait;
\ No newline at end of file
+pub mod index_data_query_repository_trait;
+pub mod sync_run_repository_trait;
\ No newline at end of file
@@ -0,0 +1,22 @@
+use crate::database::Database;
+use crate::domain::data_source::entities::sync_run::{SyncRun, SyncRunStats, SyncRunStatus, SyncTrigger};
+
+pub trait SyncRunRepositoryTrait {
+    fn new(db: Database) -> Self;
+    /// Записать начало запуска; возвращает его id
+    async fn start(&self, index_data_query_id: u32, trigger: SyncTrigger) -> Result<u32, anyhow::Error>;
+    /// Записать итог запуска
+    async fn finish(
+        &self,
+        id: u32,
+        status: SyncRunStatus,
+        stats: &SyncRunStats,
+        error: Option<String>,
+    ) -> Result<(), anyhow::Error>;
+    /// Последние запуски запроса, новые первыми
+    async fn list(&self, index_data_query_id: u32, limit: u32) -> Result<Vec<SyncRun>, anyhow::Error>;
+    /// Последний успешный запуск запроса
+    async fn last_succeeded(&self, index_data_query_id: u32) -> Result<Option<SyncRun>, anyhow::Error>;
+    /// Пометить как прерванные запуски, оставшиеся в состоянии running после остановки сервера
+    async fn fail_interrupted(&self) -> Result<u64, anyhow::Error>;
+}
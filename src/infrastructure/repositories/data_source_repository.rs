@@ -1,6 +1,7 @@
 use crate::database::Database;
 use crate::domain::data_source::entities::data_source::DataSource;
 use crate::domain::data_source::entities::index_data_query::IndexDataQuery;
+use crate::domain::data_source::entities::secret::Secret;
 use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
 use crate::presentation::requests::index_data_query::store_index_data_query_request::StoreIndexDataQueryRequest;
 use colored::Colorize;
@@ -39,11 +40,19 @@ impl DataSourceRepositoryTrait for DataSourceRepository {
                 host: row.get("host"),
                 database: row.get("database"),
                 username: row.get("username"),
-                password: row.get("password"),
+                password: Secret::from_stored(row.get("password")),
                 port: row.get("port"),
                 database_path: row.get("database_path"),
                 database_name: row.get("database_name"),
                 database_type: row.get("database_type"),
+                file_options: row
+                    .get::<Option<String>, _>("file_options")
+                    .and_then(|file_options| serde_json::from_str(&file_options).ok())
+                    .unwrap_or_default(),
+                http_options: row
+                    .get::<Option<String>, _>("http_options")
+                    .and_then(|http_options| serde_json::from_str(&http_options).ok())
+                    .unwrap_or_default(),
             }),
             Err(_) => None,
         }
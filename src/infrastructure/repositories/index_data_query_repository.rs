@@ -1,18 +1,59 @@
 use std::collections::HashMap;
 use crate::database::Database;
+use crate::domain::data_source::entities::child_query::ChildQuery;
 use crate::domain::data_source::entities::index_data_query::IndexDataQuery;
+use crate::domain::data_source::entities::transformation_spec::TransformationSpec;
+use crate::domain::data_source::services::document_key::DEFAULT_PRIMARY_KEY;
 use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
 use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
 use crate::infrastructure::repositories::data_source_repository::DataSourceRepository;
 use crate::presentation::requests::index_data_query::store_index_data_query_request::StoreIndexDataQueryRequest;
+use serde::de::DeserializeOwned;
 use serde_json::json;
 use sqlx::sqlite::SqliteRow;
 use sqlx::{Error, Row};
 
+/// Доля документов, которую сверка удалений может удалить за запуск, если не указано иное
+pub const DEFAULT_MAX_DELETE_RATIO: f64 = 0.1;
+
 pub struct IndexDataQueryRepository {
     db: Database,
 }
 
+impl IndexDataQueryRepository {
+    /// Преобразует строку таблицы index_data_queries в сущность.
+    /// Испорченный JSON в transformation, params или child_queries — ошибка: молча отброшенное
+    /// преобразование изменило бы документы индекса при следующей синхронизации.
+    fn map_row(row: &SqliteRow) -> Result<IndexDataQuery, Error> {
+        Ok(IndexDataQuery {
+            id: row.get("id"),
+            data_source_id: row.get("data_source_id"),
+            index_uid: row.get("index_uid"),
+            query: row.get("query"),
+            cursor_column: row.get("cursor_column"),
+            cursor_value: row.get("cursor_value"),
+            delete_orphans: row.get("delete_orphans"),
+            max_delete_ratio: row.get("max_delete_ratio"),
+            cron_expression: row.get("cron_expression"),
+            timezone: row.get("timezone"),
+            transformation: Self::json_column(row, "transformation")?,
+            primary_key: row.get("primary_key"),
+            primary_key_template: row.get("primary_key_template"),
+            notify_channel: row.get("notify_channel"),
+            epoch_timestamps: row.get("epoch_timestamps"),
+            params: Self::json_column(row, "params")?.unwrap_or_default(),
+            child_queries: Self::json_column(row, "child_queries")?.unwrap_or_default(),
+        })
+    }
+
+    fn json_column<T: DeserializeOwned>(row: &SqliteRow, column: &str) -> Result<Option<T>, Error> {
+        row.get::<Option<String>, _>(column)
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(|e| Error::ColumnDecode { index: column.to_string(), source: Box::new(e) })
+    }
+}
+
 impl IndexDataQueryRepositoryTrait for IndexDataQueryRepository {
     fn new(db: Database) -> Self {
         Self { db }
@@ -25,18 +66,15 @@ impl IndexDataQueryRepositoryTrait for IndexDataQueryRepository {
             .fetch_one(connection)
             .await;
 
-        match result {
-            Ok(rows) => {
-                Some(IndexDataQuery {
-                    id: rows.get("id"),
-                    data_source_id: rows.get("data_source_id"),
-                    index_uid: rows.get("index_uid"),
-                    query: rows.get("query"),
-                })
-            }
-            Err(_) => None,
+        match result.and_then(|row| Self::map_row(&row)) {
+            Ok(index_data_query) => Some(index_data_query),
+            Err(Error::RowNotFound) => None,
+            Err(e) => {
+                eprintln!("❌ Index data query {}: {}", id, e);
+                None
             }
         }
+    }
 
     /**
      * Get all index data queries
@@ -58,12 +96,7 @@ impl IndexDataQueryRepositoryTrait for IndexDataQueryRepository {
             Ok(rows) => {
                 let mut index_data_queries = Vec::new();
                 for row in rows {
-                    index_data_queries.push(IndexDataQuery {
-                        id: row.get("id"),
-                        data_source_id: row.get("data_source_id"),
-                        index_uid: row.get("index_uid"),
-                        query: row.get("query"),
-                    });
+                    index_data_queries.push(Self::map_row(&row)?);
                 }
                 Ok(index_data_queries)
             }
@@ -79,24 +112,45 @@ impl IndexDataQueryRepositoryTrait for IndexDataQueryRepository {
         let data_source = data.data_source_id;
         let index_uid = data.index_uid.clone();
         let query = data.query.clone();
+        let cursor_column = data.cursor_column.clone().filter(|column| !column.trim().is_empty());
+        let delete_orphans = data.delete_orphans.unwrap_or(false);
+        let max_delete_ratio = data.max_delete_ratio.unwrap_or(DEFAULT_MAX_DELETE_RATIO);
+        let cron_expression = data.cron_expression.clone().filter(|expression| !expression.trim().is_empty());
+        let timezone = data.timezone.clone().filter(|timezone| !timezone.trim().is_empty());
+        let transformation = data.transformation.as_ref().map(|spec| json!(spec).to_string());
+        let primary_key = data.primary_key.clone()
+            .filter(|key| !key.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PRIMARY_KEY.to_string());
+        let primary_key_template = data.primary_key_template.clone().filter(|template| !template.trim().is_empty());
+        let notify_channel = data.notify_channel.clone().filter(|channel| !channel.trim().is_empty());
+        let epoch_timestamps = data.epoch_timestamps.unwrap_or(false);
+        let params = data.params.as_ref().filter(|params| !params.is_empty()).map(|params| json!(params).to_string());
+        let child_queries = data.child_queries.as_ref().filter(|child_queries| !child_queries.is_empty()).map(|child_queries| json!(child_queries).to_string());
 
         let connection = self.db.get_pool_connection().await.unwrap();
         println!("Got connection successfully");
 
-        let result = sqlx::query(r#"INSERT INTO index_data_queries ("data_source_id","index_uid","query") VALUES ($1,$2,$3) RETURNING *"#)
+        let result = sqlx::query(r#"INSERT INTO index_data_queries ("data_source_id","index_uid","query","cursor_column","delete_orphans","max_delete_ratio","cron_expression","timezone","transformation","primary_key","primary_key_template","notify_channel","epoch_timestamps","params","child_queries") VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15) RETURNING *"#)
             .bind(data_source)
             .bind(index_uid)
             .bind(query)
+            .bind(cursor_column)
+            .bind(delete_orphans)
+            .bind(max_delete_ratio)
+            .bind(cron_expression)
+            .bind(timezone)
+            .bind(transformation)
+            .bind(primary_key)
+            .bind(primary_key_template)
+            .bind(notify_channel)
+            .bind(epoch_timestamps)
+            .bind(params)
+            .bind(child_queries)
             .fetch_one(connection)
             .await;
 
         match result {
-            Ok(row) => Some(IndexDataQuery {
-                id: row.get("id"),
-                data_source_id: row.get("data_source_id"),
-                index_uid: row.get("index_uid"),
-                query: row.get("query"),
-            }),
+            Ok(row) => Self::map_row(&row).inspect_err(|e| println!("Query result: Err({:?})", e)).ok(),
             Err(e) => {
                 println!("Query result: Err({:?})", e);
                 None
@@ -104,6 +158,83 @@ impl IndexDataQueryRepositoryTrait for IndexDataQueryRepository {
         }
     }
 
+    async fn update_cursor(&self, id: u32, cursor_value: Option<String>) -> Result<(), anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let result = sqlx::query(r#"UPDATE index_data_queries SET cursor_value = $1, updated_at = datetime('now') WHERE id = $2"#)
+            .bind(cursor_value)
+            .bind(id)
+            .execute(connection)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Index data query {} not found", id));
+        }
+
+        Ok(())
+    }
+
+    async fn update_schedule(&self, id: u32, cron_expression: Option<String>, timezone: Option<String>) -> Result<(), anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let result = sqlx::query(r#"UPDATE index_data_queries SET cron_expression = $1, timezone = $2, updated_at = datetime('now') WHERE id = $3"#)
+            .bind(cron_expression)
+            .bind(timezone)
+            .bind(id)
+            .execute(connection)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Index data query {} not found", id));
+        }
+
+        Ok(())
+    }
+
+    async fn update_transformation(&self, id: u32, transformation: Option<TransformationSpec>) -> Result<(), anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let result = sqlx::query(r#"UPDATE index_data_queries SET transformation = $1, updated_at = datetime('now') WHERE id = $2"#)
+            .bind(transformation.map(|spec| json!(spec).to_string()))
+            .bind(id)
+            .execute(connection)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Index data query {} not found", id));
+        }
+
+        Ok(())
+    }
+
+    async fn update_child_queries(&self, id: u32, child_queries: Vec<ChildQuery>) -> Result<(), anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let child_queries = Some(child_queries).filter(|child_queries| !child_queries.is_empty());
+        let result = sqlx::query(r#"UPDATE index_data_queries SET child_queries = $1, updated_at = datetime('now') WHERE id = $2"#)
+            .bind(child_queries.map(|child_queries| json!(child_queries).to_string()))
+            .bind(id)
+            .execute(connection)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Index data query {} not found", id));
+        }
+
+        Ok(())
+    }
+
+    async fn update_notify_channel(&self, id: u32, notify_channel: Option<String>) -> Result<(), anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let result = sqlx::query(r#"UPDATE index_data_queries SET notify_channel = $1, updated_at = datetime('now') WHERE id = $2"#)
+            .bind(notify_channel)
+            .bind(id)
+            .execute(connection)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(anyhow::anyhow!("Index data query {} not found", id));
+        }
+
+        Ok(())
+    }
+
     fn update(&self, id: i32, data: Self) -> Option<Self>
     where
         Self: Sized,
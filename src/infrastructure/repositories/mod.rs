@@ -1,2 +1,3 @@
 pub mod data_source_repository;
-pub mod index_data_query_repository;
\ No newline at end of file
+pub mod index_data_query_repository;
+pub mod sync_run_repository;
\ No newline at end of file
@@ -0,0 +1,102 @@
+use crate::database::Database;
+use crate::domain::data_source::entities::sync_run::{SyncRun, SyncRunStats, SyncRunStatus, SyncTrigger};
+use crate::domain::repository::sync_run_repository_trait::SyncRunRepositoryTrait;
+use serde_json::json;
+use sqlx::Row;
+use sqlx::sqlite::SqliteRow;
+
+pub struct SyncRunRepository {
+    db: Database,
+}
+
+impl SyncRunRepository {
+    /// Преобразует строку таблицы sync_runs в сущность
+    fn map_row(row: &SqliteRow) -> SyncRun {
+        SyncRun {
+            id: row.get("id"),
+            index_data_query_id: row.get("index_data_query_id"),
+            trigger: row.get("trigger_source"),
+            status: row.get("status"),
+            started_at: row.get("started_at"),
+            finished_at: row.get("finished_at"),
+            rows_read: row.get::<i64, _>("rows_read") as u64,
+            documents_sent: row.get::<i64, _>("documents_sent") as u64,
+            task_uids: serde_json::from_str(&row.get::<String, _>("task_uids")).unwrap_or_default(),
+            error: row.get("error"),
+            throttled_ms: row.get::<i64, _>("throttled_ms") as u64,
+        }
+    }
+}
+
+impl SyncRunRepositoryTrait for SyncRunRepository {
+    fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn start(&self, index_data_query_id: u32, trigger: SyncTrigger) -> Result<u32, anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let row = sqlx::query(r#"INSERT INTO sync_runs ("index_data_query_id","trigger_source","status") VALUES ($1,$2,$3) RETURNING id"#)
+            .bind(index_data_query_id)
+            .bind(trigger.as_str())
+            .bind(SyncRunStatus::Running.as_str())
+            .fetch_one(connection)
+            .await?;
+
+        Ok(row.get("id"))
+    }
+
+    async fn finish(
+        &self,
+        id: u32,
+        status: SyncRunStatus,
+        stats: &SyncRunStats,
+        error: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        sqlx::query(r#"UPDATE sync_runs SET status = $1, finished_at = datetime('now'), rows_read = $2, documents_sent = $3, task_uids = $4, throttled_ms = $5, error = $6 WHERE id = $7"#)
+            .bind(status.as_str())
+            .bind(stats.rows_read as i64)
+            .bind(stats.documents_sent as i64)
+            .bind(json!(stats.task_uids).to_string())
+            .bind(stats.throttled_ms as i64)
+            .bind(error)
+            .bind(id)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self, index_data_query_id: u32, limit: u32) -> Result<Vec<SyncRun>, anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let rows = sqlx::query(r#"SELECT * FROM sync_runs WHERE index_data_query_id = $1 ORDER BY id DESC LIMIT $2"#)
+            .bind(index_data_query_id)
+            .bind(limit)
+            .fetch_all(connection)
+            .await?;
+
+        Ok(rows.iter().map(Self::map_row).collect())
+    }
+
+    async fn last_succeeded(&self, index_data_query_id: u32) -> Result<Option<SyncRun>, anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let row = sqlx::query(r#"SELECT * FROM sync_runs WHERE index_data_query_id = $1 AND status = $2 ORDER BY id DESC LIMIT 1"#)
+            .bind(index_data_query_id)
+            .bind(SyncRunStatus::Succeeded.as_str())
+            .fetch_optional(connection)
+            .await?;
+
+        Ok(row.as_ref().map(Self::map_row))
+    }
+
+    async fn fail_interrupted(&self) -> Result<u64, anyhow::Error> {
+        let connection = self.db.get_pool_connection().await?;
+        let result = sqlx::query(r#"UPDATE sync_runs SET status = $1, finished_at = datetime('now'), error = 'Interrupted by server shutdown' WHERE status = $2"#)
+            .bind(SyncRunStatus::Failed.as_str())
+            .bind(SyncRunStatus::Running.as_str())
+            .execute(connection)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
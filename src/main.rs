@@ -4,7 +4,15 @@ use anyhow::Context;
 use crate::config::application::ApplicationConfig;
 use crate::database::Database;
 use crate::state::AppState;
+use crate::application::workers::sync_worker::SyncWorker;
+use crate::domain::repository::sync_run_repository_trait::SyncRunRepositoryTrait;
+use crate::infrastructure::repositories::sync_run_repository::SyncRunRepository;
+use crate::queues::domain::value_objects::queue_name::QueueName;
+use crate::application::workers::sync_scheduler::SyncScheduler;
+use crate::application::workers::notify_listener::NotifyListener;
 use crate::presentation::cli::cli_app::Args;
+use crate::application::use_cases::data_source::rotate_credentials_key_use_case::RotateCredentialsKeyUseCase;
+use crate::domain::data_source::services::credential_cipher::CredentialCipher;
 
 mod config;
 mod controllers;
@@ -50,6 +58,30 @@ async fn initialize_and_run_server() -> Result<(), Box<dyn std::error::Error>> {
     let config = ApplicationConfig::new().await.expect("Failed to load config");
     let database = Database::new(&config.db_path).await?;
     let state = AppState::new(config, database).await?;
+    // Запуски, оборванные прошлой остановкой сервера, уже не завершатся
+    let interrupted = SyncRunRepository::new((*state.database).clone()).fail_interrupted().await?;
+    if interrupted > 0 {
+        tracing::warn!("{} interrupted sync runs marked as failed", interrupted);
+    }
+    // Задачи, выполнявшиеся в момент остановки, иначе навсегда считаются активными
+    for queue_name in [QueueName::IndexDocuments, QueueName::ReindexAll] {
+        let interrupted = state.job_service.fail_interrupted_jobs(&queue_name).await?;
+        if interrupted > 0 {
+            tracing::warn!("{} interrupted jobs in '{}' marked as failed", interrupted, queue_name);
+        }
+    }
+    // Пароли, сохранённые до появления шифрования, шифруются при первом запуске
+    let encrypted = RotateCredentialsKeyUseCase::new((*state.database).clone())
+        .encrypt_plaintext(CredentialCipher::installed()?)
+        .await?;
+    if encrypted > 0 {
+        tracing::info!("{} data source passwords encrypted", encrypted);
+    }
+    tokio::spawn(SyncWorker::new(state.clone(), QueueName::IndexDocuments).run());
+    tokio::spawn(SyncWorker::new(state.clone(), QueueName::ReindexAll).run());
+    tokio::spawn(SyncScheduler::new(state.clone()).run());
+    tokio::spawn(NotifyListener::new(state.clone()).run());
+    tokio::spawn(state.connections.clone().run_eviction());
     let app = app::create_app(state).await;
 
     let server_address = "127.0.0.1:3000";
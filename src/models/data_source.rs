@@ -1,14 +1,68 @@
+use std::fmt;
 use sqlx::FromRow;
+use crate::domain::data_source::entities::secret::Secret;
 
-#[derive(Debug, FromRow)]
+#[derive(FromRow)]
 pub struct DataSource {
     pub name: String,
     pub host: String,
     pub database: String,
     pub username: String,
+    /// Зашифрованное значение (см. CredentialCipher)
     pub password: String,
     pub port: u16,
     pub database_path: String,
     pub database_name: String,
     pub database_type: String,
+    /// JSON FileSourceOptions для `database_type = "file"`
+    pub file_options: Option<String>,
+    /// JSON HttpSourceOptions для `database_type = "http"`
+    pub http_options: Option<String>,
+}
+
+impl DataSource {
+    /// Сущность источника для QueryExecutor; настройки файлов и HTTP разбираются из JSON
+    pub fn to_entity(&self) -> crate::domain::data_source::entities::data_source::DataSource {
+        crate::domain::data_source::entities::data_source::DataSource {
+            id: 0,
+            name: self.name.clone(),
+            host: self.host.clone(),
+            database: self.database.clone(),
+            username: self.username.clone(),
+            password: Secret::from_stored(self.password.clone()),
+            port: self.port,
+            database_path: self.database_path.clone(),
+            database_name: self.database_name.clone(),
+            database_type: self.database_type.clone(),
+            file_options: self
+                .file_options
+                .as_deref()
+                .and_then(|file_options| serde_json::from_str(file_options).ok())
+                .unwrap_or_default(),
+            http_options: self
+                .http_options
+                .as_deref()
+                .and_then(|http_options| serde_json::from_str(http_options).ok())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Пароль не выводится даже в зашифрованном виде
+impl fmt::Debug for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataSource")
+            .field("name", &self.name)
+            .field("host", &self.host)
+            .field("database", &self.database)
+            .field("username", &self.username)
+            .field("password", &Secret::from_stored(self.password.clone()))
+            .field("port", &self.port)
+            .field("database_path", &self.database_path)
+            .field("database_name", &self.database_name)
+            .field("database_type", &self.database_type)
+            .field("file_options", &self.file_options)
+            .field("http_options", &self.http_options)
+            .finish()
+    }
 }
@@ -110,7 +110,7 @@ impl JobRepositoryTrait for JobRepository {
             r#"
             SELECT * FROM jobs 
             WHERE queue_name = ?1 AND status = 'pending' 
-                AND (scheduled_at IS NULL OR scheduled_at <= datetime('now'))
+                AND (scheduled_at IS NULL OR datetime(scheduled_at) <= datetime('now'))
             ORDER BY created_at ASC
             LIMIT 1
             "#
@@ -183,7 +183,7 @@ impl JobRepositoryTrait for JobRepository {
             r#"
             SELECT * FROM jobs 
             WHERE queue_name = ?1 AND status = 'pending'
-                AND (scheduled_at IS NULL OR scheduled_at <= datetime('now'))
+                AND (scheduled_at IS NULL OR datetime(scheduled_at) <= datetime('now'))
             ORDER BY created_at ASC
             LIMIT ?2
             "#
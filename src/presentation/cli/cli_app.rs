@@ -6,6 +6,7 @@ use crate::modules::queue::storage::repositories::job_repository::JobRepository;
 use crate::presentation::cli::commands::test_command;
 use crate::presentation::cli::commands::queue_command;
 use crate::presentation::cli::commands::index_command;
+use crate::presentation::cli::commands::credentials_command;
 use crate::presentation::cli::commands::queue::queue_list_command;
 use crate::queues::application::queue_service::JobService;
 use crate::state::AppState;
@@ -28,6 +29,8 @@ pub enum Commands {
     #[command(name = "queue:list")]
     QueueList(queue_list_command::QueueListCommand),
     Queue(queue_command::QueueCommand),
+    /// Data source credentials
+    Credentials(credentials_command::CredentialsCommand),
 }
 
 impl Commands {
@@ -38,6 +41,7 @@ impl Commands {
             Commands::Index(cmd) => cmd.execute(state).await,
             Commands::Queue(cmd) => cmd.execute(state).await,
             Commands::QueueList(cmd) => cmd.execute(state).await,
+            Commands::Credentials(cmd) => cmd.execute(state).await,
         }
     }
 }
@@ -0,0 +1,41 @@
+use crate::state::AppState;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use crate::application::use_cases::data_source::rotate_credentials_key_use_case::RotateCredentialsKeyUseCase;
+use crate::domain::data_source::services::credential_cipher::CredentialCipher;
+
+/// Переменная окружения с новым мастер-ключом; в аргументах ключ виден в списке процессов
+const NEW_MASTER_KEY_VAR: &str = "CREDENTIALS_NEW_MASTER_KEY";
+
+#[derive(Parser, Debug)]
+pub(crate) struct CredentialsCommand {
+    #[command(subcommand)]
+    pub action: CredentialsAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CredentialsAction {
+    /// Re-encrypt data source passwords from CREDENTIALS_MASTER_KEY to CREDENTIALS_NEW_MASTER_KEY
+    RotateKey,
+}
+
+impl CredentialsCommand {
+    pub async fn execute(&self, state: AppState) -> Result<()> {
+        match &self.action {
+            CredentialsAction::RotateKey => {
+                let new_key = std::env::var(NEW_MASTER_KEY_VAR)
+                    .map_err(|_| anyhow::anyhow!("{} not found in environment", NEW_MASTER_KEY_VAR))?;
+                let next = CredentialCipher::from_base64(&new_key).map_err(anyhow::Error::msg)?;
+                let current = CredentialCipher::installed().map_err(anyhow::Error::msg)?;
+
+                let updated = RotateCredentialsKeyUseCase::new((*state.database).clone())
+                    .execute(current, &next)
+                    .await?;
+                println!("{} {}", "🔑 Data source passwords re-encrypted:".green(), updated);
+                println!("Set CREDENTIALS_MASTER_KEY to the value of {} before restarting the server", NEW_MASTER_KEY_VAR);
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod test_command;
 pub(crate) mod index_command;
 pub mod queue_command;
+pub(crate) mod credentials_command;
 pub mod queue;
\ No newline at end of file
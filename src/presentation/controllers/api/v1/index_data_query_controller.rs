@@ -1,13 +1,33 @@
 use axum::debug_handler;
-use axum::extract::{Json, Query, State};
+use axum::extract::{Json, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use serde_json::json;
 use crate::application::use_cases::index_data_query::get_index_data_queries_use_case::{GetIndexDataQueriesUseCase};
+use crate::application::use_cases::index_data_query::enqueue_insert_data_index_data_query_use_case::EnqueueInsertDataIndexDataQueryUseCase;
 use crate::application::use_cases::index_data_query::insert_data_index_data_query_request_use_case::InsertDataIndexDataQueryRequestUseCase;
 use crate::application::use_cases::index_data_query::test_index_data_query_use_case::TestIndexDataQueryUseCase;
 use crate::application::use_cases::index_data_query::store_index_data_query_request_use_case::StoreIndexDataQueryRequestUseCase;
+use crate::application::use_cases::index_data_query::reset_cursor_index_data_query_use_case::ResetCursorIndexDataQueryUseCase;
+use crate::application::use_cases::index_data_query::reconcile_deletions_index_data_query_use_case::ReconcileDeletionsIndexDataQueryUseCase;
+use crate::application::use_cases::index_data_query::update_schedule_index_data_query_use_case::UpdateScheduleIndexDataQueryUseCase;
+use crate::presentation::requests::index_data_query::update_schedule_index_data_query_request::UpdateScheduleIndexDataQueryRequest;
+use crate::application::use_cases::index_data_query::update_transformation_index_data_query_use_case::UpdateTransformationIndexDataQueryUseCase;
+use crate::presentation::requests::index_data_query::update_transformation_index_data_query_request::UpdateTransformationIndexDataQueryRequest;
+use crate::application::use_cases::index_data_query::update_notify_channel_index_data_query_use_case::UpdateNotifyChannelIndexDataQueryUseCase;
+use crate::application::use_cases::index_data_query::notify_trigger_index_data_query_use_case::NotifyTriggerIndexDataQueryUseCase;
+use crate::presentation::requests::index_data_query::update_notify_channel_index_data_query_request::UpdateNotifyChannelIndexDataQueryRequest;
+use crate::presentation::requests::index_data_query::notify_trigger_index_data_query_request::NotifyTriggerIndexDataQueryRequest;
+use crate::application::use_cases::index_data_query::update_child_queries_index_data_query_use_case::UpdateChildQueriesIndexDataQueryUseCase;
+use crate::presentation::requests::index_data_query::update_child_queries_index_data_query_request::UpdateChildQueriesIndexDataQueryRequest;
+use crate::application::use_cases::index_data_query::get_sync_runs_index_data_query_use_case::GetSyncRunsIndexDataQueryUseCase;
+use crate::presentation::requests::index_data_query::get_sync_runs_index_data_query_request::GetSyncRunsIndexDataQueryRequest;
+use crate::domain::repository::sync_run_repository_trait::SyncRunRepositoryTrait;
+use crate::infrastructure::repositories::sync_run_repository::SyncRunRepository;
+use crate::application::use_cases::index_data_query::preview_index_data_query_use_case::PreviewIndexDataQueryUseCase;
+use crate::presentation::requests::index_data_query::preview_index_data_query_request::PreviewIndexDataQueryRequest;
 use crate::domain::data_source::entities::index_data_query::IndexDataQuery;
+use crate::domain::data_source::entities::sync_run::SyncTrigger;
 use crate::domain::repository::data_source_repository_trait::DataSourceRepositoryTrait;
 use crate::domain::repository::index_data_query_repository_trait::IndexDataQueryRepositoryTrait;
 use crate::requests::index_data_query::index_index_data_query_request::IndexIndexDataQueryRequest;
@@ -32,18 +52,19 @@ impl IndexDataQueryController {
     ) -> impl IntoResponse{
         let db = (*state.database).clone();
         let repository = DataSourceRepository::new(db);
-        let test_index_data_query_use_case = TestIndexDataQueryUseCase::new(repository);
+        let test_index_data_query_use_case = TestIndexDataQueryUseCase::new(repository, state.query_executor());
 
         let result = test_index_data_query_use_case.execute(&payload).await;
 
         match result {
-            Ok(json_value) => (
+            Ok((json_value, warnings)) => (
                 StatusCode::OK,
                 Json(json!({
                 "code": 200,
                 "success": true,
                 "message": "Query executed successfully",
                 "result": json_value, // Вместо format!("{:?}", result)
+                "warnings": warnings,
                 "data": format!("{:?}", payload.0)
             })),
             ),
@@ -60,6 +81,38 @@ impl IndexDataQueryController {
         }
     }
 
+    /// Первые строки запроса, схема колонок, оценка числа строк и время выполнения
+    pub async fn preview(
+        Query(payload): Query<PreviewIndexDataQueryRequest>,
+        State(state): State<AppState>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let use_case = PreviewIndexDataQueryUseCase::new(DataSourceRepository::new(db), state.query_executor());
+
+        match use_case.execute(&payload).await {
+            Ok(preview) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Query preview generated",
+                "result": preview,
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                "code": 422,
+                "success": false,
+                "message": error.to_string(),
+                "result": null,
+                "data": payload
+            })),
+            )
+        }
+    }
+
     pub async fn index(
         Query(params): Query<IndexIndexDataQueryRequest>,
         State(state): State<AppState>
@@ -128,27 +181,391 @@ impl IndexDataQueryController {
         }
     }
     
+    /// Ставит синхронизацию в очередь и сразу отвечает id задачи.
+    /// С `dry_run: true` синхронно сравнивает источник с индексом и возвращает отчёт.
     pub async fn insert_data(
         State(state): State<AppState>,
-        Json(payload): Json<InsertDataIndexDataQueryRequest>,
+        Json(mut payload): Json<InsertDataIndexDataQueryRequest>,
     )->impl IntoResponse {
-        println!("{:?}", payload);
+        // Источник запуска задаёт сервер: через API синхронизация всегда ручная
+        payload.trigger = SyncTrigger::Manual;
+        if payload.dry_run {
+            return Self::dry_run(state, payload).await;
+        }
+
+        let db = (*state.database).clone();
+        let repository = IndexDataQueryRepository::new(db);
+        let use_case = EnqueueInsertDataIndexDataQueryUseCase::new(repository, state.job_service.clone());
+
+        match use_case.execute(&payload).await {
+            Ok(job_id) => (
+                StatusCode::ACCEPTED,
+                Json(json!({
+                "code": 202,
+                "success": true,
+                "message": "Sync job enqueued",
+                "result": { "job_id": job_id },
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                "code": 500,
+                "success": false,
+                "message": error.to_string(),
+                "result": null,
+                "data": payload
+            })),
+            )
+        }
+    }
+
+    async fn dry_run(
+        state: AppState,
+        payload: InsertDataIndexDataQueryRequest,
+    ) -> (StatusCode, Json<serde_json::Value>) {
         let db = (*state.database).clone();
-        let meilisearch_client = (*state.meilisearch_client).clone();
-        let repository = IndexDataQueryRepository::new(db.clone());
-        let data_source_repository = DataSourceRepository::new(db.clone());
         let use_case = InsertDataIndexDataQueryRequestUseCase::new(
-            repository, 
-            data_source_repository,
-            meilisearch_client
+            IndexDataQueryRepository::new(db.clone()),
+            DataSourceRepository::new(db),
+            (*state.meilisearch_client).clone(),
+            state.config.sync_batch_size,
+            state.config.sync_flush_bytes,
+            state.task_throttle(),
+            state.query_executor(),
         ).await;
-        let _result = use_case.execute(&payload).await;
-        Json(json!({
-        "code": 200,
-        "success": true,
-        "message": "Data inserted successfully",
-        "data": payload
-    }))
 
+        match use_case.execute_dry_run(&payload).await {
+            Ok(report) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Dry run completed, nothing was written",
+                "result": report,
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                "code": 500,
+                "success": false,
+                "message": error.to_string(),
+                "result": null,
+                "data": payload
+            })),
+            )
+        }
+    }
+
+    pub async fn reset_cursor(
+        Path(id): Path<u32>,
+        State(state): State<AppState>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let repository = IndexDataQueryRepository::new(db);
+        let use_case = ResetCursorIndexDataQueryUseCase::new(repository);
+
+        match use_case.execute(id).await {
+            Ok(()) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Cursor reset, next sync will re-read all data",
+                "data": { "id": id }
+            })),
+            ),
+            Err(error) => (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                "code": 404,
+                "success": false,
+                "message": error.to_string(),
+                "data": { "id": id }
+            })),
+            )
+        }
+    }
+
+    pub async fn reconcile_deletions(
+        Path(id): Path<u32>,
+        State(state): State<AppState>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let meilisearch_client = (*state.meilisearch_client).clone();
+        let use_case = ReconcileDeletionsIndexDataQueryUseCase::new(
+            IndexDataQueryRepository::new(db.clone()),
+            DataSourceRepository::new(db),
+            meilisearch_client,
+            state.config.sync_batch_size,
+            state.query_executor(),
+        );
+
+        // Сверка по снимку ключей удалила бы документы, которые параллельная синхронизация добавляет в индекс
+        let lock = state.sync_locks.acquire(id).await;
+        let result = use_case.execute(id).await;
+        drop(lock);
+
+        match result {
+            Ok(report) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Deletions reconciled successfully",
+                "result": report,
+                "data": { "id": id }
+            })),
+            ),
+            Err(error) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                "code": 500,
+                "success": false,
+                "message": error.to_string(),
+                "result": null,
+                "data": { "id": id }
+            })),
+            )
+        }
+    }
+
+    pub async fn update_schedule(
+        Path(id): Path<u32>,
+        State(state): State<AppState>,
+        Json(payload): Json<UpdateScheduleIndexDataQueryRequest>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let repository = IndexDataQueryRepository::new(db);
+        let use_case = UpdateScheduleIndexDataQueryUseCase::new(repository, state.job_service.clone());
+
+        match use_case.execute(id, &payload).await {
+            Ok(next_run_at) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Schedule updated successfully",
+                "result": { "next_run_at": next_run_at },
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                "code": 422,
+                "success": false,
+                "message": error.to_string(),
+                "result": null,
+                "data": payload
+            })),
+            )
+        }
+    }
+
+    /// Ставит полную переиндексацию без простоя в очередь ReindexAll
+    pub async fn reindex(
+        Path(id): Path<u32>,
+        State(state): State<AppState>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let repository = IndexDataQueryRepository::new(db);
+        let use_case = EnqueueInsertDataIndexDataQueryUseCase::new(repository, state.job_service.clone());
+        let payload = InsertDataIndexDataQueryRequest {
+            id,
+            batch_size: None,
+            dry_run: false,
+            trigger: SyncTrigger::Reindex,
+            params: None,
+        };
+
+        match use_case.execute_reindex(&payload).await {
+            Ok(job_id) => (
+                StatusCode::ACCEPTED,
+                Json(json!({
+                "code": 202,
+                "success": true,
+                "message": "Reindex job enqueued",
+                "result": { "job_id": job_id },
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                "code": 500,
+                "success": false,
+                "message": error.to_string(),
+                "result": null,
+                "data": payload
+            })),
+            )
+        }
+    }
+
+    pub async fn update_transformation(
+        Path(id): Path<u32>,
+        State(state): State<AppState>,
+        Json(payload): Json<UpdateTransformationIndexDataQueryRequest>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let repository = IndexDataQueryRepository::new(db);
+        let use_case = UpdateTransformationIndexDataQueryUseCase::new(repository);
+
+        match use_case.execute(id, &payload).await {
+            Ok(()) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Transformation updated successfully",
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                "code": 404,
+                "success": false,
+                "message": error.to_string(),
+                "data": payload
+            })),
+            )
+        }
+    }
+
+    pub async fn update_notify_channel(
+        Path(id): Path<u32>,
+        State(state): State<AppState>,
+        Json(payload): Json<UpdateNotifyChannelIndexDataQueryRequest>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let repository = IndexDataQueryRepository::new(db);
+        let use_case = UpdateNotifyChannelIndexDataQueryUseCase::new(repository);
+
+        match use_case.execute(id, &payload).await {
+            Ok(()) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Notify channel updated successfully",
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                "code": 422,
+                "success": false,
+                "message": error.to_string(),
+                "data": payload
+            })),
+            )
+        }
+    }
+
+    pub async fn update_child_queries(
+        Path(id): Path<u32>,
+        State(state): State<AppState>,
+        Json(payload): Json<UpdateChildQueriesIndexDataQueryRequest>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let repository = IndexDataQueryRepository::new(db);
+        let use_case = UpdateChildQueriesIndexDataQueryUseCase::new(repository);
+
+        match use_case.execute(id, &payload).await {
+            Ok(()) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Child queries updated successfully",
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                "code": 422,
+                "success": false,
+                "message": error.to_string(),
+                "data": payload
+            })),
+            )
+        }
+    }
+
+    /// SQL триггера для таблицы-источника, отправляющего изменения в канал запроса
+    pub async fn notify_trigger(
+        Path(id): Path<u32>,
+        Query(payload): Query<NotifyTriggerIndexDataQueryRequest>,
+        State(state): State<AppState>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let repository = IndexDataQueryRepository::new(db);
+        let use_case = NotifyTriggerIndexDataQueryUseCase::new(repository);
+
+        match use_case.execute(id, &payload).await {
+            Ok(sql) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Trigger SQL generated",
+                "result": sql,
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                "code": 422,
+                "success": false,
+                "message": error.to_string(),
+                "result": null,
+                "data": payload
+            })),
+            )
+        }
+    }
+
+    /// История запусков синхронизации запроса
+    pub async fn runs(
+        Path(id): Path<u32>,
+        Query(payload): Query<GetSyncRunsIndexDataQueryRequest>,
+        State(state): State<AppState>,
+    ) -> impl IntoResponse {
+        let db = (*state.database).clone();
+        let use_case = GetSyncRunsIndexDataQueryUseCase::new(
+            IndexDataQueryRepository::new(db.clone()),
+            SyncRunRepository::new(db),
+        );
+
+        match use_case.execute(id, &payload).await {
+            Ok(runs) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Sync runs retrieved successfully",
+                "result": runs,
+                "data": payload
+            })),
+            ),
+            Err(error) => (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                "code": 404,
+                "success": false,
+                "message": error.to_string(),
+                "result": null,
+                "data": payload
+            })),
+            )
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,55 @@
+use axum::extract::{Json, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde_json::json;
+use crate::state::AppState;
+
+pub struct JobController {}
+
+impl JobController {
+    /// Статус фоновой задачи (например, синхронизации, поставленной через insert-data)
+    pub async fn show(
+        Path(id): Path<i32>,
+        State(state): State<AppState>,
+    ) -> impl IntoResponse {
+        match state.job_service.get_job_info(id).await {
+            Ok(Some(job)) => (
+                StatusCode::OK,
+                Json(json!({
+                "code": 200,
+                "success": true,
+                "message": "Job retrieved successfully",
+                "data": {
+                    "id": job.id,
+                    "queue_name": job.queue_name.to_string(),
+                    "status": job.status.to_string(),
+                    "attempts": job.attempts,
+                    "max_attempts": job.max_attempts,
+                    "created_at": job.created_at,
+                    "scheduled_at": job.scheduled_at,
+                    "started_at": job.started_at,
+                    "finished_at": job.finished_at,
+                }
+            })),
+            ),
+            Ok(None) => (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                "code": 404,
+                "success": false,
+                "message": format!("Job {} not found", id),
+                "data": null
+            })),
+            ),
+            Err(error) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                "code": 500,
+                "success": false,
+                "message": error.to_string(),
+                "data": null
+            })),
+            )
+        }
+    }
+}
@@ -4,4 +4,5 @@ pub mod ws_controller;
 
 pub mod data_source_controller;
 pub mod home_controller;
+pub mod job_controller;
 
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetSyncRunsIndexDataQueryRequest {
+    /// Сколько последних запусков вернуть (по умолчанию 20, не больше 500)
+    pub limit: Option<u32>,
+}
@@ -1,2 +1,10 @@
 pub mod store_index_data_query_request;
-pub mod get_index_data_query_request_dto;
\ No newline at end of file
+pub mod get_index_data_query_request_dto;
+pub mod update_schedule_index_data_query_request;
+pub mod update_transformation_index_data_query_request;
+pub mod update_notify_channel_index_data_query_request;
+pub mod notify_trigger_index_data_query_request;
+
+pub mod get_sync_runs_index_data_query_request;
+pub mod preview_index_data_query_request;
+pub mod update_child_queries_index_data_query_request;
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotifyTriggerIndexDataQueryRequest {
+    /// Таблица, на которую вешается триггер (`table` или `schema.table`)
+    pub table: String,
+    /// Колонка ключа в таблице; по умолчанию — первичный ключ запроса
+    pub key_column: Option<String>,
+}
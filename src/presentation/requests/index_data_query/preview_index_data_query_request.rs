@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PreviewIndexDataQueryRequest {
+    pub data_source_id: u32,
+    pub query: String,
+    /// Сколько строк вернуть (по умолчанию 10, не больше 1000)
+    pub limit: Option<u32>,
+    /// Значения параметров `:name` в виде JSON-объекта
+    pub params: Option<String>,
+    /// TransformationSpec в виде JSON-строки
+    pub transformation: Option<String>,
+    /// Добавлять к датам поле `<колонка>_epoch`
+    pub epoch_timestamps: Option<bool>,
+}
@@ -1,8 +1,23 @@
 use serde::{Deserialize, Serialize};
+use crate::domain::data_source::entities::child_query::ChildQuery;
+use crate::domain::data_source::entities::transformation_spec::TransformationSpec;
+use crate::domain::data_source::services::named_params::QueryParams;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StoreIndexDataQueryRequest {
     pub data_source_id: u32,
     pub index_uid: String,
     pub query: String,
+    pub cursor_column: Option<String>,
+    pub delete_orphans: Option<bool>,
+    pub max_delete_ratio: Option<f64>,
+    pub cron_expression: Option<String>,
+    pub timezone: Option<String>,
+    pub transformation: Option<TransformationSpec>,
+    pub primary_key: Option<String>,
+    pub primary_key_template: Option<String>,
+    pub notify_channel: Option<String>,
+    pub epoch_timestamps: Option<bool>,
+    pub params: Option<QueryParams>,
+    pub child_queries: Option<Vec<ChildQuery>>,
 }
\ No newline at end of file
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use crate::domain::data_source::entities::child_query::ChildQuery;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateChildQueriesIndexDataQueryRequest {
+    /// Пустой список убирает вложенные массивы из документов
+    #[serde(default)]
+    pub child_queries: Vec<ChildQuery>,
+}
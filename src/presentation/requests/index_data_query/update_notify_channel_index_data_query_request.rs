@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateNotifyChannelIndexDataQueryRequest {
+    /// Канал Postgres NOTIFY; null выключает прослушивание
+    pub notify_channel: Option<String>,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateScheduleIndexDataQueryRequest {
+    /// Cron-выражение; null или пустая строка отключают расписание
+    pub cron_expression: Option<String>,
+    pub timezone: Option<String>,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use crate::domain::data_source::entities::transformation_spec::TransformationSpec;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTransformationIndexDataQueryRequest {
+    /// null убирает преобразование: документы снова совпадают со строками запроса
+    pub transformation: Option<TransformationSpec>,
+}
@@ -168,6 +168,37 @@ impl JobService {
         }
     }
 
+    /// Задачи очереди, которые ещё ожидают запуска или выполняются
+    pub async fn list_active_jobs(&self, queue_name: &QueueName) -> Result<Vec<Job>> {
+        let mut jobs = self.job_repository.find_by_queue_and_status(queue_name, &JobStatus::Pending).await?;
+        jobs.extend(self.job_repository.find_by_queue_and_status(queue_name, &JobStatus::Running).await?);
+        Ok(jobs)
+    }
+
+    /// Провалить задачи, оставшиеся выполняющимися после остановки сервера: их уже никто не завершит,
+    /// а активная задача не даёт планировщику поставить следующий запуск того же запроса
+    pub async fn fail_interrupted_jobs(&self, queue_name: &QueueName) -> Result<usize> {
+        let jobs = self.job_repository.find_by_queue_and_status(queue_name, &JobStatus::Running).await?;
+        let count = jobs.len();
+
+        for job in jobs {
+            self.fail_job(job, "Interrupted by server restart".to_string()).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// Отменить задачу, если она ещё не начала выполняться
+    pub async fn cancel_job(&self, id: i32) -> Result<bool> {
+        match self.job_repository.find_by_id(id).await? {
+            Some(job) if job.status == JobStatus::Pending => {
+                info!("Cancelling pending job ID: {}", id);
+                self.job_repository.delete(id).await
+            }
+            _ => Ok(false),
+        }
+    }
+
     /// Получить статистику по очередям
     pub async fn get_queue_statistics(&self) -> Result<Vec<QueueStats>> {
         self.job_repository.get_queue_stats().await
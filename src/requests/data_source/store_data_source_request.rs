@@ -1,6 +1,10 @@
-use serde::Deserialize;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::domain::data_source::entities::file_source_options::FileSourceOptions;
+use crate::domain::data_source::entities::http_source_options::HttpSourceOptions;
+use crate::domain::data_source::entities::secret::Secret;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize)]
 pub struct StoreDataSourceRequest {
     pub name: String,
     pub host: String,
@@ -11,4 +15,29 @@ pub struct StoreDataSourceRequest {
     pub database_path: String,
     pub database_name: String,
     pub database_type: String,
-}
\ No newline at end of file
+    /// Настройки чтения для `database_type = "file"`; путь или glob-шаблон — в `database_path`
+    #[serde(default)]
+    pub file_options: Option<FileSourceOptions>,
+    /// Настройки для `database_type = "http"`; базовый URL — в `database_path`, bearer-токен — в `password`
+    #[serde(default)]
+    pub http_options: Option<HttpSourceOptions>,
+}
+
+/// Пароль или токен из запроса не попадает в лог
+impl fmt::Debug for StoreDataSourceRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreDataSourceRequest")
+            .field("name", &self.name)
+            .field("host", &self.host)
+            .field("database", &self.database)
+            .field("username", &self.username)
+            .field("password", &Secret::from_stored(self.password.clone()))
+            .field("port", &self.port)
+            .field("database_path", &self.database_path)
+            .field("database_name", &self.database_name)
+            .field("database_type", &self.database_type)
+            .field("file_options", &self.file_options)
+            .field("http_options", &self.http_options)
+            .finish()
+    }
+}
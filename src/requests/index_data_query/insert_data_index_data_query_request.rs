@@ -1,14 +1,27 @@
 use serde::{Deserialize, Serialize};
+use crate::domain::data_source::entities::sync_run::SyncTrigger;
+use crate::domain::data_source::services::named_params::QueryParams;
 use crate::requests::index_data_query::test_index_data_query_request::TestIndexDataQueryRequest;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 
 pub struct InsertDataIndexDataQueryRequest{
     pub id: u32,
+    /// Размер пачки документов; по умолчанию берётся SYNC_BATCH_SIZE из конфигурации
+    pub batch_size: Option<u32>,
+    /// Только сравнить источник с индексом и вернуть отчёт, ничего не записывая
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Источник запуска для истории синхронизаций; у запросов API контроллер всегда ставит Manual
+    #[serde(default)]
+    pub trigger: SyncTrigger,
+    /// Значения параметров `:name` для этого запуска поверх сохранённых в запросе
+    #[serde(default)]
+    pub params: Option<QueryParams>,
 }
 
 impl InsertDataIndexDataQueryRequest {
     pub fn serialise(&self)-> Result<String, serde_json::Error>  {
         serde_json::to_string(self)
     }
-}
\ No newline at end of file
+}
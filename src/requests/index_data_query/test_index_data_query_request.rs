@@ -5,6 +5,10 @@ pub struct TestIndexDataQueryRequest {
     pub uid: String,
     pub data_source_id: u32,
     pub query: String,
+    /// TransformationSpec в виде JSON-строки, чтобы превью совпадало с индексируемым документом
+    pub transformation: Option<String>,
+    /// Значения параметров `:name` в виде JSON-объекта
+    pub params: Option<String>,
 }
 
 impl TestIndexDataQueryRequest {
@@ -1,7 +1,14 @@
 use std::sync::Arc;
+use std::path::PathBuf;
 use meilisearch_sdk::client::Client;
 use crate::config::application::ApplicationConfig;
 use crate::database::Database;
+use crate::domain::data_source::services::connection_registry::ConnectionRegistry;
+use crate::domain::data_source::services::connector_registry::ConnectorRegistry;
+use crate::domain::data_source::services::credential_cipher::CredentialCipher;
+use crate::domain::data_source::services::query_executor::QueryExecutor;
+use crate::domain::data_source::services::task_throttle::TaskThrottleSettings;
+use crate::application::workers::sync_lock::SyncLocks;
 use crate::queues::application::queue_service::JobService;
 use crate::modules::queue::storage::repositories::job_repository::JobRepository as ModuleJobRepository;
 use crate::queues::infrastructure::repositories::job_repository_adapter::{JobRepositoryAdapter, FailedJobRepositoryStub};
@@ -12,6 +19,11 @@ pub struct AppState {
     pub meilisearch_client: Arc<Client>,
     pub database: Arc<Database>,
     pub job_service: Arc<JobService>,
+    pub connections: Arc<ConnectionRegistry>,
+    /// Коннекторы по типам источников данных
+    pub connectors: Arc<ConnectorRegistry>,
+    /// Один запуск записи в индекс на запрос одновременно
+    pub sync_locks: Arc<SyncLocks>,
 }
 
 impl AppState {
@@ -19,6 +31,11 @@ impl AppState {
         config: ApplicationConfig,
         database: Database,
     ) -> Result< Self, anyhow::Error> {
+        // Пароли источников расшифровываются этим ключом при подключении
+        CredentialCipher::install(
+            CredentialCipher::from_base64(&config.credentials_master_key).map_err(anyhow::Error::msg)?,
+        );
+
         let meilisearch_client = Arc::new(
             Client::new(
                 config.get_meilisearch_url(),
@@ -27,12 +44,16 @@ impl AppState {
         );
 
         let job_service = Self::get_job_service(database.clone()).await;
+        let connections = Arc::new(Self::get_connections(&config));
 
         Ok(Self {
             config: Arc::new(config),
             meilisearch_client,
             database: Arc::new(database),
             job_service,
+            connections,
+            connectors: Arc::new(ConnectorRegistry::with_builtin()),
+            sync_locks: Arc::new(SyncLocks::new()),
         })
 
     }
@@ -50,13 +71,16 @@ impl AppState {
         // Создаем временную базу данных в памяти для тестов
         let temp_db = Database::new(":memory:").await?;
         let job_service = Self::get_job_service(temp_db.clone()).await;
-
+        let connections = Arc::new(Self::get_connections(&config));
 
         Ok(Self {
             config: Arc::new(config),
             meilisearch_client,
             database: Arc::new(temp_db),
             job_service,
+            connections,
+            connectors: Arc::new(ConnectorRegistry::with_builtin()),
+            sync_locks: Arc::new(SyncLocks::new()),
         })
     }
     
@@ -73,5 +97,26 @@ impl AppState {
         job_service
     }
 
+    pub fn get_connections(config: &ApplicationConfig) -> ConnectionRegistry {
+        ConnectionRegistry::new(config.pool_max_connections, config.pool_idle_timeout())
+    }
+
+    /// Пороги обратного давления на очередь задач Meilisearch
+    pub fn task_throttle(&self) -> TaskThrottleSettings {
+        TaskThrottleSettings {
+            watermark: self.config.sync_task_watermark,
+            max_in_flight: self.config.sync_max_in_flight_tasks,
+        }
+    }
+
+    /// Исполнитель запросов к источникам с общими пулами и ограничением времени из конфигурации
+    pub fn query_executor(&self) -> QueryExecutor {
+        QueryExecutor::new()
+            .with_statement_timeout(self.config.query_timeout())
+            .with_file_root(PathBuf::from(&self.config.file_sources_root))
+            .with_connections(self.connections.clone())
+            .with_connectors(self.connectors.clone())
+    }
+
 
 }